@@ -1,4 +1,4 @@
-use crate::{gui::AssetItemMessage, load_image, preview::PreviewPanel, GameEngine};
+use crate::{gui::AssetItemMessage, load_image, preview::PreviewPanel, GameEngine, Message};
 use rg3d::gui::message::UiMessage;
 use rg3d::gui::widget::Widget;
 use rg3d::gui::{BuildContext, UiNode, UserInterface};
@@ -13,8 +13,8 @@ use rg3d::{
         grid::{Column, GridBuilder, Row},
         image::ImageBuilder,
         message::{
-            FileBrowserMessage, MessageDirection, ScrollViewerMessage, TextMessage, UiMessageData,
-            WidgetMessage,
+            FileBrowserMessage, MessageDirection, MouseButton, ScrollViewerMessage, TextMessage,
+            UiMessageData, WidgetMessage,
         },
         scroll_viewer::ScrollViewerBuilder,
         text::TextBuilder,
@@ -29,6 +29,7 @@ use std::{
     ffi::OsStr,
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
+    sync::mpsc::Sender,
 };
 
 #[derive(Debug, Clone)]
@@ -222,10 +223,11 @@ pub struct AssetBrowser {
     preview: PreviewPanel,
     items: Vec<Handle<UiNode>>,
     item_to_select: Option<PathBuf>,
+    sender: Sender<Message>,
 }
 
 impl AssetBrowser {
-    pub fn new(engine: &mut GameEngine) -> Self {
+    pub fn new(engine: &mut GameEngine, sender: Sender<Message>) -> Self {
         let preview = PreviewPanel::new(engine, 250, 250);
         let mut ctx = engine.user_interface.build_ctx();
 
@@ -319,6 +321,7 @@ impl AssetBrowser {
             selected_properties,
             items: Default::default(),
             item_to_select: None,
+            sender,
         }
     }
 
@@ -373,6 +376,25 @@ impl AssetBrowser {
                     }
                 }
             }
+            UiMessageData::Widget(WidgetMessage::DoubleClick {
+                button: MouseButton::Left,
+            }) => {
+                if let Some(item) = self
+                    .items
+                    .iter()
+                    .find(|&&i| {
+                        message.destination() == i || ui.is_node_child_of(message.destination(), i)
+                    })
+                    .and_then(|&i| ui.node(i).cast::<AssetItem>())
+                {
+                    self.sender
+                        .send(Message::OpenAsset {
+                            path: item.path.clone(),
+                            kind: item.kind,
+                        })
+                        .unwrap();
+                }
+            }
             UiMessageData::FileBrowser(FileBrowserMessage::Path(path))
                 if message.destination() == self.folder_browser
                     && message.direction() == MessageDirection::FromWidget =>
@@ -0,0 +1,259 @@
+//! Bounding volume hierarchies of mesh surfaces, they are used to speed up precise picking
+//! of heavy meshes. Trees are built lazily on first test of a surface and cached until its
+//! geometry changes.
+
+use rg3d::{
+    core::{
+        algebra::Vector3,
+        math::{aabb::AxisAlignedBoundingBox, ray::Ray},
+    },
+    scene::mesh::{
+        buffer::{VertexAttributeUsage, VertexReadTrait},
+        surface::{Surface, SurfaceData},
+    },
+};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, RwLock, Weak},
+};
+
+/// Maximum amount of triangles in a leaf. Smaller leaves make the tree deeper, bigger ones
+/// make leaves slower to test.
+const LEAF_SIZE: usize = 4;
+
+enum BvhNode {
+    Leaf {
+        bounds: AxisAlignedBoundingBox,
+        first: usize,
+        count: usize,
+    },
+    Branch {
+        bounds: AxisAlignedBoundingBox,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &AxisAlignedBoundingBox {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+type Triangle = [Vector3<f32>; 3];
+
+fn centroid(triangle: &Triangle) -> Vector3<f32> {
+    (triangle[0] + triangle[1] + triangle[2]).scale(1.0 / 3.0)
+}
+
+fn contains_point(bounds: &AxisAlignedBoundingBox, point: Vector3<f32>) -> bool {
+    (0..3).all(|axis| point[axis] >= bounds.min[axis] && point[axis] <= bounds.max[axis])
+}
+
+/// Splits triangles by median of their centers along the longest axis of their bounds until
+/// leaves are small enough. Returns index of the created node.
+fn build(nodes: &mut Vec<BvhNode>, triangles: &mut [Triangle], first: usize) -> usize {
+    let mut bounds = AxisAlignedBoundingBox::default();
+    for triangle in triangles.iter() {
+        for &vertex in triangle {
+            bounds.add_point(vertex);
+        }
+    }
+
+    let index = nodes.len();
+    nodes.push(BvhNode::Leaf {
+        bounds,
+        first,
+        count: triangles.len(),
+    });
+
+    if triangles.len() > LEAF_SIZE {
+        let size = bounds.max - bounds.min;
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+
+        let middle = triangles.len() / 2;
+        triangles.select_nth_unstable_by(middle, |a, b| {
+            centroid(a)[axis]
+                .partial_cmp(&centroid(b)[axis])
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let (left_triangles, right_triangles) = triangles.split_at_mut(middle);
+        let left = build(nodes, left_triangles, first);
+        let right = build(nodes, right_triangles, first + middle);
+        nodes[index] = BvhNode::Branch {
+            bounds,
+            left,
+            right,
+        };
+    }
+
+    index
+}
+
+/// Bounding volume hierarchy of triangles of a single surface in object space.
+pub struct SurfaceBvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<Triangle>,
+}
+
+impl SurfaceBvh {
+    pub fn new(data: &SurfaceData) -> Self {
+        let vertex = |index: u32| {
+            data.vertex_buffer
+                .get(index as usize)
+                .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
+        };
+        let mut triangles = data
+            .geometry_buffer
+            .triangles_ref()
+            .iter()
+            .filter_map(|t| Some([vertex(t[0])?, vertex(t[1])?, vertex(t[2])?]))
+            .collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+        if !triangles.is_empty() {
+            build(&mut nodes, &mut triangles, 0);
+        }
+
+        Self { nodes, triangles }
+    }
+
+    /// Returns distance from the origin of the ray to the closest intersection point, the
+    /// point itself and the normal of the hit triangle.
+    pub fn intersect(&self, ray: &Ray) -> Option<(f32, Vector3<f32>, Vector3<f32>)> {
+        let mut closest: Option<(f32, Vector3<f32>, Vector3<f32>)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            let bounds = node.bounds();
+            let near = if contains_point(bounds, ray.origin) {
+                0.0
+            } else if let Some(points) = ray.aabb_intersection_points(bounds) {
+                points[0]
+                    .metric_distance(&ray.origin)
+                    .min(points[1].metric_distance(&ray.origin))
+            } else {
+                continue;
+            };
+            // Node is behind a triangle that was hit already.
+            if closest.map_or(false, |(d, _, _)| near > d) {
+                continue;
+            }
+
+            match *node {
+                BvhNode::Leaf { first, count, .. } => {
+                    for [a, b, c] in &self.triangles[first..first + count] {
+                        if let Some(point) = ray.triangle_intersection(&[*a, *b, *c]) {
+                            let distance = point.metric_distance(&ray.origin);
+                            if closest.map_or(true, |(d, _, _)| distance < d) {
+                                closest = Some((distance, point, (b - a).cross(&(c - a))));
+                            }
+                        }
+                    }
+                }
+                BvhNode::Branch { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        closest
+    }
+}
+
+struct CacheEntry {
+    // Weak reference keeps allocation of the data alive, so its address can't be reused
+    // by other surface data while the entry exists.
+    data: Weak<RwLock<SurfaceData>>,
+    geometry_hash: u64,
+    bvh: SurfaceBvh,
+}
+
+/// Hashes positions of vertices and indices of triangles, so any change of the geometry that
+/// affects picking gives a different hash. It is much cheaper than rebuilding a tree.
+fn geometry_hash(data: &SurfaceData) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for index in 0..data.vertex_buffer.vertex_count() as usize {
+        if let Some(position) = data
+            .vertex_buffer
+            .get(index)
+            .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
+        {
+            for coordinate in position.iter() {
+                coordinate.to_bits().hash(&mut hasher);
+            }
+        }
+    }
+    for triangle in data.geometry_buffer.triangles_ref() {
+        (triangle[0], triangle[1], triangle[2]).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Trees of surfaces keyed by their surface data. Surface data is shared between instances
+/// of the same model, so they share a tree too. A tree is rebuilt when the geometry of the
+/// data changes.
+#[derive(Default)]
+pub struct BvhCache {
+    entries: HashMap<usize, CacheEntry>,
+}
+
+impl BvhCache {
+    /// Returns closest intersection point of the ray with triangles of given surfaces and the
+    /// normal of the hit triangle. The ray and the result are in object space.
+    pub fn intersect(
+        &mut self,
+        ray: &Ray,
+        surfaces: &[Surface],
+    ) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let mut closest: Option<(f32, Vector3<f32>, Vector3<f32>)> = None;
+        for surface in surfaces {
+            let data = surface.data();
+            let key = Arc::as_ptr(&data) as usize;
+            let guard = data.read().unwrap();
+            let geometry_hash = geometry_hash(&guard);
+
+            let up_to_date = self
+                .entries
+                .get(&key)
+                .map_or(false, |entry| entry.geometry_hash == geometry_hash);
+            if !up_to_date {
+                // Good moment to forget trees of removed surfaces.
+                self.entries
+                    .retain(|_, entry| entry.data.strong_count() > 0);
+                self.entries.insert(
+                    key,
+                    CacheEntry {
+                        data: Arc::downgrade(&data),
+                        geometry_hash,
+                        bvh: SurfaceBvh::new(&guard),
+                    },
+                );
+            }
+
+            if let Some((distance, point, normal)) = self.entries[&key].bvh.intersect(ray) {
+                if closest.map_or(true, |(d, _, _)| distance < d) {
+                    closest = Some((distance, point, normal));
+                }
+            }
+        }
+        closest.map(|(_, point, normal)| (point, normal))
+    }
+}
@@ -1,32 +1,221 @@
+use crate::bvh::BvhCache;
+use crate::gamepad::GamepadState;
 use crate::rg3d::core::math::Matrix4Ext;
+use crate::settings::{
+    bookmarks::CameraBookmark,
+    camera::{CameraSettings, DragAxisLock, OrbitStyle},
+};
+use crate::Message;
 use rg3d::core::algebra::Matrix4;
-use rg3d::core::math::plane::Plane;
+use rg3d::core::math::{plane::Plane, ray::Ray, Rect};
 use rg3d::{
     core::{
-        algebra::{Point3, UnitQuaternion, Vector2, Vector3},
+        algebra::{Point3, Quaternion, UnitQuaternion, Vector2, Vector3},
+        arrayvec::ArrayVec,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
     },
     gui::message::{KeyCode, MouseButton},
     scene::{
-        base::BaseBuilder, camera::CameraBuilder, graph::Graph, node::Node,
+        base::BaseBuilder,
+        camera::CameraBuilder,
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::Surface,
+        },
+        node::Node,
+        terrain::TerrainRayCastResult,
         transform::TransformBuilder,
     },
 };
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
 };
 
+/// Returns closest intersection point of the ray with triangles of given surfaces and the
+/// normal of the hit triangle. The ray and the result are in object space. It tests every
+/// triangle, so it is used only as a reference for [`BvhCache`] timings.
+fn surfaces_intersection(ray: &Ray, surfaces: &[Surface]) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    let mut closest: Option<(f32, Vector3<f32>, Vector3<f32>)> = None;
+    for surface in surfaces {
+        let data = surface.data();
+        let data = data.read().unwrap();
+        let vertex = |index: u32| {
+            data.vertex_buffer
+                .get(index as usize)
+                .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
+        };
+        for triangle in data.geometry_buffer.triangles_ref() {
+            if let (Some(a), Some(b), Some(c)) = (
+                vertex(triangle[0]),
+                vertex(triangle[1]),
+                vertex(triangle[2]),
+            ) {
+                if let Some(point) = ray.triangle_intersection(&[a, b, c]) {
+                    let distance = point.metric_distance(&ray.origin);
+                    if closest.map_or(true, |(d, _, _)| distance < d) {
+                        closest = Some((distance, point, (b - a).cross(&(c - a))));
+                    }
+                }
+            }
+        }
+    }
+    closest.map(|(_, point, normal)| (point, normal))
+}
+
+/// Returns normal of the side of the bounding box the point lies on.
+fn aabb_side_normal(aabb: &AxisAlignedBoundingBox, point: Vector3<f32>) -> Vector3<f32> {
+    let half_extents = aabb.half_extents();
+    let local = point - aabb.center();
+    let mut normal = Vector3::default();
+    let mut max = f32::MIN;
+    for axis in 0..3 {
+        let k = local[axis].abs() / half_extents[axis].max(f32::EPSILON);
+        if k > max {
+            max = k;
+            normal = Vector3::default();
+            normal[axis] = local[axis].signum();
+        }
+    }
+    normal
+}
+
+/// Predefined view directions, camera looks along (or against) world axes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ViewPreset {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl ViewPreset {
+    /// Returns yaw and pitch (in radians) of the view.
+    fn angles(self) -> (f32, f32) {
+        let half_pi = std::f32::consts::FRAC_PI_2;
+        match self {
+            ViewPreset::Front => (0.0, 0.0),
+            ViewPreset::Back => (std::f32::consts::PI, 0.0),
+            ViewPreset::Left => (half_pi, 0.0),
+            ViewPreset::Right => (-half_pi, 0.0),
+            ViewPreset::Top => (0.0, half_pi),
+            ViewPreset::Bottom => (0.0, -half_pi),
+        }
+    }
+}
+
+/// Movement of the camera to a bookmark.
+struct BookmarkTransition {
+    from: CameraBookmark,
+    to: CameraBookmark,
+    elapsed: f32,
+    duration: f32,
+}
+
+fn bookmark_rotation(bookmark: &CameraBookmark) -> UnitQuaternion<f32> {
+    let [x, y, z, w] = bookmark.rotation;
+    UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z))
+}
+
+/// Interpolates between two views, yaw goes the shortest way around.
+fn interpolate_bookmarks(from: &CameraBookmark, to: &CameraBookmark, k: f32) -> CameraBookmark {
+    let lerp = |a: [f32; 3], b: [f32; 3]| -> [f32; 3] {
+        let a = Vector3::from(a);
+        a.lerp(&Vector3::from(b), k).into()
+    };
+
+    let pi = std::f32::consts::PI;
+    let yaw_delta = (to.yaw - from.yaw + pi).rem_euclid(2.0 * pi) - pi;
+
+    let rotation = bookmark_rotation(from)
+        .try_slerp(&bookmark_rotation(to), k, f32::EPSILON)
+        .unwrap_or_else(|| bookmark_rotation(to))
+        .coords;
+
+    CameraBookmark {
+        name: to.name.clone(),
+        pivot_position: lerp(from.pivot_position, to.pivot_position),
+        camera_offset: lerp(from.camera_offset, to.camera_offset),
+        yaw: from.yaw + yaw_delta * k,
+        pitch: from.pitch + (to.pitch - from.pitch) * k,
+        rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+    }
+}
+
+/// Scene camera the viewport is rendered through instead of the editor camera, along with
+/// the state that is restored when looking through it ends.
+struct LookThrough {
+    camera: Handle<Node>,
+    was_enabled: bool,
+    // Other scene cameras that were enabled, they are disabled so only one view is rendered.
+    disabled_cameras: Vec<Handle<Node>>,
+    pivot_position: Vector3<f32>,
+    pivot_rotation: UnitQuaternion<f32>,
+    camera_position: Vector3<f32>,
+    camera_rotation: UnitQuaternion<f32>,
+    yaw: f32,
+    pitch: f32,
+    rotation: UnitQuaternion<f32>,
+}
+
+/// Range of fly speed (units per second) that can be set with mouse wheel.
+const MIN_FLY_SPEED: f32 = 0.1;
+const MAX_FLY_SPEED: f32 = 1000.0;
+/// Fly speed multiplier per one step of mouse wheel.
+const FLY_SPEED_WHEEL_STEP: f32 = 1.2;
+
+/// Amount of "mouse pixels" per second that fully deflected gamepad stick turns the camera by.
+const GAMEPAD_LOOK_SPEED: f32 = 300.0;
+
 pub struct CameraController {
     pub pivot: Handle<Node>,
     pub camera: Handle<Node>,
     yaw: f32,
     pitch: f32,
+    // Used only by trackball orbit style.
+    rotation: UnitQuaternion<f32>,
+    // Orientation that is actually applied, it follows the values above when smoothing
+    // is enabled.
+    smoothed_yaw: f32,
+    smoothed_pitch: f32,
+    smoothed_rotation: UnitQuaternion<f32>,
+    // Current fly velocity in units per second.
+    velocity: Vector3<f32>,
+    gamepad: GamepadState,
+    orbit_style: OrbitStyle,
+    precise_picking: bool,
+    log_picking_time: bool,
+    bvh_cache: BvhCache,
+    sender: Sender<Message>,
+    rotate_sensitivity: f32,
+    drag_sensitivity: f32,
+    speed: f32,
+    // Multiplier of base fly speed adjusted with mouse wheel while flying.
+    fly_speed_scale: f32,
+    scroll_adjusts_speed: bool,
+    fast_speed_multiplier: f32,
+    slow_speed_multiplier: f32,
+    zoom_to_cursor: bool,
+    invert_x: bool,
+    invert_y: bool,
     rotate: bool,
     drag_side: f32,
     drag_up: f32,
     drag: bool,
+    // Mouse movement since the drag has started, its dominant axis is used by axis lock.
+    drag_accumulated: Vector2<f32>,
+    drag_axis_lock: DragAxisLock,
+    axis_lock_active: bool,
+    bookmark_transition: Option<BookmarkTransition>,
+    // World-space point the camera orbits around while Alt + left mouse button drag is active.
+    orbit_point: Option<Vector3<f32>>,
+    look_through: Option<LookThrough>,
     move_left: bool,
     move_right: bool,
     move_forward: bool,
@@ -42,6 +231,9 @@ pub struct CameraController {
 #[derive(Clone)]
 pub struct CameraPickResult {
     pub position: Vector3<f32>,
+    /// World-space normal of the surface at the hit point. It is the normal of the bounding
+    /// box side for nodes that were tested with bounding box only.
+    pub normal: Vector3<f32>,
     pub node: Handle<Node>,
     pub toi: f32,
 }
@@ -55,7 +247,7 @@ struct PickContext {
 }
 
 impl CameraController {
-    pub fn new(graph: &mut Graph, root: Handle<Node>) -> Self {
+    pub fn new(graph: &mut Graph, root: Handle<Node>, sender: Sender<Message>) -> Self {
         let camera;
         let pivot = BaseBuilder::new()
             .with_children(&[{
@@ -79,10 +271,37 @@ impl CameraController {
             camera,
             yaw: 0.0,
             pitch: 0.0,
+            rotation: UnitQuaternion::identity(),
+            smoothed_yaw: 0.0,
+            smoothed_pitch: 0.0,
+            smoothed_rotation: UnitQuaternion::identity(),
+            velocity: Vector3::default(),
+            gamepad: Default::default(),
+            orbit_style: OrbitStyle::Turntable,
+            precise_picking: false,
+            log_picking_time: false,
+            bvh_cache: Default::default(),
+            sender,
+            rotate_sensitivity: 0.01,
+            drag_sensitivity: 0.01,
+            speed: 10.0,
+            fly_speed_scale: 1.0,
+            scroll_adjusts_speed: true,
+            fast_speed_multiplier: 2.0,
+            slow_speed_multiplier: 0.25,
+            zoom_to_cursor: false,
+            invert_x: false,
+            invert_y: false,
             rotate: false,
             drag_side: 0.0,
             drag_up: 0.0,
             drag: false,
+            drag_accumulated: Vector2::default(),
+            drag_axis_lock: DragAxisLock::Shift,
+            axis_lock_active: false,
+            bookmark_transition: None,
+            orbit_point: None,
+            look_through: None,
             move_left: false,
             move_right: false,
             move_forward: false,
@@ -97,35 +316,123 @@ impl CameraController {
     }
 
     pub fn on_mouse_move(&mut self, delta: Vector2<f32>) {
-        if self.rotate {
-            self.yaw -= delta.x as f32 * 0.01;
-            self.pitch += delta.y as f32 * 0.01;
-            if self.pitch > 90.0f32.to_radians() {
-                self.pitch = 90.0f32.to_radians();
-            }
-            if self.pitch < -90.0f32.to_radians() {
-                self.pitch = -90.0f32.to_radians();
-            }
+        if self.rotate || self.orbit_point.is_some() {
+            self.rotate_view(delta);
         }
 
         if self.drag {
-            self.drag_side -= delta.x * 0.01;
-            self.drag_up -= delta.y * 0.01;
+            self.drag_accumulated += delta;
+
+            let mut delta = delta;
+            if self.axis_lock_active {
+                // Compare whole drag instead of single move, so small jitter does not
+                // switch the axis.
+                if self.drag_accumulated.x.abs() >= self.drag_accumulated.y.abs() {
+                    delta.y = 0.0;
+                } else {
+                    delta.x = 0.0;
+                }
+            }
+
+            self.drag_side -= delta.x * self.drag_sensitivity;
+            self.drag_up -= delta.y * self.drag_sensitivity;
         }
     }
 
-    pub fn on_mouse_wheel(&mut self, delta: f32, graph: &mut Graph) {
-        let camera = &mut graph[self.camera];
+    /// Sets current state of a gamepad, it will be applied on next update.
+    pub fn on_gamepad_input(&mut self, state: GamepadState) {
+        self.gamepad = state;
+    }
+
+    /// Rotates the view by given amount of mouse movement (in pixels).
+    fn rotate_view(&mut self, delta: Vector2<f32>) {
+        let mut delta = delta;
+        if self.invert_x {
+            delta.x = -delta.x;
+        }
+        if self.invert_y {
+            delta.y = -delta.y;
+        }
+
+        match self.orbit_style {
+            OrbitStyle::Turntable => {
+                self.yaw -= delta.x as f32 * self.rotate_sensitivity;
+                self.pitch += delta.y as f32 * self.rotate_sensitivity;
+                if self.pitch > 90.0f32.to_radians() {
+                    self.pitch = 90.0f32.to_radians();
+                }
+                if self.pitch < -90.0f32.to_radians() {
+                    self.pitch = -90.0f32.to_radians();
+                }
+            }
+            OrbitStyle::Trackball => {
+                // Rotate around local axes of the camera.
+                let yaw = -delta.x * self.rotate_sensitivity;
+                let pitch = delta.y * self.rotate_sensitivity;
+                self.rotation = self.rotation
+                    * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+                    * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch);
+                self.rotation.renormalize();
+            }
+        }
+    }
 
-        let look = camera.global_transform().look();
+    /// Returns base fly speed with mouse wheel adjustment applied.
+    pub fn fly_speed(&self) -> f32 {
+        self.speed * self.fly_speed_scale
+    }
+
+    /// Dollies the camera. In zoom-to-cursor mode camera moves along the ray through the
+    /// cursor, so the point under the cursor stays in place. While flying (right mouse button
+    /// is held) the wheel can change fly speed instead, new speed is returned then.
+    pub fn on_mouse_wheel(
+        &mut self,
+        delta: f32,
+        cursor_pos: Vector2<f32>,
+        screen_size: Vector2<f32>,
+        graph: &mut Graph,
+    ) -> Option<f32> {
+        if self.rotate && self.scroll_adjusts_speed {
+            if self.speed > 0.0 {
+                let speed = (self.fly_speed() * FLY_SPEED_WHEEL_STEP.powf(delta.signum()))
+                    .clamp(MIN_FLY_SPEED, MAX_FLY_SPEED);
+                self.fly_speed_scale = speed / self.speed;
+            }
+            return Some(self.fly_speed());
+        }
+
+        let direction = match &graph[self.camera] {
+            Node::Camera(camera) if self.zoom_to_cursor => camera
+                .make_ray(cursor_pos, screen_size)
+                .dir
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(|| camera.global_transform().look()),
+            camera => camera.global_transform().look(),
+        };
 
         if let Node::Base(pivot) = &mut graph[self.pivot] {
-            pivot.local_transform_mut().offset(look.scale(delta));
+            pivot.local_transform_mut().offset(direction.scale(delta));
         }
+
+        None
+    }
+
+    /// Starts orbiting around given world-space point, the camera keeps its distance to the
+    /// point while rotated. Orbiting lasts until left mouse button is released.
+    pub fn begin_orbit(&mut self, point: Vector3<f32>) {
+        self.bookmark_transition = None;
+        self.orbit_point = Some(point);
+    }
+
+    pub fn is_orbiting(&self) -> bool {
+        self.orbit_point.is_some()
     }
 
     pub fn on_mouse_button_up(&mut self, button: MouseButton) {
         match button {
+            MouseButton::Left => {
+                self.orbit_point = None;
+            }
             MouseButton::Right => {
                 self.rotate = false;
             }
@@ -137,18 +444,28 @@ impl CameraController {
     }
 
     pub fn on_mouse_button_down(&mut self, button: MouseButton) {
+        // User takes control over the camera.
+        if matches!(button, MouseButton::Right | MouseButton::Middle) {
+            self.bookmark_transition = None;
+        }
+
         match button {
             MouseButton::Right => {
                 self.rotate = true;
             }
             MouseButton::Middle => {
                 self.drag = true;
+                self.drag_accumulated = Vector2::default();
             }
             _ => (),
         }
     }
 
     pub fn on_key_up(&mut self, key: KeyCode) {
+        if self.drag_axis_lock.is_modifier(key) {
+            self.axis_lock_active = false;
+        }
+
         match key {
             KeyCode::W => self.move_forward = false,
             KeyCode::S => self.move_backward = false,
@@ -162,6 +479,10 @@ impl CameraController {
     }
 
     pub fn on_key_down(&mut self, key: KeyCode) {
+        if self.drag_axis_lock.is_modifier(key) {
+            self.axis_lock_active = true;
+        }
+
         match key {
             KeyCode::W => self.move_forward = true,
             KeyCode::S => self.move_backward = true,
@@ -169,16 +490,82 @@ impl CameraController {
             KeyCode::D => self.move_right = true,
             KeyCode::Space | KeyCode::Q => self.move_up = true,
             KeyCode::E => self.move_down = true,
-            KeyCode::LControl => self.speed_factor = 2.0,
-            KeyCode::LShift => self.speed_factor = 0.25,
+            KeyCode::LControl => self.speed_factor = self.fast_speed_multiplier,
+            KeyCode::LShift => self.speed_factor = self.slow_speed_multiplier,
             _ => (),
         }
     }
 
-    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
-        let camera = &mut graph[self.camera];
+    fn set_orbit_style(&mut self, orbit_style: OrbitStyle) {
+        if self.orbit_style == orbit_style {
+            return;
+        }
+
+        // Convert current orientation to keep the view unchanged after switching.
+        match orbit_style {
+            OrbitStyle::Trackball => {
+                self.rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw)
+                    * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch);
+            }
+            OrbitStyle::Turntable => {
+                let look = self.rotation * Vector3::z();
+                self.yaw = look.x.atan2(look.z);
+                self.pitch = (-look.y).clamp(-1.0, 1.0).asin();
+            }
+        }
+        self.smoothed_yaw = self.yaw;
+        self.smoothed_pitch = self.pitch;
+        self.smoothed_rotation = self.rotation;
+
+        self.orbit_style = orbit_style;
+    }
+
+    pub fn update(
+        &mut self,
+        graph: &mut Graph,
+        root: Handle<Node>,
+        settings: &CameraSettings,
+        dt: f32,
+    ) {
+        self.set_orbit_style(settings.orbit_style);
+        self.precise_picking = settings.precise_picking;
+        self.log_picking_time = settings.log_picking_time;
+        self.rotate_sensitivity = settings.rotate_sensitivity;
+        self.drag_sensitivity = settings.drag_sensitivity;
+        self.speed = settings.speed;
+        self.scroll_adjusts_speed = settings.scroll_adjusts_fly_speed;
+        self.fast_speed_multiplier = settings.fast_speed_multiplier;
+        self.slow_speed_multiplier = settings.slow_speed_multiplier;
+        self.zoom_to_cursor = settings.zoom_to_cursor;
+        self.invert_x = settings.invert_x;
+        self.invert_y = settings.invert_y;
+        if self.drag_axis_lock != settings.drag_axis_lock {
+            self.drag_axis_lock = settings.drag_axis_lock;
+            self.axis_lock_active = false;
+        }
+
+        if let Some(look_through) = self.look_through.as_ref() {
+            if graph.is_valid_handle(look_through.camera) {
+                // Editor camera is not rendered, so it must stay where it was.
+                return;
+            }
+            // Scene camera was deleted, there is nothing to look through anymore.
+            self.stop_looking_through(graph);
+        }
+
+        if let Some(transition) = self.bookmark_transition.as_mut() {
+            transition.elapsed += dt;
+            let t = (transition.elapsed / transition.duration).min(1.0);
+            // Ease in and out.
+            let view =
+                interpolate_bookmarks(&transition.from, &transition.to, t * t * (3.0 - 2.0 * t));
+            if t >= 1.0 {
+                self.bookmark_transition = None;
+            }
+            self.apply_bookmark(&view, graph);
+        }
 
-        let global_transform = camera.global_transform();
+        let global_transform = graph[self.camera].global_transform();
         let look = global_transform.look();
         let side = global_transform.side();
         let up = global_transform.up();
@@ -202,29 +589,380 @@ impl CameraController {
         if self.move_down {
             move_vec -= up;
         }
-        if let Some(v) = move_vec.try_normalize(std::f32::EPSILON) {
-            move_vec = v.scale(self.speed_factor * 10.0 * dt);
+        // Stick deflection defines the speed, unlike keys which are either pressed or not.
+        let gamepad_move_vec = look * self.gamepad.movement.z - side * self.gamepad.movement.x
+            + up * self.gamepad.movement.y;
+
+        let target_velocity = (move_vec
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_default()
+            + gamepad_move_vec)
+            .scale(self.speed_factor * self.fly_speed());
+
+        if self.gamepad.look != Vector2::default() {
+            self.rotate_view(self.gamepad.look.scale(GAMEPAD_LOOK_SPEED * dt));
+        }
+
+        if settings.smoothing && settings.smoothing_time > 0.0 {
+            // Frame rate independent exponential approach to the target values.
+            let k = 1.0 - (-dt / settings.smoothing_time).exp();
+            self.velocity += (target_velocity - self.velocity).scale(k);
+            self.smoothed_yaw += (self.yaw - self.smoothed_yaw) * k;
+            self.smoothed_pitch += (self.pitch - self.smoothed_pitch) * k;
+            self.smoothed_rotation = self
+                .smoothed_rotation
+                .try_slerp(&self.rotation, k, f32::EPSILON)
+                .unwrap_or(self.rotation);
+        } else {
+            self.velocity = target_velocity;
+            self.smoothed_yaw = self.yaw;
+            self.smoothed_pitch = self.pitch;
+            self.smoothed_rotation = self.rotation;
         }
 
+        move_vec = self.velocity.scale(dt);
+
         move_vec += side * self.drag_side;
         move_vec.y += self.drag_up;
         self.drag_side = 0.0;
         self.drag_up = 0.0;
 
-        if let Node::Camera(camera) = camera {
-            let pitch = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch);
-            camera.local_transform_mut().set_rotation(pitch);
+        if settings.collide_with_scene {
+            move_vec = self.clamp_movement(graph, root, global_transform.position(), move_vec);
+        }
+
+        let (pivot_rotation, camera_rotation) = match self.orbit_style {
+            OrbitStyle::Turntable => (
+                UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.smoothed_yaw),
+                UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.smoothed_pitch),
+            ),
+            OrbitStyle::Trackball => (self.smoothed_rotation, UnitQuaternion::identity()),
+        };
+
+        // Rotate camera position around the orbit point by the same amount the view is
+        // rotated by, then move the pivot so the camera ends up there.
+        let orbit_pivot_position = self.orbit_point.map(|point| {
+            let camera_transform = graph[self.camera].local_transform();
+            let camera_offset = **camera_transform.position();
+            let old_rotation =
+                **graph[self.pivot].local_transform().rotation() * **camera_transform.rotation();
+            let delta = pivot_rotation * camera_rotation * old_rotation.inverse();
+            let camera_position = point + delta * (global_transform.position() - point);
+            camera_position - pivot_rotation * camera_offset
+        });
+
+        if let Node::Camera(camera) = &mut graph[self.camera] {
+            camera.local_transform_mut().set_rotation(camera_rotation);
         }
         if let Node::Base(pivot) = &mut graph[self.pivot] {
-            let yaw = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw);
-            pivot
-                .local_transform_mut()
-                .set_rotation(yaw)
-                .offset(move_vec);
+            let transform = pivot.local_transform_mut();
+            if let Some(position) = orbit_pivot_position {
+                transform.set_position(position);
+            }
+            transform.set_rotation(pivot_rotation).offset(move_vec);
         }
     }
 
-    pub fn pick<F>(
+    pub fn is_looking_through(&self) -> bool {
+        self.look_through.is_some()
+    }
+
+    /// Returns scene cameras whose `enabled` flag was changed by looking through a camera,
+    /// paired with the flag they had before. Scene must be saved with these values.
+    pub fn original_camera_states(&self) -> Vec<(Handle<Node>, bool)> {
+        match self.look_through.as_ref() {
+            Some(look_through) => std::iter::once((look_through.camera, look_through.was_enabled))
+                .chain(
+                    look_through
+                        .disabled_cameras
+                        .iter()
+                        .map(|&handle| (handle, true)),
+                )
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Renders the viewport through given scene camera instead of the editor camera. The
+    /// editor camera ignores input until [`Self::stop_looking_through`] is called.
+    pub fn look_through(&mut self, camera: Handle<Node>, graph: &mut Graph) {
+        if self.look_through.is_some() {
+            self.stop_looking_through(graph);
+        }
+
+        let disabled_cameras = graph
+            .pair_iter_mut()
+            .filter(|(handle, _)| *handle != camera && *handle != self.camera)
+            .filter_map(|(handle, node)| match node {
+                Node::Camera(other) if other.is_enabled() => {
+                    other.set_enabled(false);
+                    Some(handle)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let scene_camera = graph[camera].as_camera_mut();
+        let was_enabled = scene_camera.is_enabled();
+        scene_camera.set_enabled(true);
+        graph[self.camera].as_camera_mut().set_enabled(false);
+
+        let pivot_transform = graph[self.pivot].local_transform();
+        let camera_transform = graph[self.camera].local_transform();
+        self.look_through = Some(LookThrough {
+            camera,
+            was_enabled,
+            disabled_cameras,
+            pivot_position: **pivot_transform.position(),
+            pivot_rotation: **pivot_transform.rotation(),
+            camera_position: **camera_transform.position(),
+            camera_rotation: **camera_transform.rotation(),
+            yaw: self.yaw,
+            pitch: self.pitch,
+            rotation: self.rotation,
+        });
+        self.bookmark_transition = None;
+        self.orbit_point = None;
+    }
+
+    /// Switches the viewport back to the editor camera, which gets exactly the same view it had
+    /// before looking through a scene camera.
+    pub fn stop_looking_through(&mut self, graph: &mut Graph) {
+        let look_through = match self.look_through.take() {
+            Some(look_through) => look_through,
+            None => return,
+        };
+
+        if let Some(Node::Camera(camera)) = graph.try_get_mut(look_through.camera) {
+            camera.set_enabled(look_through.was_enabled);
+        }
+        for &handle in look_through.disabled_cameras.iter() {
+            if let Some(Node::Camera(camera)) = graph.try_get_mut(handle) {
+                camera.set_enabled(true);
+            }
+        }
+        graph[self.camera].as_camera_mut().set_enabled(true);
+
+        graph[self.pivot]
+            .local_transform_mut()
+            .set_position(look_through.pivot_position)
+            .set_rotation(look_through.pivot_rotation);
+        graph[self.camera]
+            .local_transform_mut()
+            .set_position(look_through.camera_position)
+            .set_rotation(look_through.camera_rotation);
+
+        self.yaw = look_through.yaw;
+        self.pitch = look_through.pitch;
+        self.rotation = look_through.rotation;
+        self.smoothed_yaw = self.yaw;
+        self.smoothed_pitch = self.pitch;
+        self.smoothed_rotation = self.rotation;
+
+        // Forget input that was received while looking through, so the camera does not
+        // start moving on its own.
+        self.velocity = Vector3::default();
+        self.gamepad = Default::default();
+        self.rotate = false;
+        self.drag = false;
+        self.drag_side = 0.0;
+        self.drag_up = 0.0;
+        self.move_left = false;
+        self.move_right = false;
+        self.move_forward = false;
+        self.move_backward = false;
+        self.move_up = false;
+        self.move_down = false;
+        self.speed_factor = 1.0;
+    }
+
+    /// Shortens movement of the camera from given world-space position so it stops in front
+    /// of scene meshes instead of passing through them. Editor nodes are ignored.
+    fn clamp_movement(
+        &mut self,
+        graph: &Graph,
+        root: Handle<Node>,
+        origin: Vector3<f32>,
+        move_vec: Vector3<f32>,
+    ) -> Vector3<f32> {
+        // Distance that camera keeps from geometry, so near clipping plane won't cut it.
+        const SKIN: f32 = 0.25;
+
+        let length = move_vec.norm();
+        let dir = match move_vec.try_normalize(f32::EPSILON) {
+            Some(dir) => dir,
+            None => return move_vec,
+        };
+
+        // Intersection tests treat the ray as a segment, so it covers the whole movement
+        // plus the skin.
+        let ray = Ray::new(origin, dir.scale(length + SKIN));
+
+        let mut closest = f32::MAX;
+
+        self.stack.clear();
+        self.stack.push(graph.get_root());
+        while let Some(handle) = self.stack.pop() {
+            if handle == root {
+                continue;
+            }
+
+            let node = &graph[handle];
+
+            self.stack.extend_from_slice(node.children());
+
+            if let Node::Mesh(mesh) = node {
+                if !mesh.global_visibility() {
+                    continue;
+                }
+
+                let transform = mesh.global_transform();
+                let object_space_ray = ray.transform(transform.try_inverse().unwrap_or_default());
+                if object_space_ray
+                    .aabb_intersection_points(&mesh.bounding_box())
+                    .is_none()
+                {
+                    continue;
+                }
+
+                if let Some((point, _)) =
+                    self.bvh_cache.intersect(&object_space_ray, mesh.surfaces())
+                {
+                    let distance = transform
+                        .transform_point(&Point3::from(point))
+                        .coords
+                        .metric_distance(&origin);
+                    closest = closest.min(distance);
+                }
+            }
+        }
+
+        if closest < length + SKIN {
+            dir.scale((closest - SKIN).max(0.0))
+        } else {
+            move_vec
+        }
+    }
+
+    /// Moves pivot to given world-space point, camera offset is adjusted so its world
+    /// position and orientation stay the same. Further orbiting will be done around the point.
+    pub fn recenter_pivot(&mut self, graph: &mut Graph, point: Vector3<f32>) {
+        let camera_position = graph[self.camera].global_position();
+
+        let pivot = &mut graph[self.pivot];
+        let pivot_rotation = **pivot.local_transform().rotation();
+        pivot.local_transform_mut().set_position(point);
+
+        let offset = pivot_rotation.inverse() * (camera_position - point);
+        graph[self.camera]
+            .local_transform_mut()
+            .set_position(offset);
+    }
+
+    /// Moves the camera so the whole bounding box is visible, keeping current view direction.
+    /// Pivot is placed at the center of the box, so the camera will orbit around it.
+    pub fn fit_bounds(&mut self, aabb: &AxisAlignedBoundingBox, graph: &mut Graph) {
+        let radius = aabb.half_extents().norm();
+        // Degenerate bounds, use distance to a unit object.
+        let radius = if radius > f32::EPSILON { radius } else { 1.0 };
+
+        let fov = if let Node::Camera(camera) = &graph[self.camera] {
+            camera.fov()
+        } else {
+            unreachable!()
+        };
+        let distance = radius / (fov * 0.5).sin();
+
+        let camera_rotation = match self.orbit_style {
+            OrbitStyle::Turntable => {
+                UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch)
+            }
+            OrbitStyle::Trackball => UnitQuaternion::identity(),
+        };
+
+        graph[self.pivot]
+            .local_transform_mut()
+            .set_position(aabb.center());
+        graph[self.camera]
+            .local_transform_mut()
+            .set_position(camera_rotation * Vector3::new(0.0, 0.0, -distance));
+    }
+
+    /// Orients the camera to look along one of world axes and moves pivot to given point.
+    /// Distance between the camera and the pivot is preserved.
+    pub fn set_view(&mut self, view: ViewPreset, center: Vector3<f32>, graph: &mut Graph) {
+        let (yaw, pitch) = view.angles();
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self.rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch);
+
+        let camera_rotation = match self.orbit_style {
+            OrbitStyle::Turntable => UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch),
+            OrbitStyle::Trackball => UnitQuaternion::identity(),
+        };
+
+        let distance = graph[self.camera].local_transform().position().norm();
+        graph[self.pivot].local_transform_mut().set_position(center);
+        graph[self.camera]
+            .local_transform_mut()
+            .set_position(camera_rotation * Vector3::new(0.0, 0.0, -distance));
+    }
+
+    /// Captures current view of the camera.
+    pub fn capture_bookmark(&self, name: String, graph: &Graph) -> CameraBookmark {
+        let rotation = self.rotation.coords;
+        CameraBookmark {
+            name,
+            pivot_position: (**graph[self.pivot].local_transform().position()).into(),
+            camera_offset: (**graph[self.camera].local_transform().position()).into(),
+            yaw: self.yaw,
+            pitch: self.pitch,
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+        }
+    }
+
+    /// Moves the camera to a bookmarked view. The camera flies to the view during given time,
+    /// or jumps to it instantly if the time is zero.
+    pub fn restore_bookmark(
+        &mut self,
+        bookmark: &CameraBookmark,
+        graph: &mut Graph,
+        transition_time: f32,
+    ) {
+        if transition_time > 0.0 {
+            self.bookmark_transition = Some(BookmarkTransition {
+                from: self.capture_bookmark(bookmark.name.clone(), graph),
+                to: bookmark.clone(),
+                elapsed: 0.0,
+                duration: transition_time,
+            });
+        } else {
+            self.bookmark_transition = None;
+            self.apply_bookmark(bookmark, graph);
+        }
+    }
+
+    fn apply_bookmark(&mut self, bookmark: &CameraBookmark, graph: &mut Graph) {
+        self.yaw = bookmark.yaw;
+        self.pitch = bookmark.pitch;
+        self.rotation = bookmark_rotation(bookmark);
+        // View is set directly, there is nothing to smooth.
+        self.smoothed_yaw = self.yaw;
+        self.smoothed_pitch = self.pitch;
+        self.smoothed_rotation = self.rotation;
+
+        graph[self.pivot]
+            .local_transform_mut()
+            .set_position(Vector3::from(bookmark.pivot_position));
+        graph[self.camera]
+            .local_transform_mut()
+            .set_position(Vector3::from(bookmark.camera_offset));
+    }
+
+    /// Returns every node under the cursor sorted by distance, closest first. Unlike `pick`
+    /// it does not affect picking cycle.
+    pub fn pick_all<F>(
         &mut self,
         cursor_pos: Vector2<f32>,
         graph: &Graph,
@@ -232,7 +970,7 @@ impl CameraController {
         screen_size: Vector2<f32>,
         editor_only: bool,
         mut filter: F,
-    ) -> Option<CameraPickResult>
+    ) -> &[CameraPickResult]
     where
         F: FnMut(Handle<Node>, &Node) -> bool,
     {
@@ -253,6 +991,9 @@ impl CameraController {
 
             context.pick_list.clear();
 
+            let mut bvh_time = Duration::default();
+            let mut brute_force_time = Duration::default();
+
             while let Some(handle) = self.stack.pop() {
                 // Ignore editor nodes if we picking scene stuff only.
                 if !editor_only && handle == root {
@@ -269,6 +1010,7 @@ impl CameraController {
 
                 let (aabb, surfaces) = match node {
                     Node::Mesh(mesh) => (mesh.bounding_box(), Some(mesh.surfaces())),
+                    Node::Terrain(terrain) => (terrain.bounding_box(), None),
                     Node::Base(_) if handle == graph.get_root() || handle == root => {
                         (AxisAlignedBoundingBox::default(), None)
                     }
@@ -280,63 +1022,273 @@ impl CameraController {
                         ray.transform(node.global_transform().try_inverse().unwrap_or_default());
                     // Do coarse intersection test with bounding box.
                     if let Some(points) = object_space_ray.aabb_intersection_points(&aabb) {
+                        let da = points[0].metric_distance(&object_space_ray.origin);
+                        let db = points[1].metric_distance(&object_space_ray.origin);
+                        let mut closest_point = if da < db { points[0] } else { points[1] };
+                        let mut normal = aabb_side_normal(&aabb, closest_point);
+
                         // Do fine intersection test with surfaces if any
-                        if let Some(_surfaces) = surfaces {
-                            // TODO
+                        if let Some(surfaces) = surfaces.filter(|_| self.precise_picking) {
+                            let start = Instant::now();
+                            let intersection =
+                                self.bvh_cache.intersect(&object_space_ray, surfaces);
+                            bvh_time += start.elapsed();
+                            if self.log_picking_time {
+                                let start = Instant::now();
+                                surfaces_intersection(&object_space_ray, surfaces);
+                                brute_force_time += start.elapsed();
+                            }
+
+                            match intersection {
+                                Some((point, triangle_normal)) => {
+                                    closest_point = point;
+                                    normal = triangle_normal;
+                                }
+                                // Ray passes through bounding box, but misses the mesh.
+                                None => continue,
+                            }
                         }
 
-                        let da = points[0].metric_distance(&object_space_ray.origin);
-                        let db = points[1].metric_distance(&object_space_ray.origin);
-                        let closest_distance = da.min(db);
+                        // Terrain is always tested against its height map, its bounding box
+                        // is too coarse to be useful.
+                        if let Node::Terrain(terrain) = node {
+                            let mut intersections = ArrayVec::<TerrainRayCastResult, 128>::new();
+                            terrain.raycast(ray, &mut intersections, true);
+                            match intersections.first() {
+                                Some(closest) => {
+                                    closest_point = closest.position;
+                                    normal = closest.normal;
+                                }
+                                None => continue,
+                            }
+                        }
+
+                        // Normals must be transformed with inverse transpose matrix to stay
+                        // perpendicular to the surface under non-uniform scale.
+                        let normal_matrix = node
+                            .global_transform()
+                            .try_inverse()
+                            .unwrap_or_default()
+                            .transpose();
+
                         context.pick_list.push(CameraPickResult {
                             position: node
                                 .global_transform()
-                                .transform_point(&Point3::from(if da < db {
-                                    points[0]
-                                } else {
-                                    points[1]
-                                }))
+                                .transform_point(&Point3::from(closest_point))
                                 .coords,
+                            normal: normal_matrix
+                                .transform_vector(&normal)
+                                .try_normalize(f32::EPSILON)
+                                .unwrap_or_else(Vector3::y),
                             node: handle,
-                            toi: closest_distance,
+                            toi: closest_point.metric_distance(&object_space_ray.origin),
                         });
                     }
                 }
             }
 
+            if self.log_picking_time && bvh_time > Duration::default() {
+                self.sender
+                    .send(Message::Log(format!(
+                        "Precise picking took {:?} (brute force would take {:?}).",
+                        bvh_time, brute_force_time
+                    )))
+                    .unwrap();
+            }
+
             // Make sure closest will be selected first.
             context
                 .pick_list
                 .sort_by(|a, b| a.toi.partial_cmp(&b.toi).unwrap());
 
-            let mut hasher = DefaultHasher::new();
-            for result in context.pick_list.iter() {
-                result.node.hash(&mut hasher);
+            &context.pick_list
+        } else {
+            unreachable!()
+        }
+    }
+
+    pub fn pick<F>(
+        &mut self,
+        cursor_pos: Vector2<f32>,
+        graph: &Graph,
+        root: Handle<Node>,
+        screen_size: Vector2<f32>,
+        editor_only: bool,
+        filter: F,
+    ) -> Option<CameraPickResult>
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        self.pick_all(cursor_pos, graph, root, screen_size, editor_only, filter);
+
+        let context = if editor_only {
+            &mut self.editor_context
+        } else {
+            &mut self.scene_context
+        };
+
+        let mut hasher = DefaultHasher::new();
+        for result in context.pick_list.iter() {
+            result.node.hash(&mut hasher);
+        }
+        let selection_hash = hasher.finish();
+        if selection_hash == context.old_selection_hash && cursor_pos == context.old_cursor_pos {
+            context.pick_index += 1;
+
+            // Wrap picking loop.
+            if context.pick_index >= context.pick_list.len() {
+                context.pick_index = 0;
+            }
+        } else {
+            // Select is different, start from beginning.
+            context.pick_index = 0;
+        }
+        context.old_selection_hash = selection_hash;
+        context.old_cursor_pos = cursor_pos;
+
+        context.pick_list.get(context.pick_index).cloned()
+    }
+
+    fn pick_context(&self, editor_only: bool) -> &PickContext {
+        if editor_only {
+            &self.editor_context
+        } else {
+            &self.scene_context
+        }
+    }
+
+    /// Returns index of the result that was returned by last `pick` among every node that
+    /// was under the cursor.
+    pub fn pick_index(&self, editor_only: bool) -> usize {
+        self.pick_context(editor_only).pick_index
+    }
+
+    /// Returns amount of nodes that were under the cursor during last `pick`.
+    pub fn pick_count(&self, editor_only: bool) -> usize {
+        self.pick_context(editor_only).pick_list.len()
+    }
+
+    /// Selects exactly which of overlapping nodes under the cursor is the current one, next
+    /// `pick` at the same position continues cycling from it. Returns the result at given
+    /// index, or `None` if the index is out of range.
+    pub fn set_pick_index(&mut self, editor_only: bool, index: usize) -> Option<CameraPickResult> {
+        let context = if editor_only {
+            &mut self.editor_context
+        } else {
+            &mut self.scene_context
+        };
+
+        let result = context.pick_list.get(index).cloned();
+        if result.is_some() {
+            context.pick_index = index;
+        }
+        result
+    }
+
+    /// Returns every node whose screen-space projection of bounding box lies in given
+    /// rectangle. If `fully_contained` is false, it is enough for a single corner of the box
+    /// to be in the rectangle.
+    pub fn pick_in_rect<F>(
+        &mut self,
+        rect: Rect<f32>,
+        graph: &Graph,
+        root: Handle<Node>,
+        screen_size: Vector2<f32>,
+        editor_only: bool,
+        fully_contained: bool,
+        mut filter: F,
+    ) -> Vec<Handle<Node>>
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        let camera = if let Node::Camera(camera) = &graph[self.camera] {
+            camera
+        } else {
+            unreachable!()
+        };
+
+        self.stack.clear();
+        self.stack
+            .push(if editor_only { root } else { graph.get_root() });
+
+        let mut result = Vec::new();
+        while let Some(handle) = self.stack.pop() {
+            // Ignore editor nodes if we picking scene stuff only.
+            if !editor_only && handle == root {
+                continue;
             }
-            let selection_hash = hasher.finish();
-            if selection_hash == context.old_selection_hash && cursor_pos == context.old_cursor_pos
+
+            let node = &graph[handle];
+
+            self.stack.extend_from_slice(node.children());
+
+            if handle == graph.get_root()
+                || handle == root
+                || !node.global_visibility()
+                || !filter(handle, node)
             {
-                context.pick_index += 1;
+                continue;
+            }
 
-                // Wrap picking loop.
-                if context.pick_index >= context.pick_list.len() {
-                    context.pick_index = 0;
-                }
+            let aabb = match node {
+                Node::Mesh(mesh) => mesh.bounding_box(),
+                Node::Terrain(terrain) => terrain.bounding_box(),
+                _ => AxisAlignedBoundingBox::unit(),
+            };
+
+            let transform = node.global_transform();
+            let mut inside = aabb.corners().iter().map(|corner| {
+                let world = transform.transform_point(&Point3::from(*corner)).coords;
+                camera
+                    .project(world, screen_size)
+                    .map_or(false, |p| rect.contains(p))
+            });
+            let selected = if fully_contained {
+                inside.all(|i| i)
             } else {
-                // Select is different, start from beginning.
-                context.pick_index = 0;
-            }
-            context.old_selection_hash = selection_hash;
-            context.old_cursor_pos = cursor_pos;
+                inside.any(|i| i)
+            };
 
-            if !context.pick_list.is_empty() {
-                if let Some(result) = context.pick_list.get(context.pick_index) {
-                    return Some(result.clone());
-                }
+            if selected {
+                result.push(handle);
             }
         }
 
-        None
+        result
+    }
+
+    /// Returns world-space ray that starts at the camera and goes through given point
+    /// of the screen.
+    pub fn make_ray(
+        &self,
+        cursor_pos: Vector2<f32>,
+        screen_size: Vector2<f32>,
+        graph: &Graph,
+    ) -> Option<Ray> {
+        if let Node::Camera(camera) = &graph[self.camera] {
+            Some(camera.make_ray(cursor_pos, screen_size))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the point where view direction of the camera hits the ground (XZ plane), if
+    /// the camera looks down and the point is not farther than far clipping plane.
+    pub fn ground_look_at_point(&self, graph: &Graph) -> Option<Vector3<f32>> {
+        if let Node::Camera(camera) = &graph[self.camera] {
+            let look = camera
+                .global_transform()
+                .look()
+                .try_normalize(f32::EPSILON)?;
+            if look.y >= 0.0 {
+                return None;
+            }
+            let plane = Plane::from_normal_and_point(&Vector3::y(), &Vector3::default())?;
+            Ray::new(camera.global_position(), look.scale(camera.z_far()))
+                .plane_intersection_point(&plane)
+        } else {
+            None
+        }
     }
 
     pub fn pick_on_plane(
@@ -347,13 +1299,8 @@ impl CameraController {
         viewport_size: Vector2<f32>,
         transform: Matrix4<f32>,
     ) -> Option<Vector3<f32>> {
-        if let Node::Camera(camera) = &graph[self.camera] {
-            camera
-                .make_ray(mouse_position, viewport_size)
-                .transform(transform)
-                .plane_intersection_point(&plane)
-        } else {
-            unreachable!()
-        }
+        self.make_ray(mouse_position, viewport_size, graph)?
+            .transform(transform)
+            .plane_intersection_point(&plane)
     }
 }
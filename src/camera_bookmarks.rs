@@ -0,0 +1,288 @@
+//! Named views of the editor camera. Bookmarks are stored in settings per scene file, so
+//! they are available every time the scene is opened.
+
+use crate::{
+    scene::EditorScene,
+    settings::{bookmarks::CameraBookmark, Settings},
+    GameEngine, Message,
+};
+use rg3d::{
+    core::{pool::Handle, scope_profile},
+    gui::{
+        border::BorderBuilder,
+        button::ButtonBuilder,
+        decorator::DecoratorBuilder,
+        grid::{Column, GridBuilder, Row},
+        list_view::ListViewBuilder,
+        message::{
+            ButtonMessage, ListViewMessage, MessageDirection, TextBoxMessage, UiMessage,
+            UiMessageData, WidgetMessage,
+        },
+        text::TextBuilder,
+        text_box::{TextBoxBuilder, TextCommitMode},
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::sync::mpsc::Sender;
+
+pub struct CameraBookmarksPanel {
+    pub window: Handle<UiNode>,
+    list: Handle<UiNode>,
+    name: Handle<UiNode>,
+    add: Handle<UiNode>,
+    go_to: Handle<UiNode>,
+    remove: Handle<UiNode>,
+    name_value: String,
+    selection: Option<usize>,
+    // Bookmarks that are shown in the list, the list is rebuilt only when they change.
+    shown_bookmarks: Vec<CameraBookmark>,
+}
+
+fn make_list_item(ctx: &mut BuildContext, bookmark: &CameraBookmark) -> Handle<UiNode> {
+    DecoratorBuilder::new(BorderBuilder::new(
+        WidgetBuilder::new().with_height(22.0).with_child(
+            TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .with_text(&bookmark.name)
+                .build(ctx),
+        ),
+    ))
+    .build(ctx)
+}
+
+impl CameraBookmarksPanel {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let list;
+        let name;
+        let add;
+        let go_to;
+        let remove;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(250.0).with_height(300.0))
+            .with_title(WindowTitle::text("Camera Bookmarks"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            list = ListViewBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .on_column(0)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .build(ctx);
+                            list
+                        })
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(0)
+                                    .with_child({
+                                        name = TextBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text_commit_mode(TextCommitMode::Immediate)
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .build(ctx);
+                                        name
+                                    })
+                                    .with_child({
+                                        add = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Add")
+                                        .build(ctx);
+                                        add
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(70.0))
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .on_column(0)
+                                    .with_child({
+                                        go_to = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Go To")
+                                        .build(ctx);
+                                        go_to
+                                    })
+                                    .with_child({
+                                        remove = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Remove")
+                                        .build(ctx);
+                                        remove
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::stretch())
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            list,
+            name,
+            add,
+            go_to,
+            remove,
+            name_value: Default::default(),
+            selection: None,
+            shown_bookmarks: Default::default(),
+        }
+    }
+
+    pub fn sync_to_model(
+        &mut self,
+        editor_scene: Option<&EditorScene>,
+        settings: &Settings,
+        ui: &mut UserInterface,
+    ) {
+        let bookmarks = editor_scene
+            .and_then(|s| s.path.as_ref())
+            .map_or(&[] as &[CameraBookmark], |path| {
+                settings.camera_bookmarks.of_scene(path)
+            });
+
+        if bookmarks == self.shown_bookmarks.as_slice() {
+            return;
+        }
+        self.shown_bookmarks = bookmarks.to_vec();
+
+        let ctx = &mut ui.build_ctx();
+        let items = bookmarks
+            .iter()
+            .map(|bookmark| make_list_item(ctx, bookmark))
+            .collect::<Vec<_>>();
+
+        ui.send_message(ListViewMessage::items(
+            self.list,
+            MessageDirection::ToWidget,
+            items,
+        ));
+
+        self.selection = None;
+        self.sync_buttons(ui);
+    }
+
+    fn sync_buttons(&self, ui: &UserInterface) {
+        for &button in &[self.go_to, self.remove] {
+            ui.send_message(WidgetMessage::enabled(
+                button,
+                MessageDirection::ToWidget,
+                self.selection.is_some(),
+            ));
+        }
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_scene: &mut EditorScene,
+        engine: &mut GameEngine,
+        settings: &mut Settings,
+        sender: &Sender<Message>,
+    ) {
+        scope_profile!();
+
+        match message.data() {
+            UiMessageData::TextBox(TextBoxMessage::Text(text))
+                if message.destination() == self.name
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                self.name_value = text.clone();
+            }
+            &UiMessageData::ListView(ListViewMessage::SelectionChanged(selection))
+                if message.destination() == self.list =>
+            {
+                self.selection = selection;
+                self.sync_buttons(&engine.user_interface);
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.add
+                    || message.destination() == self.go_to
+                    || message.destination() == self.remove =>
+            {
+                let path = match editor_scene.path.clone() {
+                    Some(path) => path,
+                    None => {
+                        sender
+                            .send(Message::Log(
+                                "Save the scene first, camera bookmarks are stored per scene."
+                                    .to_owned(),
+                            ))
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                let graph = &mut engine.scenes[editor_scene.scene].graph;
+
+                if message.destination() == self.add {
+                    let name = if self.name_value.trim().is_empty() {
+                        format!(
+                            "Bookmark {}",
+                            settings.camera_bookmarks.of_scene(&path).len() + 1
+                        )
+                    } else {
+                        self.name_value.trim().to_owned()
+                    };
+
+                    let bookmark = editor_scene.camera_controller.capture_bookmark(name, graph);
+                    settings.camera_bookmarks.add(&path, bookmark);
+                } else if message.destination() == self.go_to {
+                    if let Some(bookmark) = self
+                        .selection
+                        .and_then(|i| settings.camera_bookmarks.of_scene(&path).get(i))
+                    {
+                        editor_scene.camera_controller.restore_bookmark(
+                            bookmark,
+                            graph,
+                            settings.camera.bookmark_transition_time,
+                        );
+                    }
+                    return;
+                } else if let Some(index) = self.selection {
+                    settings.camera_bookmarks.remove(&path, index);
+                }
+
+                if let Err(e) = settings.save() {
+                    println!("Unable to save settings! Reason: {:?}!", e);
+                }
+
+                self.sync_to_model(Some(editor_scene), settings, &mut engine.user_interface);
+            }
+            _ => {}
+        }
+    }
+}
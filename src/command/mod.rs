@@ -10,6 +10,11 @@ macro_rules! define_command_stack {
             fn execute(&mut self, context: &mut $context);
             fn revert(&mut self, context: &mut $context);
             fn finalize(&mut self, _: &mut $context) {}
+            /// Commands that only change selection are not changes of the scene, so they do
+            /// not make it unsaved.
+            fn is_selection_only(&self) -> bool {
+                false
+            }
         }
 
         pub struct $command_stack {
@@ -61,14 +66,17 @@ macro_rules! define_command_stack {
                 self.commands.push(command);
             }
 
-            pub fn undo(&mut self, mut context: $context) {
+            /// Reverts the top command. Returns true if the command changed more than selection.
+            pub fn undo(&mut self, mut context: $context) -> bool {
+                let mut changed = false;
                 if !self.commands.is_empty() {
                     if let Some(top) = self.top.as_mut() {
                         if let Some(command) = self.commands.get_mut(*top) {
                             if self.debug {
                                 println!("Undo command {:?}", command);
                             }
-                            command.revert(&mut context)
+                            command.revert(&mut context);
+                            changed = !command.is_selection_only();
                         }
                         if *top == 0 {
                             self.top = None;
@@ -77,9 +85,12 @@ macro_rules! define_command_stack {
                         }
                     }
                 }
+                changed
             }
 
-            pub fn redo(&mut self, mut context: $context) {
+            /// Executes the next reverted command. Returns true if the command changed more
+            /// than selection.
+            pub fn redo(&mut self, mut context: $context) -> bool {
                 if !self.commands.is_empty() {
                     let command = match self.top.as_mut() {
                         None => {
@@ -101,9 +112,11 @@ macro_rules! define_command_stack {
                         if self.debug {
                             println!("Redo command {:?}", command);
                         }
-                        command.execute(&mut context)
+                        command.execute(&mut context);
+                        return !command.is_selection_only();
                     }
                 }
+                false
             }
 
             pub fn clear(&mut self, mut context: $context) {
@@ -0,0 +1,85 @@
+//! Reads state of a game controller to fly editor camera with it.
+
+use gilrs::{Axis, Button, Gamepad, Gilrs};
+use rg3d::{
+    core::algebra::{Vector2, Vector3},
+    utils::log::{Log, MessageKind},
+};
+
+/// State of a gamepad translated to camera controls.
+#[derive(Default, Copy, Clone)]
+pub struct GamepadState {
+    /// X - strafe right, Y - fly up, Z - fly forward. Each component is in [-1; 1] range.
+    pub movement: Vector3<f32>,
+    /// Look direction change, has the same meaning as mouse movement: positive X turns right,
+    /// positive Y turns down.
+    pub look: Vector2<f32>,
+}
+
+pub struct GamepadInput {
+    // Gamepad support could be unavailable on some platforms.
+    gilrs: Option<Gilrs>,
+}
+
+fn apply_deadzone(stick: Vector2<f32>, deadzone: f32) -> Vector2<f32> {
+    let length = stick.norm();
+    if length <= deadzone || deadzone >= 1.0 {
+        Vector2::default()
+    } else {
+        // Rescale so output starts from zero at the edge of the dead zone.
+        stick.scale(((length - deadzone) / (1.0 - deadzone)).min(1.0) / length)
+    }
+}
+
+fn trigger_value(gamepad: &Gamepad, button: Button) -> f32 {
+    gamepad.button_data(button).map_or(0.0, |data| data.value())
+}
+
+impl GamepadInput {
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                Log::writeln(
+                    MessageKind::Warning,
+                    format!("Gamepad support is unavailable. Reason: {:?}", e),
+                );
+                None
+            }
+        };
+
+        Self { gilrs }
+    }
+
+    /// Returns state of the first connected gamepad, if any.
+    pub fn poll(&mut self, deadzone: f32) -> Option<GamepadState> {
+        let gilrs = self.gilrs.as_mut()?;
+
+        // State of gamepads is updated only when events are pulled.
+        while gilrs.next_event().is_some() {}
+
+        let (_, gamepad) = gilrs.gamepads().find(|(_, g)| g.is_connected())?;
+
+        let left = apply_deadzone(
+            Vector2::new(
+                gamepad.value(Axis::LeftStickX),
+                gamepad.value(Axis::LeftStickY),
+            ),
+            deadzone,
+        );
+        let right = apply_deadzone(
+            Vector2::new(
+                gamepad.value(Axis::RightStickX),
+                gamepad.value(Axis::RightStickY),
+            ),
+            deadzone,
+        );
+        let up = trigger_value(&gamepad, Button::RightTrigger2)
+            - trigger_value(&gamepad, Button::LeftTrigger2);
+
+        Some(GamepadState {
+            movement: Vector3::new(left.x, up, left.y),
+            look: Vector2::new(right.x, -right.y),
+        })
+    }
+}
@@ -11,6 +11,7 @@ use rg3d::{
         grid::{Column, GridBuilder, Row},
         image::ImageBuilder,
         message::{ButtonMessage, MessageDirection, UiMessageData},
+        numeric::NumericUpDownBuilder,
         text::TextBuilder,
         widget::WidgetBuilder,
         Control, HorizontalAlignment, NodeHandleMapping, Thickness, VerticalAlignment,
@@ -36,6 +37,37 @@ pub fn make_dropdown_list_option(ctx: &mut BuildContext, name: &str) -> Handle<U
     .build(ctx)
 }
 
+/// Makes a fixed-width button for the bottom row of a dialog.
+pub fn make_button(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    ButtonBuilder::new(
+        WidgetBuilder::new()
+            .with_width(100.0)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_text(text)
+    .build(ctx)
+}
+
+/// Makes a numeric field for the second column of a dialog grid.
+pub fn make_f32_field(
+    ctx: &mut BuildContext,
+    row: usize,
+    value: f32,
+    min: f32,
+    precision: usize,
+) -> Handle<UiNode> {
+    NumericUpDownBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_value(value)
+    .with_min_value(min)
+    .with_precision(precision)
+    .build(ctx)
+}
+
 impl AssetItemMessage {
     pub fn select(destination: Handle<UiNode>, select: bool) -> UiMessage {
         UiMessage::user(
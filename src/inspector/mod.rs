@@ -10,24 +10,34 @@ use crate::{
             rigid_body::handle_rigid_body_property_changed,
             sound::*,
         },
+        surfaces::SurfacesPanel,
     },
     physics::RigidBody,
-    scene::{EditorScene, Selection},
-    GameEngine, Message, MSG_SYNC_FLAG,
+    scene::{
+        commands::graph::{is_node_enabled, make_set_enabled_command},
+        EditorScene, Selection,
+    },
+    send_sync_message, GameEngine, Message, MSG_SYNC_FLAG,
 };
 use rg3d::{
     core::{inspect::Inspect, pool::Handle},
     engine::resource_manager::ResourceManager,
     gui::{
+        check_box::CheckBoxBuilder,
         inspector::{
             editors::PropertyEditorDefinitionContainer, InspectorBuilder, InspectorContext,
             InspectorEnvironment,
         },
-        message::{InspectorMessage, MessageDirection, UiMessage, UiMessageData},
+        message::{
+            CheckBoxMessage, InspectorMessage, MessageDirection, TextMessage, UiMessage,
+            UiMessageData, WidgetMessage,
+        },
         scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
-        BuildContext, UiNode, UserInterface,
+        BuildContext, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
     sound::source::{generic::GenericSource, spatial::SpatialSource},
     utils::log::{Log, MessageKind},
@@ -40,6 +50,7 @@ use std::{
 
 pub mod editors;
 pub mod handlers;
+pub mod surfaces;
 
 pub struct EditorEnvironment {
     resource_manager: ResourceManager,
@@ -54,6 +65,10 @@ impl InspectorEnvironment for EditorEnvironment {
 pub struct Inspector {
     pub window: Handle<UiNode>,
     inspector: Handle<UiNode>,
+    // Enabled state of selected scene nodes, works for multi-selection too.
+    enabled: Handle<UiNode>,
+    // Summary mass of selected rigid bodies.
+    total_mass: Handle<UiNode>,
     property_editors: Rc<PropertyEditorDefinitionContainer>,
     // Hack. This flag tells whether the inspector should sync with model or not.
     // There is only one situation when it has to be `false` - when inspector has
@@ -61,6 +76,7 @@ pub struct Inspector {
     // inspector is already in correct state.
     needs_sync: bool,
     node_property_changed_handler: SceneNodePropertyChangedHandler,
+    pub surfaces_panel: SurfacesPanel,
 }
 
 pub struct SenderHelper {
@@ -85,17 +101,55 @@ macro_rules! do_command {
 
 impl Inspector {
     pub fn new(ctx: &mut BuildContext, sender: Sender<Message>) -> Self {
+        let surfaces_panel = SurfacesPanel::new(ctx, sender.clone());
         let property_editors = make_property_editors_container(sender);
 
         let inspector;
+        let enabled;
+        let total_mass;
         let window = WindowBuilder::new(WidgetBuilder::new())
             .with_title(WindowTitle::text("Inspector"))
             .with_content(
                 ScrollViewerBuilder::new(WidgetBuilder::new())
-                    .with_content({
-                        inspector = InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
-                        inspector
-                    })
+                    .with_content(
+                        StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_child({
+                                    enabled = CheckBoxBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_visibility(false)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_content(
+                                        TextBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Enabled")
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .build(ctx),
+                                    )
+                                    .build(ctx);
+                                    enabled
+                                })
+                                .with_child({
+                                    total_mass = TextBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_visibility(false)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .build(ctx);
+                                    total_mass
+                                })
+                                .with_child({
+                                    inspector =
+                                        InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
+                                    inspector
+                                })
+                                .with_child(surfaces_panel.panel),
+                        )
+                        .build(ctx),
+                    )
                     .build(ctx),
             )
             .build(ctx);
@@ -103,11 +157,14 @@ impl Inspector {
         Self {
             window,
             inspector,
+            enabled,
+            total_mass,
             property_editors,
             needs_sync: true,
             node_property_changed_handler: SceneNodePropertyChangedHandler {
                 particle_system_handler: ParticleSystemHandler::new(ctx),
             },
+            surfaces_panel,
         }
     }
 
@@ -130,6 +187,10 @@ impl Inspector {
     }
 
     pub fn sync_to_model(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        self.surfaces_panel.sync_to_model(editor_scene, engine);
+        self.sync_enabled(editor_scene, engine);
+        self.sync_total_mass(editor_scene, &mut engine.user_interface);
+
         let scene = &engine.scenes[editor_scene.scene];
 
         if self.needs_sync {
@@ -166,6 +227,66 @@ impl Inspector {
         }
     }
 
+    fn sync_enabled(&self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+        let ui = &mut engine.user_interface;
+
+        let state = if let Selection::Graph(selection) = &editor_scene.selection {
+            let mut states = selection
+                .nodes()
+                .iter()
+                .filter_map(|&n| graph.try_get(n).map(is_node_enabled));
+            states.next().map(|first| {
+                // Mixed states are shown as undefined.
+                if states.all(|s| s == first) {
+                    Some(first)
+                } else {
+                    None
+                }
+            })
+        } else {
+            None
+        };
+
+        send_sync_message(
+            ui,
+            WidgetMessage::visibility(self.enabled, MessageDirection::ToWidget, state.is_some()),
+        );
+        if let Some(state) = state {
+            send_sync_message(
+                ui,
+                CheckBoxMessage::checked(self.enabled, MessageDirection::ToWidget, state),
+            );
+        }
+    }
+
+    fn sync_total_mass(&self, editor_scene: &EditorScene, ui: &mut UserInterface) {
+        let total_mass = if let Selection::RigidBody(selection) = &editor_scene.selection {
+            Some(selection.total_mass(&editor_scene.physics))
+        } else {
+            None
+        };
+
+        send_sync_message(
+            ui,
+            WidgetMessage::visibility(
+                self.total_mass,
+                MessageDirection::ToWidget,
+                total_mass.is_some(),
+            ),
+        );
+        if let Some(total_mass) = total_mass {
+            send_sync_message(
+                ui,
+                TextMessage::text(
+                    self.total_mass,
+                    MessageDirection::ToWidget,
+                    format!("Total Mass (dynamic bodies): {:.3}", total_mass),
+                ),
+            );
+        }
+    }
+
     fn change_context(
         &mut self,
         obj: &dyn Inspect,
@@ -251,8 +372,24 @@ impl Inspector {
             sender: sender.clone(),
         };
 
+        self.surfaces_panel.handle_ui_message(message);
+
         let scene = &engine.scenes[editor_scene.scene];
 
+        if let UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) = *message.data() {
+            if message.destination() == self.enabled
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Selection::Graph(selection) = &editor_scene.selection {
+                    if let Some(command) =
+                        make_set_enabled_command(&scene.graph, selection.nodes(), value)
+                    {
+                        sender.send(Message::DoSceneCommand(command)).unwrap();
+                    }
+                }
+            }
+        }
+
         let mut success = Some(());
 
         // Special case for particle systems.
@@ -0,0 +1,238 @@
+use crate::{
+    scene::{commands::mesh::SetMeshSurfacesMaterialCommand, EditorScene, Selection},
+    send_sync_message, GameEngine, Message,
+};
+use rg3d::{
+    core::{algebra::Point3, math::aabb::AxisAlignedBoundingBox, pool::Handle},
+    gui::{
+        button::ButtonBuilder,
+        grid::{Column, GridBuilder, Row},
+        message::{ButtonMessage, MessageDirection, UiMessage, UiMessageData, WidgetMessage},
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        widget::WidgetBuilder,
+        BuildContext, Thickness, UiNode, VerticalAlignment,
+    },
+    material::Material,
+    scene::{
+        graph::Graph,
+        mesh::buffer::{VertexAttributeUsage, VertexReadTrait},
+        node::Node,
+    },
+};
+use std::sync::{mpsc::Sender, Arc, Mutex};
+
+struct SurfaceItem {
+    edit: Handle<UiNode>,
+    show: Handle<UiNode>,
+    use_for_all: Handle<UiNode>,
+}
+
+/// Per-surface overview of selected mesh - each surface is listed with its material,
+/// a button to open the material in the material editor and a button to assign the
+/// material to every surface of the mesh.
+pub struct SurfacesPanel {
+    pub panel: Handle<UiNode>,
+    sender: Sender<Message>,
+    mesh: Handle<Node>,
+    materials: Vec<Arc<Mutex<Material>>>,
+    items: Vec<SurfaceItem>,
+    highlighted: Option<usize>,
+}
+
+fn make_surface_item(
+    ctx: &mut BuildContext,
+    index: usize,
+    material: &Arc<Mutex<Material>>,
+) -> (Handle<UiNode>, SurfaceItem) {
+    let shader_name = material
+        .lock()
+        .unwrap()
+        .shader()
+        .data_ref()
+        .definition
+        .name
+        .clone();
+
+    let edit;
+    let show;
+    let use_for_all;
+    let container = GridBuilder::new(
+        WidgetBuilder::new()
+            .with_margin(Thickness::uniform(1.0))
+            .with_child(
+                TextBuilder::new(WidgetBuilder::new().on_column(0))
+                    .with_text(format!("Surface {}", index))
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .build(ctx),
+            )
+            .with_child(
+                TextBuilder::new(WidgetBuilder::new().on_column(1))
+                    .with_text(shader_name)
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .build(ctx),
+            )
+            .with_child({
+                show = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .on_column(2)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text("Show")
+                .build(ctx);
+                show
+            })
+            .with_child({
+                edit = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .on_column(3)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text("Edit...")
+                .build(ctx);
+                edit
+            })
+            .with_child({
+                use_for_all = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .on_column(4)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text("Use For All")
+                .build(ctx);
+                use_for_all
+            }),
+    )
+    .add_row(Row::strict(24.0))
+    .add_column(Column::strict(100.0))
+    .add_column(Column::stretch())
+    .add_column(Column::strict(50.0))
+    .add_column(Column::strict(50.0))
+    .add_column(Column::strict(80.0))
+    .build(ctx);
+
+    (
+        container,
+        SurfaceItem {
+            edit,
+            show,
+            use_for_all,
+        },
+    )
+}
+
+impl SurfacesPanel {
+    pub fn new(ctx: &mut BuildContext, sender: Sender<Message>) -> Self {
+        let panel = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+
+        Self {
+            panel,
+            sender,
+            mesh: Default::default(),
+            materials: Default::default(),
+            items: Default::default(),
+            highlighted: None,
+        }
+    }
+
+    pub fn sync_to_model(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+
+        let mut mesh = Handle::NONE;
+        let mut materials = Vec::new();
+        if let Selection::Graph(selection) = &editor_scene.selection {
+            if selection.is_single_selection() {
+                let handle = selection.nodes()[0];
+                if let Some(Node::Mesh(mesh_node)) = graph.try_get(handle) {
+                    mesh = handle;
+                    materials = mesh_node
+                        .surfaces()
+                        .iter()
+                        .map(|s| s.material().clone())
+                        .collect::<Vec<_>>();
+                }
+            }
+        }
+
+        let changed = mesh != self.mesh
+            || materials.len() != self.materials.len()
+            || materials
+                .iter()
+                .zip(self.materials.iter())
+                .any(|(a, b)| !Arc::ptr_eq(a, b));
+
+        if changed {
+            let ui = &mut engine.user_interface;
+
+            if mesh != self.mesh {
+                self.highlighted = None;
+            }
+
+            for &child in ui.node(self.panel).children() {
+                send_sync_message(ui, WidgetMessage::remove(child, MessageDirection::ToWidget));
+            }
+            self.items.clear();
+
+            for (i, material) in materials.iter().enumerate() {
+                let (container, item) = make_surface_item(&mut ui.build_ctx(), i, material);
+                send_sync_message(
+                    ui,
+                    WidgetMessage::link(container, MessageDirection::ToWidget, self.panel),
+                );
+                self.items.push(item);
+            }
+
+            self.mesh = mesh;
+            self.materials = materials;
+        }
+    }
+
+    pub fn handle_ui_message(&mut self, message: &UiMessage) {
+        if let UiMessageData::Button(ButtonMessage::Click) = message.data() {
+            for (i, item) in self.items.iter().enumerate() {
+                if message.destination() == item.edit {
+                    self.sender
+                        .send(Message::OpenMaterialEditor(self.materials[i].clone()))
+                        .unwrap();
+                    break;
+                } else if message.destination() == item.use_for_all {
+                    self.sender
+                        .send(Message::do_scene_command(
+                            SetMeshSurfacesMaterialCommand::new(
+                                self.mesh,
+                                self.materials[i].clone(),
+                                self.materials.len(),
+                            ),
+                        ))
+                        .unwrap();
+                    break;
+                } else if message.destination() == item.show {
+                    self.highlighted = if self.highlighted == Some(i) {
+                        None
+                    } else {
+                        Some(i)
+                    };
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns world-space bounds of currently highlighted surface (if any).
+    pub fn highlighted_surface_bounds(&self, graph: &Graph) -> Option<AxisAlignedBoundingBox> {
+        let index = self.highlighted?;
+        if let Some(Node::Mesh(mesh)) = graph.try_get(self.mesh) {
+            let surface = mesh.surfaces().get(index)?;
+            let transform = mesh.global_transform();
+            let mut bounds = AxisAlignedBoundingBox::default();
+            for vertex in surface.data().read().unwrap().vertex_buffer.iter() {
+                if let Ok(position) = vertex.read_3_f32(VertexAttributeUsage::Position) {
+                    bounds.add_point(transform.transform_point(&Point3::from(position)).coords);
+                }
+            }
+            Some(bounds)
+        } else {
+            None
+        }
+    }
+}
@@ -79,6 +79,7 @@ pub trait InteractionMode: BaseInteractionMode {
         _key: KeyCode,
         _editor_scene: &mut EditorScene,
         _engine: &mut GameEngine,
+        _settings: &Settings,
     ) {
     }
 
@@ -95,6 +96,7 @@ pub trait InteractionMode: BaseInteractionMode {
         _message: &UiMessage,
         _editor_scene: &mut EditorScene,
         _engine: &mut GameEngine,
+        _settings: &Settings,
     ) {
     }
 
@@ -294,36 +294,16 @@ impl MoveContext {
             .map(|p| self.plane_kind.project_point(p))
         {
             for entry in self.objects.iter_mut() {
-                let mut new_local_position = entry.initial_local_position
+                let new_local_position = entry.initial_local_position
                     + entry.initial_parent_inv_global_transform.transform_vector(
                         &self.gizmo_local_transform.transform_vector(
                             &(picked_position_gizmo_space + entry.initial_offset_gizmo_space),
                         ),
                     );
 
-                // Snap to grid if needed.
-                if settings.move_mode_settings.grid_snapping {
-                    fn round_to_step(x: f32, step: f32) -> f32 {
-                        x - x % step
-                    }
-
-                    new_local_position = Vector3::new(
-                        round_to_step(
-                            new_local_position.x,
-                            settings.move_mode_settings.x_snap_step,
-                        ),
-                        round_to_step(
-                            new_local_position.y,
-                            settings.move_mode_settings.y_snap_step,
-                        ),
-                        round_to_step(
-                            new_local_position.z,
-                            settings.move_mode_settings.z_snap_step,
-                        ),
-                    );
-                }
-
-                entry.new_local_position = new_local_position;
+                entry.new_local_position = settings
+                    .move_mode_settings
+                    .snap_position(new_local_position);
             }
         }
     }
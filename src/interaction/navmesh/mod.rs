@@ -716,6 +716,7 @@ impl InteractionMode for EditNavmeshMode {
         key: KeyCode,
         editor_scene: &mut EditorScene,
         engine: &mut GameEngine,
+        _settings: &Settings,
     ) {
         match key {
             KeyCode::Delete => {
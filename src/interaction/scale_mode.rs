@@ -1,14 +1,16 @@
-use crate::scene::commands::SceneCommand;
-use crate::world::graph::selection::GraphSelection;
 use crate::{
     interaction::{
         calculate_gizmo_distance_scaling, gizmo::scale_gizmo::ScaleGizmo, InteractionMode,
     },
     scene::{
-        commands::{graph::ScaleNodeCommand, ChangeSelectionCommand, CommandGroup},
+        commands::{
+            graph::{MoveNodeCommand, ScaleNodeCommand},
+            ChangeSelectionCommand, CommandGroup, SceneCommand,
+        },
         EditorScene, Selection,
     },
     settings::Settings,
+    world::graph::selection::GraphSelection,
     GameEngine, Message,
 };
 use rg3d::{
@@ -16,12 +18,24 @@ use rg3d::{
         algebra::{Vector2, Vector3},
         pool::Handle,
     },
-    scene::node::Node,
+    scene::{graph::Graph, node::Node},
 };
 use std::sync::mpsc::Sender;
 
+/// Initial local transform of a child of a scaled node, used to compensate parent's scale.
+struct ChildState {
+    // Index of parent in selection.
+    parent: usize,
+    node: Handle<Node>,
+    position: Vector3<f32>,
+    scale: Vector3<f32>,
+}
+
 pub struct ScaleInteractionMode {
     initial_scales: Vec<Vector3<f32>>,
+    // Filled only if children should keep their world transform (Alt is held when
+    // interaction starts).
+    children: Vec<ChildState>,
     scale_gizmo: ScaleGizmo,
     interacting: bool,
     message_sender: Sender<Message>,
@@ -35,6 +49,7 @@ impl ScaleInteractionMode {
     ) -> Self {
         Self {
             initial_scales: Default::default(),
+            children: Default::default(),
             scale_gizmo: ScaleGizmo::new(editor_scene, engine),
             interacting: false,
             message_sender,
@@ -42,6 +57,25 @@ impl ScaleInteractionMode {
     }
 }
 
+fn collect_children(selection: &GraphSelection, graph: &Graph) -> Vec<ChildState> {
+    let mut children = Vec::new();
+    for (parent, &handle) in selection.nodes().iter().enumerate() {
+        for &child in graph[handle].children() {
+            // Selected children are scaled by themselves.
+            if !selection.contains(child) {
+                let transform = graph[child].local_transform();
+                children.push(ChildState {
+                    parent,
+                    node: child,
+                    position: **transform.position(),
+                    scale: **transform.scale(),
+                });
+            }
+        }
+    }
+    children
+}
+
 impl InteractionMode for ScaleInteractionMode {
     fn on_left_mouse_button_down(
         &mut self,
@@ -71,6 +105,11 @@ impl InteractionMode for ScaleInteractionMode {
                     let graph = &mut engine.scenes[editor_scene.scene].graph;
                     self.interacting = true;
                     self.initial_scales = selection.local_scales(graph);
+                    self.children = if engine.user_interface.keyboard_modifiers().alt {
+                        collect_children(selection, graph)
+                    } else {
+                        Vec::new()
+                    };
                 }
             }
         }
@@ -92,7 +131,7 @@ impl InteractionMode for ScaleInteractionMode {
                     let current_scales = selection.local_scales(graph);
                     if current_scales != self.initial_scales {
                         // Commit changes.
-                        let commands = CommandGroup::from(
+                        let mut commands = CommandGroup::from(
                             selection
                                 .nodes()
                                 .iter()
@@ -104,6 +143,19 @@ impl InteractionMode for ScaleInteractionMode {
                                 })
                                 .collect::<Vec<_>>(),
                         );
+                        for child in self.children.iter() {
+                            let transform = graph[child.node].local_transform();
+                            commands.push(SceneCommand::new(MoveNodeCommand::new(
+                                child.node,
+                                child.position,
+                                **transform.position(),
+                            )));
+                            commands.push(SceneCommand::new(ScaleNodeCommand::new(
+                                child.node,
+                                child.scale,
+                                **transform.scale(),
+                            )));
+                        }
                         self.message_sender
                             .send(Message::do_scene_command(commands))
                             .unwrap();
@@ -175,6 +227,20 @@ impl InteractionMode for ScaleInteractionMode {
                     let sz = (initial_scale.z * (1.0 + scale_delta.z)).max(std::f32::EPSILON);
                     transform.set_scale(Vector3::new(sx, sy, sz));
                 }
+
+                // Compensate parent's scale, so children keep their world position and size.
+                // Size is preserved exactly only for children that are not rotated relative
+                // to the parent or if the parent is scaled uniformly.
+                let graph = &mut engine.scenes[editor_scene.scene].graph;
+                for child in self.children.iter() {
+                    let parent = selection.nodes()[child.parent];
+                    let ratio = self.initial_scales[child.parent]
+                        .component_div(&**graph[parent].local_transform().scale());
+                    graph[child.node]
+                        .local_transform_mut()
+                        .set_position(child.position.component_mul(&ratio))
+                        .set_scale(child.scale.component_mul(&ratio));
+                }
             }
         }
     }
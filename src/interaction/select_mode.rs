@@ -6,7 +6,7 @@ use crate::{
     GameEngine, Message,
 };
 use rg3d::{
-    core::{algebra::Vector2, math::aabb::AxisAlignedBoundingBox, pool::Handle},
+    core::{algebra::Vector2, pool::Handle},
     gui::{
         message::{MessageDirection, WidgetMessage},
         UiNode,
@@ -19,7 +19,6 @@ pub struct SelectInteractionMode {
     preview: Handle<UiNode>,
     selection_frame: Handle<UiNode>,
     message_sender: Sender<Message>,
-    stack: Vec<Handle<Node>>,
     click_pos: Vector2<f32>,
 }
 
@@ -33,7 +32,6 @@ impl SelectInteractionMode {
             preview,
             selection_frame,
             message_sender,
-            stack: Vec::new(),
             click_pos: Vector2::default(),
         }
     }
@@ -79,51 +77,23 @@ impl InteractionMode for SelectInteractionMode {
         frame_size: Vector2<f32>,
     ) {
         let scene = &engine.scenes[editor_scene.scene];
-        let camera = scene.graph[editor_scene.camera_controller.camera].as_camera();
         let preview_screen_bounds = engine.user_interface.node(self.preview).screen_bounds();
         let frame_screen_bounds = engine
             .user_interface
             .node(self.selection_frame)
             .screen_bounds();
         let relative_bounds = frame_screen_bounds.translate(-preview_screen_bounds.position);
-        self.stack.clear();
-        self.stack.push(scene.graph.get_root());
-        let mut graph_selection = GraphSelection::default();
-        while let Some(handle) = self.stack.pop() {
-            let node = &scene.graph[handle];
-            if handle == editor_scene.root {
-                continue;
-            }
-            if handle == scene.graph.get_root() {
-                self.stack.extend_from_slice(node.children());
-                continue;
-            }
-            let aabb = match node {
-                Node::Base(_) => AxisAlignedBoundingBox::unit(),
-                Node::Light(_) => AxisAlignedBoundingBox::unit(),
-                Node::Camera(_) => AxisAlignedBoundingBox::unit(),
-                Node::Mesh(mesh) => mesh.bounding_box(),
-                Node::Sprite(_) => AxisAlignedBoundingBox::unit(),
-                Node::Decal(_) => AxisAlignedBoundingBox::unit(),
-                Node::ParticleSystem(_) => AxisAlignedBoundingBox::unit(),
-                Node::Terrain(ref terrain) => terrain.bounding_box(),
-            };
-
-            for screen_corner in aabb
-                .corners()
-                .iter()
-                .filter_map(|&p| camera.project(p + node.global_position(), frame_size))
-            {
-                if relative_bounds.contains(screen_corner) {
-                    graph_selection.insert_or_exclude(handle);
-                    break;
-                }
-            }
-
-            self.stack.extend_from_slice(node.children());
-        }
+        let nodes = editor_scene.camera_controller.pick_in_rect(
+            relative_bounds,
+            &scene.graph,
+            editor_scene.root,
+            frame_size,
+            false,
+            false,
+            |_, _| true,
+        );
 
-        let new_selection = Selection::Graph(graph_selection);
+        let new_selection = Selection::Graph(GraphSelection::from_list(nodes));
 
         if new_selection != editor_scene.selection {
             self.message_sender
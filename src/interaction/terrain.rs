@@ -1,52 +1,71 @@
 use crate::{
+    gui::make_dropdown_list_option,
     interaction::InteractionMode,
-    make_color_material,
+    menu::confirm::ConfirmationDialog,
     scene::{
-        commands::terrain::{ModifyTerrainHeightCommand, ModifyTerrainLayerMaskCommand},
+        commands::terrain::{
+            diff_terrain_regions, AddTerrainLayerCommand, DeleteTerrainLayerCommand,
+            ModifyTerrainHeightCommand, ModifyTerrainLayerMaskCommand, MoveTerrainLayerCommand,
+            RenameTerrainLayerCommand, ResizeTerrainCommand,
+        },
         EditorScene, Selection,
     },
-    settings::Settings,
+    send_sync_message,
+    settings::{terrain::TerrainBrushSettings, Settings},
+    utils::{
+        heightmap::{export_heightmap, import_heightmap, read_height_range},
+        terrain::{height_at, resample_terrain, TerrainSize},
+    },
     GameEngine, Message, MSG_SYNC_FLAG,
 };
-use rg3d::engine::Engine;
 use rg3d::{
     core::{
-        algebra::{Matrix4, Point3, Vector2, Vector3},
+        algebra::{Point3, Vector2, Vector3},
         arrayvec::ArrayVec,
         color::Color,
-        math::vector_to_quat,
         pool::Handle,
     },
+    engine::Engine,
     gui::{
+        border::BorderBuilder,
+        button::ButtonBuilder,
+        check_box::CheckBoxBuilder,
+        decorator::DecoratorBuilder,
+        dropdown_list::DropdownListBuilder,
+        file_browser::{FileBrowserMode, FileSelectorBuilder, Filter},
+        grid::{Column, GridBuilder, Row},
         inspector::{
             editors::{
                 enumeration::EnumPropertyEditorDefinition, PropertyEditorDefinitionContainer,
             },
             Inspector, InspectorBuilder, InspectorContext,
         },
+        list_view::ListViewBuilder,
         message::{
-            FieldKind, InspectorMessage, MessageDirection, UiMessage, UiMessageData, WidgetMessage,
-            WindowMessage,
+            ButtonMessage, CheckBoxMessage, DropdownListMessage, FieldKind, FileSelectorMessage,
+            InspectorMessage, KeyCode, ListViewMessage, MessageDirection, MouseButton,
+            TextBoxMessage, TextMessage, UiMessage, UiMessageData, WidgetMessage, WindowMessage,
         },
+        numeric::{NumericUpDownBuilder, NumericUpDownMessage},
+        text::TextBuilder,
+        text_box::{TextBoxBuilder, TextCommitMode},
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
-        BuildContext, UiNode, UserInterface,
+        BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
+    material::PropertyValue,
+    resource::texture::TextureKind,
     scene::{
-        base::BaseBuilder,
-        graph::Graph,
-        mesh::{
-            surface::{SurfaceBuilder, SurfaceData},
-            MeshBuilder, RenderPath,
-        },
+        debug::{Line, SceneDrawingContext},
         node::Node,
-        terrain::{Brush, BrushMode, BrushShape, Terrain, TerrainRayCastResult},
+        terrain::{Brush, BrushMode, BrushShape, Layer, Terrain, TerrainRayCastResult},
     },
     utils::log::{Log, MessageKind},
 };
 use std::{
+    path::{Path, PathBuf},
     rc::Rc,
-    sync::{mpsc::Sender, Arc, RwLock},
+    sync::mpsc::Sender,
 };
 
 pub struct TerrainInteractionMode {
@@ -57,14 +76,12 @@ pub struct TerrainInteractionMode {
     brush_gizmo: BrushGizmo,
     brush: Brush,
     brush_panel: BrushPanel,
+    // Target height of flatten brush, sampled at the beginning of a stroke.
+    flatten_height: Option<f32>,
 }
 
 impl TerrainInteractionMode {
-    pub fn new(
-        editor_scene: &EditorScene,
-        engine: &mut GameEngine,
-        message_sender: Sender<Message>,
-    ) -> Self {
+    pub fn new(engine: &mut GameEngine, message_sender: Sender<Message>) -> Self {
         let brush = Brush {
             center: Default::default(),
             shape: BrushShape::Circle { radius: 1.0 },
@@ -76,46 +93,315 @@ impl TerrainInteractionMode {
         Self {
             brush_panel,
             heightmaps: Default::default(),
-            brush_gizmo: BrushGizmo::new(editor_scene, engine),
+            brush_gizmo: Default::default(),
             interacting: false,
             message_sender,
             brush,
             masks: Default::default(),
+            flatten_height: None,
+        }
+    }
+}
+
+/// Amount of segments of the outline of a circle brush.
+const CIRCLE_OUTLINE_SEGMENTS: usize = 64;
+
+/// Amount of segments of every side of the outline of a rectangle brush.
+const RECTANGLE_OUTLINE_SEGMENTS: usize = 16;
+
+/// Returns points of closed outline of the brush relative to its center in XZ plane.
+fn brush_outline(shape: &BrushShape) -> Vec<Vector2<f32>> {
+    match *shape {
+        BrushShape::Circle { radius } => (0..CIRCLE_OUTLINE_SEGMENTS)
+            .map(|i| {
+                let angle = i as f32 / CIRCLE_OUTLINE_SEGMENTS as f32 * std::f32::consts::TAU;
+                Vector2::new(angle.cos(), angle.sin()).scale(radius)
+            })
+            .collect(),
+        BrushShape::Rectangle { width, length } => {
+            let (hw, hl) = (width * 0.5, length * 0.5);
+            let corners = [
+                Vector2::new(-hw, -hl),
+                Vector2::new(hw, -hl),
+                Vector2::new(hw, hl),
+                Vector2::new(-hw, hl),
+            ];
+            let mut points = Vec::with_capacity(4 * RECTANGLE_OUTLINE_SEGMENTS);
+            for (i, &begin) in corners.iter().enumerate() {
+                let end = corners[(i + 1) % corners.len()];
+                for k in 0..RECTANGLE_OUTLINE_SEGMENTS {
+                    points.push(begin.lerp(&end, k as f32 / RECTANGLE_OUTLINE_SEGMENTS as f32));
+                }
+            }
+            points
         }
     }
 }
 
+/// Outline of the brush that lies on surface of the terrain under the cursor, so it is
+/// visible where the brush will land before the terrain is modified.
+#[derive(Default)]
 pub struct BrushGizmo {
-    brush: Handle<Node>,
+    visible: bool,
+    /// Terrain under the cursor and position of the brush center in its local coordinates.
+    center: Option<(Handle<Node>, Vector3<f32>)>,
 }
 
 impl BrushGizmo {
-    pub fn new(editor_scene: &EditorScene, engine: &mut GameEngine) -> Self {
-        let scene = &mut engine.scenes[editor_scene.scene];
-        let graph = &mut scene.graph;
-
-        let brush = MeshBuilder::new(
-            BaseBuilder::new()
-                .with_depth_offset(0.01)
-                .with_name("Brush")
-                .with_visibility(false),
-        )
-        .with_render_path(RenderPath::Forward)
-        .with_cast_shadows(false)
-        .with_surfaces(vec![SurfaceBuilder::new(Arc::new(RwLock::new(
-            SurfaceData::make_quad(&Matrix4::identity()),
-        )))
-        .with_material(make_color_material(Color::from_rgba(0, 255, 0, 130)))
-        .build()])
-        .build(graph);
+    pub fn set_visible(&mut self, visibility: bool) {
+        self.visible = visibility;
+    }
 
-        graph.link_nodes(brush, editor_scene.root);
+    fn draw(
+        &self,
+        handle: Handle<Node>,
+        terrain: &Terrain,
+        shape: &BrushShape,
+        ctx: &mut SceneDrawingContext,
+    ) {
+        let center = match self.center {
+            Some((center_terrain, center)) if self.visible && center_terrain == handle => center,
+            _ => return,
+        };
 
-        Self { brush }
+        let transform = terrain.global_transform();
+        // Points are lifted a bit to not be hidden by the surface between samples.
+        let points = brush_outline(shape)
+            .iter()
+            .map(|offset| {
+                let (x, z) = (center.x + offset.x, center.z + offset.y);
+                height_at(terrain, x, z).map(|y| {
+                    transform.transform_point(&Point3::new(x, y, z)).coords
+                        + Vector3::new(0.0, 0.02, 0.0)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (i, begin) in points.iter().enumerate() {
+            // Parts of the outline outside of the terrain are not drawn.
+            if let (Some(begin), Some(end)) = (begin, points[(i + 1) % points.len()]) {
+                ctx.add_line(Line {
+                    begin: *begin,
+                    end,
+                    color: Color::GREEN,
+                });
+            }
+        }
     }
+}
+
+/// Defines how influence of the brush changes from its center to its edges.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum FalloffCurve {
+    Constant,
+    Linear,
+    Smooth,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct BrushFalloff {
+    curve: FalloffCurve,
+    /// Part of the brush size (from its center) that is affected with full strength.
+    hardness: f32,
+}
 
-    pub fn set_visible(&self, graph: &mut Graph, visibility: bool) {
-        graph[self.brush].set_visibility(visibility);
+impl Default for BrushFalloff {
+    fn default() -> Self {
+        Self {
+            curve: FalloffCurve::Constant,
+            hardness: 0.5,
+        }
+    }
+}
+
+impl BrushFalloff {
+    /// Returns influence of the brush at given normalized distance from its center (see
+    /// [`brush_distance`]).
+    fn weight(&self, distance: f32) -> f32 {
+        if distance > 1.0 {
+            return 0.0;
+        }
+
+        let t = if self.hardness >= 1.0 {
+            1.0
+        } else {
+            ((1.0 - distance) / (1.0 - self.hardness)).max(0.0).min(1.0)
+        };
+
+        match self.curve {
+            FalloffCurve::Constant => 1.0,
+            FalloffCurve::Linear => t,
+            FalloffCurve::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Returns distance from brush center to a point normalized by the size of the brush, so
+/// points with distance up to 1.0 are under the brush.
+fn brush_distance(shape: &BrushShape, dx: f32, dz: f32) -> f32 {
+    match *shape {
+        BrushShape::Circle { radius } => (dx * dx + dz * dz).sqrt() / radius,
+        BrushShape::Rectangle { width, length } => {
+            (dx.abs() / (width * 0.5)).max(dz.abs() / (length * 0.5))
+        }
+    }
+}
+
+/// Multiplier applied to brush size by one press of a brush size key. Size changes by the
+/// same fraction of itself, so steps feel alike for small and large brushes.
+const BRUSH_SIZE_STEP: f32 = 1.2;
+
+/// Smallest size a brush can be shrunk to with brush size keys.
+const MIN_BRUSH_SIZE: f32 = 0.05;
+
+fn scale_brush_shape(shape: &mut BrushShape, factor: f32) {
+    match shape {
+        BrushShape::Circle { radius } => *radius = (*radius * factor).max(MIN_BRUSH_SIZE),
+        BrushShape::Rectangle { width, length } => {
+            *width = (*width * factor).max(MIN_BRUSH_SIZE);
+            *length = (*length * factor).max(MIN_BRUSH_SIZE);
+        }
+    }
+}
+
+/// Replaces height of every vertex under the brush with a value returned by `func`, which
+/// gets current height and influence of the brush at the vertex. `center` is in local
+/// coordinates of the terrain. Layer masks are left untouched.
+fn modify_terrain_height<F>(
+    terrain: &mut Terrain,
+    shape: &BrushShape,
+    falloff: &BrushFalloff,
+    center: Vector3<f32>,
+    func: F,
+) where
+    F: Fn(f32, f32) -> f32,
+{
+    for chunk in terrain.chunks_mut() {
+        let origin = chunk.position();
+        let width_points = chunk.width_point_count() as usize;
+        let length_points = chunk.length_point_count() as usize;
+
+        let mut heightmap = chunk.heightmap().to_vec();
+        let mut modified = false;
+        for z in 0..length_points {
+            let kz = z as f32 / (length_points - 1) as f32;
+            for x in 0..width_points {
+                let kx = x as f32 / (width_points - 1) as f32;
+                let dx = origin.x + kx * chunk.width() - center.x;
+                let dz = origin.z + kz * chunk.length() - center.z;
+                let weight = falloff.weight(brush_distance(shape, dx, dz));
+                if weight > 0.0 {
+                    let height = &mut heightmap[z * width_points + x];
+                    *height = func(*height, weight);
+                    modified = true;
+                }
+            }
+        }
+
+        if modified {
+            chunk.set_heightmap(heightmap);
+        }
+    }
+}
+
+/// Moves height of every vertex under the brush towards average height of its neighbours by
+/// `strength` (in [0; 1] range) scaled by influence of the brush. Vertices on chunk borders
+/// are shared with neighbour chunks, so they are averaged only along the border; this way
+/// both copies get the same height and no cracks appear. Corners of chunks have no neighbours
+/// that could be used and are left as is. `center` is in local coordinates of the terrain.
+fn smooth_terrain_height(
+    terrain: &mut Terrain,
+    shape: &BrushShape,
+    falloff: &BrushFalloff,
+    center: Vector3<f32>,
+    strength: f32,
+) {
+    for chunk in terrain.chunks_mut() {
+        let origin = chunk.position();
+        let width_points = chunk.width_point_count() as usize;
+        let length_points = chunk.length_point_count() as usize;
+
+        // Averages are taken from heights before smoothing, so result does not depend on
+        // the order vertices are visited.
+        let source = chunk.heightmap().to_vec();
+        let mut heightmap = source.clone();
+        let mut modified = false;
+        for z in 0..length_points {
+            let kz = z as f32 / (length_points - 1) as f32;
+            let z_border = z == 0 || z == length_points - 1;
+            for x in 0..width_points {
+                let kx = x as f32 / (width_points - 1) as f32;
+                let dx = origin.x + kx * chunk.width() - center.x;
+                let dz = origin.z + kz * chunk.length() - center.z;
+                let weight = falloff.weight(brush_distance(shape, dx, dz));
+                if weight > 0.0 {
+                    let x_border = x == 0 || x == width_points - 1;
+
+                    let mut sum = 0.0;
+                    let mut count = 0;
+                    for nz in z.saturating_sub(1)..=(z + 1).min(length_points - 1) {
+                        for nx in x.saturating_sub(1)..=(x + 1).min(width_points - 1) {
+                            if (x_border && nx != x) || (z_border && nz != z) {
+                                continue;
+                            }
+                            sum += source[nz * width_points + nx];
+                            count += 1;
+                        }
+                    }
+                    let average = sum / count as f32;
+
+                    let height = &mut heightmap[z * width_points + x];
+                    *height += (average - *height) * strength * weight;
+                    modified = true;
+                }
+            }
+        }
+
+        if modified {
+            chunk.set_heightmap(heightmap);
+        }
+    }
+}
+
+/// Adds `alpha` to the mask of given layer under the brush, negative alpha erases the layer.
+/// Mask values are kept in [0; 1] range. `center` is in local coordinates of the terrain.
+fn paint_layer_mask(
+    terrain: &Terrain,
+    shape: &BrushShape,
+    falloff: &BrushFalloff,
+    center: Vector3<f32>,
+    layer: usize,
+    alpha: f32,
+) {
+    let layer = match terrain.layers().get(layer) {
+        Some(layer) => layer,
+        None => return,
+    };
+
+    for (chunk, mask) in terrain.chunks_ref().iter().zip(layer.chunk_masks()) {
+        let origin = chunk.position();
+
+        let mut data = mask.data_ref();
+        let (mask_width, mask_height) = match data.kind() {
+            TextureKind::Rectangle { width, height } => (width as usize, height as usize),
+            _ => continue,
+        };
+
+        let pixels = data.modify().data_mut();
+        for z in 0..mask_height {
+            let kz = z as f32 / mask_height as f32;
+            for x in 0..mask_width {
+                let kx = x as f32 / mask_width as f32;
+                let dx = origin.x + kx * chunk.width() - center.x;
+                let dz = origin.z + kz * chunk.length() - center.z;
+                let weight = falloff.weight(brush_distance(shape, dx, dz));
+                if weight > 0.0 {
+                    let pixel = &mut pixels[z * mask_width + x];
+                    let value = (*pixel as f32 / 255.0 + alpha * weight).clamp(0.0, 1.0);
+                    *pixel = (value * 255.0).round() as u8;
+                }
+            }
+        }
     }
 }
 
@@ -150,6 +436,10 @@ impl InteractionMode for TerrainInteractionMode {
                                 .collect();
                         }
                         BrushMode::DrawOnMask { layer, .. } => {
+                            // Every layer could be removed, there is nothing to draw on then.
+                            if layer >= terrain.layers().len() {
+                                return;
+                            }
                             self.masks = copy_layer_masks(terrain, layer);
                         }
                     }
@@ -174,39 +464,43 @@ impl InteractionMode for TerrainInteractionMode {
 
                 if let Node::Terrain(terrain) = &graph[handle] {
                     if self.interacting {
-                        let new_heightmaps = terrain
-                            .chunks_ref()
-                            .iter()
-                            .map(|c| c.heightmap().to_vec())
-                            .collect();
-
+                        // Snapshots taken on mouse down are only needed to find modified
+                        // regions, commands store just the changed samples.
                         match self.brush.mode {
                             BrushMode::ModifyHeightMap { .. } => {
-                                self.message_sender
-                                    .send(Message::do_scene_command(
-                                        ModifyTerrainHeightCommand::new(
-                                            handle,
-                                            std::mem::take(&mut self.heightmaps),
-                                            new_heightmaps,
-                                        ),
-                                    ))
-                                    .unwrap();
+                                let regions = diff_terrain_regions(
+                                    &std::mem::take(&mut self.heightmaps),
+                                    terrain.chunks_ref().iter().map(|c| c.heightmap()),
+                                );
+                                if !regions.is_empty() {
+                                    self.message_sender
+                                        .send(Message::do_scene_command(
+                                            ModifyTerrainHeightCommand::new(handle, regions),
+                                        ))
+                                        .unwrap();
+                                }
                             }
                             BrushMode::DrawOnMask { layer, .. } => {
-                                self.message_sender
-                                    .send(Message::do_scene_command(
-                                        ModifyTerrainLayerMaskCommand::new(
-                                            handle,
-                                            std::mem::take(&mut self.masks),
-                                            copy_layer_masks(terrain, layer),
-                                            layer,
-                                        ),
-                                    ))
-                                    .unwrap();
+                                let regions = diff_terrain_regions(
+                                    &std::mem::take(&mut self.masks),
+                                    copy_layer_masks(terrain, layer)
+                                        .iter()
+                                        .map(|m| m.as_slice()),
+                                );
+                                if !regions.is_empty() {
+                                    self.message_sender
+                                        .send(Message::do_scene_command(
+                                            ModifyTerrainLayerMaskCommand::new(
+                                                handle, regions, layer,
+                                            ),
+                                        ))
+                                        .unwrap();
+                                }
                             }
                         }
 
                         self.interacting = false;
+                        self.flatten_height = None;
                     }
                 }
             }
@@ -258,21 +552,63 @@ impl InteractionMode for TerrainInteractionMode {
                             }
 
                             if self.interacting {
-                                terrain.draw(&brush_copy);
-                            }
-
-                            let scale = match self.brush.shape {
-                                BrushShape::Circle { radius } => Vector3::new(radius, 1.0, radius),
-                                BrushShape::Rectangle { width, length } => {
-                                    Vector3::new(width, 1.0, length)
+                                let shape = &self.brush.shape;
+                                let falloff = &self.brush_panel.falloff;
+                                if let Some(strength) =
+                                    self.brush_panel.smooth_strength(&self.brush)
+                                {
+                                    smooth_terrain_height(
+                                        terrain,
+                                        shape,
+                                        falloff,
+                                        closest.position,
+                                        strength,
+                                    );
+                                } else {
+                                    match (
+                                        self.brush_panel.flatten_strength(&self.brush),
+                                        &brush_copy.mode,
+                                    ) {
+                                        (Some(strength), _) => {
+                                            let target = *self
+                                                .flatten_height
+                                                .get_or_insert(closest.position.y);
+                                            modify_terrain_height(
+                                                terrain,
+                                                shape,
+                                                falloff,
+                                                closest.position,
+                                                |height, weight| {
+                                                    height + (target - height) * strength * weight
+                                                },
+                                            );
+                                        }
+                                        (None, &BrushMode::ModifyHeightMap { amount }) => {
+                                            modify_terrain_height(
+                                                terrain,
+                                                shape,
+                                                falloff,
+                                                closest.position,
+                                                |height, weight| height + amount * weight,
+                                            );
+                                        }
+                                        (None, &BrushMode::DrawOnMask { layer, alpha }) => {
+                                            paint_layer_mask(
+                                                terrain,
+                                                shape,
+                                                falloff,
+                                                closest.position,
+                                                layer,
+                                                alpha,
+                                            );
+                                        }
+                                    }
                                 }
-                            };
+                            }
 
-                            graph[self.brush_gizmo.brush]
-                                .local_transform_mut()
-                                .set_position(global_position)
-                                .set_scale(scale)
-                                .set_rotation(vector_to_quat(closest.normal));
+                            self.brush_gizmo.center = Some((handle, closest.position));
+                        } else {
+                            self.brush_gizmo.center = None;
                         }
                     }
                 }
@@ -280,12 +616,42 @@ impl InteractionMode for TerrainInteractionMode {
         }
     }
 
-    fn activate(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
-        self.brush_gizmo
-            .set_visible(&mut engine.scenes[editor_scene.scene].graph, true);
+    fn update(
+        &mut self,
+        editor_scene: &mut EditorScene,
+        _camera: Handle<Node>,
+        engine: &mut GameEngine,
+    ) {
+        if let Selection::Graph(selection) = &editor_scene.selection {
+            if selection.is_single_selection() {
+                let scene = &mut engine.scenes[editor_scene.scene];
+                let handle = selection.nodes()[0];
+                if let Node::Terrain(terrain) = &scene.graph[handle] {
+                    self.brush_panel.sync_layers(
+                        handle,
+                        terrain,
+                        editor_scene,
+                        &mut engine.user_interface,
+                    );
+                    self.brush_panel
+                        .sync_size(handle, terrain, &engine.user_interface);
+                    self.brush_gizmo.draw(
+                        handle,
+                        terrain,
+                        &self.brush.shape,
+                        &mut scene.drawing_context,
+                    );
+                }
+            }
+        }
+    }
+
+    fn activate(&mut self, _editor_scene: &EditorScene, engine: &mut GameEngine) {
+        self.brush_gizmo.set_visible(true);
 
         self.brush_panel
             .sync_to_model(&mut engine.user_interface, &self.brush);
+        self.brush_panel.sync_layer_buttons(&engine.user_interface);
 
         engine.user_interface.send_message(WindowMessage::open(
             self.brush_panel.window,
@@ -294,9 +660,8 @@ impl InteractionMode for TerrainInteractionMode {
         ));
     }
 
-    fn deactivate(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
-        self.brush_gizmo
-            .set_visible(&mut engine.scenes[editor_scene.scene].graph, false);
+    fn deactivate(&mut self, _editor_scene: &EditorScene, engine: &mut GameEngine) {
+        self.brush_gizmo.set_visible(false);
 
         engine.user_interface.send_message(WindowMessage::close(
             self.brush_panel.window,
@@ -304,11 +669,33 @@ impl InteractionMode for TerrainInteractionMode {
         ));
     }
 
+    fn on_key_down(
+        &mut self,
+        key: KeyCode,
+        _editor_scene: &mut EditorScene,
+        engine: &mut GameEngine,
+        settings: &Settings,
+    ) {
+        let shortcuts = &settings.brush_size_shortcuts;
+        let factor = if shortcuts.decrease.matches(key) {
+            1.0 / BRUSH_SIZE_STEP
+        } else if shortcuts.increase.matches(key) {
+            BRUSH_SIZE_STEP
+        } else {
+            return;
+        };
+
+        scale_brush_shape(&mut self.brush.shape, factor);
+        self.brush_panel
+            .sync_to_model(&mut engine.user_interface, &self.brush);
+    }
+
     fn handle_ui_message(
         &mut self,
         message: &UiMessage,
         editor_scene: &mut EditorScene,
         engine: &mut GameEngine,
+        settings: &Settings,
     ) {
         if let Selection::Graph(selection) = &editor_scene.selection {
             if selection.is_single_selection() {
@@ -318,22 +705,203 @@ impl InteractionMode for TerrainInteractionMode {
                     selection.nodes()[0],
                     editor_scene,
                     engine,
+                    &self.message_sender,
+                    settings,
                 );
             }
         }
     }
 
     fn on_drop(&mut self, engine: &mut GameEngine) {
-        engine.user_interface.send_message(WidgetMessage::remove(
+        for &window in [
             self.brush_panel.window,
-            MessageDirection::ToWidget,
-        ));
+            self.brush_panel.import_selector,
+            self.brush_panel.export_selector,
+            self.brush_panel.resize_confirmation.window,
+        ]
+        .iter()
+        {
+            engine
+                .user_interface
+                .send_message(WidgetMessage::remove(window, MessageDirection::ToWidget));
+        }
     }
 }
 
 struct BrushPanel {
     window: Handle<UiNode>,
     inspector: Handle<UiNode>,
+    amount_label: Handle<UiNode>,
+    amount: Handle<UiNode>,
+    flatten: Handle<UiNode>,
+    flatten_enabled: bool,
+    flatten_strength: f32,
+    smooth: Handle<UiNode>,
+    smooth_enabled: bool,
+    smooth_strength: f32,
+    falloff_curve: Handle<UiNode>,
+    hardness: Handle<UiNode>,
+    falloff: BrushFalloff,
+    layers: Handle<UiNode>,
+    add_layer: Handle<UiNode>,
+    remove_layer: Handle<UiNode>,
+    move_layer_up: Handle<UiNode>,
+    move_layer_down: Handle<UiNode>,
+    edit_layer_material: Handle<UiNode>,
+    layer_name: Handle<UiNode>,
+    rename_layer: Handle<UiNode>,
+    layer_name_value: String,
+    // Terrain and names of layers the list view was filled with, as well as names given to
+    // the layers by the user.
+    terrain: Handle<Node>,
+    layer_names: Vec<String>,
+    layer_user_names: Vec<String>,
+    current_layer: Option<usize>,
+    min_height: Handle<UiNode>,
+    max_height: Handle<UiNode>,
+    import_heightmap: Handle<UiNode>,
+    import_selector: Handle<UiNode>,
+    export_heightmap: Handle<UiNode>,
+    export_selector: Handle<UiNode>,
+    min_height_value: f32,
+    max_height_value: f32,
+    terrain_width: Handle<UiNode>,
+    terrain_length: Handle<UiNode>,
+    terrain_resolution: Handle<UiNode>,
+    resize: Handle<UiNode>,
+    resize_confirmation: ConfirmationDialog,
+    // "Don't ask again" answer of resize confirmation, kept for the session only.
+    dont_ask_resize: bool,
+    size_value: TerrainSize,
+    // Terrain and its size the size fields were filled with.
+    shown_size: Option<(Handle<Node>, TerrainSize)>,
+}
+
+fn make_heightmap_filter() -> Filter {
+    Filter::new(|p: &Path| {
+        if let Some(ext) = p.extension() {
+            matches!(ext.to_string_lossy().to_lowercase().as_ref(), "png" | "exr")
+        } else {
+            p.is_dir()
+        }
+    })
+}
+
+fn make_height_field(ctx: &mut BuildContext, column: usize, value: f32) -> Handle<UiNode> {
+    NumericUpDownBuilder::new(
+        WidgetBuilder::new()
+            .on_column(column)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_value(value)
+    .build(ctx)
+}
+
+fn make_size_field(ctx: &mut BuildContext, column: usize) -> Handle<UiNode> {
+    NumericUpDownBuilder::new(
+        WidgetBuilder::new()
+            .on_column(column)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_min_value(0.1)
+    .build(ctx)
+}
+
+/// Returns name of the "strength" parameter of given brush mode. Flatten and smooth brushes
+/// use strength instead of amount.
+fn amount_label(mode: &BrushMode, strength: bool) -> &'static str {
+    match mode {
+        BrushMode::ModifyHeightMap { .. } if strength => "Strength",
+        BrushMode::ModifyHeightMap { .. } => "Amount",
+        BrushMode::DrawOnMask { .. } => "Alpha",
+    }
+}
+
+/// Returns allowed range of the "strength" parameter of given brush mode, amount and alpha
+/// ranges are configured in the settings.
+fn amount_range(mode: &BrushMode, strength: bool, settings: &TerrainBrushSettings) -> (f32, f32) {
+    match mode {
+        BrushMode::ModifyHeightMap { .. } if strength => (0.0, 1.0),
+        BrushMode::ModifyHeightMap { .. } => (settings.min_amount, settings.max_amount),
+        BrushMode::DrawOnMask { .. } => (settings.min_alpha, settings.max_alpha),
+    }
+}
+
+/// Inspector creates new shape with default size when user switches between circle and
+/// rectangle, this keeps the size of previous shape instead so the footprint stays the same.
+fn convert_brush_shape(old: &BrushShape, new: BrushShape) -> BrushShape {
+    match (old, new) {
+        (&BrushShape::Circle { radius }, BrushShape::Rectangle { .. }) => BrushShape::Rectangle {
+            width: radius * 2.0,
+            length: radius * 2.0,
+        },
+        (&BrushShape::Rectangle { width, length }, BrushShape::Circle { .. }) => {
+            BrushShape::Circle {
+                radius: width.max(length) * 0.5,
+            }
+        }
+        (_, new) => new,
+    }
+}
+
+fn brush_amount(mode: &BrushMode) -> f32 {
+    match *mode {
+        BrushMode::ModifyHeightMap { amount } => amount,
+        BrushMode::DrawOnMask { alpha, .. } => alpha,
+    }
+}
+
+fn falloff_curve_index(curve: FalloffCurve) -> usize {
+    match curve {
+        FalloffCurve::Constant => 0,
+        FalloffCurve::Linear => 1,
+        FalloffCurve::Smooth => 2,
+    }
+}
+
+fn make_layer_name(index: usize, layer: &Layer, user_name: Option<&str>) -> String {
+    if let Some(user_name) = user_name {
+        return format!("Layer {} - {}", index, user_name);
+    }
+
+    let texture = layer
+        .material
+        .lock()
+        .unwrap()
+        .property_ref("diffuseTexture")
+        .and_then(|p| {
+            if let PropertyValue::Sampler { value, .. } = p {
+                value.clone()
+            } else {
+                None
+            }
+        });
+
+    match texture {
+        Some(texture) => format!(
+            "Layer {} - {}",
+            index,
+            texture
+                .state()
+                .path()
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        ),
+        None => format!("Layer {}", index),
+    }
+}
+
+fn make_layer_item(ctx: &mut BuildContext, text: String) -> Handle<UiNode> {
+    DecoratorBuilder::new(BorderBuilder::new(
+        WidgetBuilder::new().with_height(22.0).with_child(
+            TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .with_text(text)
+                .build(ctx),
+        ),
+    ))
+    .build(ctx)
 }
 
 fn make_brush_mode_enum_property_editor_definition() -> EnumPropertyEditorDefinition<BrushMode> {
@@ -387,19 +955,649 @@ impl BrushPanel {
         );
 
         let inspector;
-        let window = WindowBuilder::new(WidgetBuilder::new().with_width(200.0).with_height(250.0))
+        let amount_label;
+        let amount;
+        let flatten;
+        let smooth;
+        let falloff_curve;
+        let hardness;
+        let layers;
+        let falloff = BrushFalloff::default();
+        let add_layer;
+        let remove_layer;
+        let move_layer_up;
+        let move_layer_down;
+        let edit_layer_material;
+        let layer_name;
+        let rename_layer;
+        let min_height_value = 0.0;
+        let max_height_value = 10.0;
+        let min_height;
+        let max_height;
+        let import_heightmap;
+        let export_heightmap;
+        let terrain_width;
+        let terrain_length;
+        let terrain_resolution;
+        let resize;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(240.0).with_height(610.0))
             .can_close(false)
-            .with_content({
-                inspector = InspectorBuilder::new(WidgetBuilder::new())
-                    .with_context(context)
-                    .build(ctx);
-                inspector
-            })
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            inspector = InspectorBuilder::new(WidgetBuilder::new().on_row(0))
+                                .with_context(context)
+                                .build(ctx);
+                            inspector
+                        })
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_child({
+                                        amount_label = TextBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .with_text(amount_label(&brush.mode, false))
+                                        .build(ctx);
+                                        amount_label
+                                    })
+                                    .with_child({
+                                        amount = NumericUpDownBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_value(brush_amount(&brush.mode))
+                                        .build(ctx);
+                                        amount
+                                    })
+                                    .with_child(
+                                        TextBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_row(1)
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .with_text("Falloff")
+                                        .build(ctx),
+                                    )
+                                    .with_child({
+                                        falloff_curve = DropdownListBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_row(1)
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_items(vec![
+                                            make_dropdown_list_option(ctx, "Constant"),
+                                            make_dropdown_list_option(ctx, "Linear"),
+                                            make_dropdown_list_option(ctx, "Smooth"),
+                                        ])
+                                        .with_selected(falloff_curve_index(falloff.curve))
+                                        .with_close_on_selection(true)
+                                        .build(ctx);
+                                        falloff_curve
+                                    })
+                                    .with_child(
+                                        TextBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_row(2)
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .with_text("Hardness")
+                                        .build(ctx),
+                                    )
+                                    .with_child({
+                                        hardness = NumericUpDownBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_row(2)
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_min_value(0.0)
+                                        .with_max_value(1.0)
+                                        .with_value(falloff.hardness)
+                                        .build(ctx);
+                                        hardness
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_row(Row::stretch())
+                            .add_row(Row::stretch())
+                            .add_column(Column::strict(60.0))
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .with_child({
+                                        flatten = CheckBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_vertical_text_alignment(
+                                                    VerticalAlignment::Center,
+                                                )
+                                                .with_text("Flatten")
+                                                .build(ctx),
+                                        )
+                                        .checked(Some(false))
+                                        .build(ctx);
+                                        flatten
+                                    })
+                                    .with_child({
+                                        smooth = CheckBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_vertical_text_alignment(
+                                                    VerticalAlignment::Center,
+                                                )
+                                                .with_text("Smooth")
+                                                .build(ctx),
+                                        )
+                                        .checked(Some(false))
+                                        .build(ctx);
+                                        smooth
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        )
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .with_text("Layers")
+                            .build(ctx),
+                        )
+                        .with_child({
+                            edit_layer_material = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .with_width(90.0)
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_text("Edit Material...")
+                            .build(ctx);
+                            edit_layer_material
+                        })
+                        .with_child({
+                            layers = ListViewBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(4)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .build(ctx);
+                            layers
+                        })
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(5)
+                                    .with_child({
+                                        layer_name = TextBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text_commit_mode(TextCommitMode::Immediate)
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .build(ctx);
+                                        layer_name
+                                    })
+                                    .with_child({
+                                        rename_layer = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Rename")
+                                        .build(ctx);
+                                        rename_layer
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(60.0))
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(6)
+                                    .with_child({
+                                        add_layer = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Add Layer")
+                                        .build(ctx);
+                                        add_layer
+                                    })
+                                    .with_child({
+                                        remove_layer = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Remove Layer")
+                                        .build(ctx);
+                                        remove_layer
+                                    })
+                                    .with_child({
+                                        move_layer_up = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(2)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Up")
+                                        .build(ctx);
+                                        move_layer_up
+                                    })
+                                    .with_child({
+                                        move_layer_down = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(3)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Down")
+                                        .build(ctx);
+                                        move_layer_down
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(36.0))
+                            .add_column(Column::strict(36.0))
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(7)
+                                    .with_child(
+                                        TextBuilder::new(WidgetBuilder::new().on_column(0))
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text("Min")
+                                            .build(ctx),
+                                    )
+                                    .with_child({
+                                        min_height = make_height_field(ctx, 1, min_height_value);
+                                        min_height
+                                    })
+                                    .with_child(
+                                        TextBuilder::new(WidgetBuilder::new().on_column(2))
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text("Max")
+                                            .build(ctx),
+                                    )
+                                    .with_child({
+                                        max_height = make_height_field(ctx, 3, max_height_value);
+                                        max_height
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::strict(30.0))
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(30.0))
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(8)
+                                    .with_child({
+                                        import_heightmap = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Import...")
+                                        .build(ctx);
+                                        import_heightmap
+                                    })
+                                    .with_child({
+                                        export_heightmap = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Export...")
+                                        .build(ctx);
+                                        export_heightmap
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(9)
+                                    .with_child(
+                                        TextBuilder::new(WidgetBuilder::new().on_column(0))
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text("Width")
+                                            .build(ctx),
+                                    )
+                                    .with_child({
+                                        terrain_width = make_size_field(ctx, 1);
+                                        terrain_width
+                                    })
+                                    .with_child(
+                                        TextBuilder::new(WidgetBuilder::new().on_column(2))
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text("Length")
+                                            .build(ctx),
+                                    )
+                                    .with_child({
+                                        terrain_length = make_size_field(ctx, 3);
+                                        terrain_length
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::strict(40.0))
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(40.0))
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(10)
+                                    .with_child(
+                                        TextBuilder::new(WidgetBuilder::new().on_column(0))
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text("Resolution")
+                                            .build(ctx),
+                                    )
+                                    .with_child({
+                                        terrain_resolution = make_size_field(ctx, 1);
+                                        terrain_resolution
+                                    })
+                                    .with_child({
+                                        resize = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(2)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Resize")
+                                        .build(ctx);
+                                        resize
+                                    }),
+                            )
+                            .add_row(Row::stretch())
+                            .add_column(Column::strict(70.0))
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(60.0))
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::stretch())
+                .add_row(Row::strict(78.0))
+                .add_row(Row::strict(22.0))
+                .add_row(Row::strict(22.0))
+                .add_row(Row::strict(100.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
             .open(false)
             .with_title(WindowTitle::text("Brush Options"))
             .build(ctx);
 
-        Self { window, inspector }
+        let import_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::text("Select a Heightmap To Import")),
+        )
+        .with_filter(make_heightmap_filter())
+        .build(ctx);
+
+        let export_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::text("Export Heightmap As")),
+        )
+        .with_mode(FileBrowserMode::Save {
+            default_file_name: PathBuf::from("heightmap.png"),
+        })
+        .with_path("./")
+        .with_filter(make_heightmap_filter())
+        .build(ctx);
+
+        Self {
+            window,
+            inspector,
+            amount_label,
+            amount,
+            flatten,
+            flatten_enabled: false,
+            flatten_strength: 0.5,
+            smooth,
+            smooth_enabled: false,
+            smooth_strength: 0.5,
+            falloff_curve,
+            hardness,
+            falloff,
+            layers,
+            add_layer,
+            remove_layer,
+            move_layer_up,
+            move_layer_down,
+            edit_layer_material,
+            layer_name,
+            rename_layer,
+            layer_name_value: Default::default(),
+            terrain: Default::default(),
+            layer_names: Default::default(),
+            layer_user_names: Default::default(),
+            current_layer: None,
+            min_height,
+            max_height,
+            import_heightmap,
+            import_selector,
+            export_heightmap,
+            export_selector,
+            min_height_value,
+            max_height_value,
+            terrain_width,
+            terrain_length,
+            terrain_resolution,
+            resize,
+            resize_confirmation: ConfirmationDialog::new(ctx, "Resize Terrain"),
+            dont_ask_resize: false,
+            size_value: TerrainSize {
+                width: 0.0,
+                length: 0.0,
+                resolution: 0.0,
+            },
+            shown_size: None,
+        }
+    }
+
+    /// Refills size fields if they are out of date, for example when other terrain was
+    /// selected or resizing was undone.
+    fn sync_size(&mut self, handle: Handle<Node>, terrain: &Terrain, ui: &UserInterface) {
+        let size = TerrainSize::of(terrain);
+        if self.shown_size == Some((handle, size)) {
+            return;
+        }
+
+        for &(field, value) in &[
+            (self.terrain_width, size.width),
+            (self.terrain_length, size.length),
+            (self.terrain_resolution, size.resolution),
+        ] {
+            send_sync_message(
+                ui,
+                NumericUpDownMessage::value(field, MessageDirection::ToWidget, value),
+            );
+        }
+
+        self.size_value = size;
+        self.shown_size = Some((handle, size));
+    }
+
+    fn resize_terrain(
+        &self,
+        terrain: Handle<Node>,
+        editor_scene: &EditorScene,
+        engine: &Engine,
+        sender: &Sender<Message>,
+    ) {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+        if let Node::Terrain(terrain_ref) = &graph[terrain] {
+            sender
+                .send(Message::do_scene_command(ResizeTerrainCommand::new(
+                    terrain,
+                    resample_terrain(terrain_ref, self.size_value),
+                )))
+                .unwrap();
+        }
+    }
+
+    /// Refills list of layers if the list is out of date, for example when a layer was
+    /// added or renamed, its texture was changed, the command was undone or other terrain
+    /// was selected.
+    fn sync_layers(
+        &mut self,
+        handle: Handle<Node>,
+        terrain: &Terrain,
+        editor_scene: &EditorScene,
+        ui: &mut UserInterface,
+    ) {
+        let names = terrain
+            .layers()
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| make_layer_name(i, layer, editor_scene.terrain_layer_name(layer)))
+            .collect::<Vec<_>>();
+        if self.terrain == handle && self.layer_names == names {
+            return;
+        }
+
+        let items = {
+            let ctx = &mut ui.build_ctx();
+            names
+                .iter()
+                .map(|name| make_layer_item(ctx, name.clone()))
+                .collect::<Vec<_>>()
+        };
+        ui.send_message(ListViewMessage::items(
+            self.layers,
+            MessageDirection::ToWidget,
+            items,
+        ));
+
+        // Keep current layer when possible, so switching between terrains won't reset it.
+        self.current_layer = self.current_layer.filter(|&i| i < names.len());
+        ui.send_message(ListViewMessage::selection(
+            self.layers,
+            MessageDirection::ToWidget,
+            self.current_layer,
+        ));
+
+        self.terrain = handle;
+        self.layer_names = names;
+        self.layer_user_names = terrain
+            .layers()
+            .iter()
+            .map(|layer| {
+                editor_scene
+                    .terrain_layer_name(layer)
+                    .unwrap_or_default()
+                    .to_owned()
+            })
+            .collect();
+
+        self.sync_layer_buttons(ui);
+        self.sync_layer_name(ui);
+    }
+
+    /// Fills name field with the name of current layer.
+    fn sync_layer_name(&mut self, ui: &UserInterface) {
+        let name = self
+            .current_layer
+            .and_then(|i| self.layer_user_names.get(i))
+            .cloned()
+            .unwrap_or_default();
+        send_sync_message(
+            ui,
+            TextBoxMessage::text(self.layer_name, MessageDirection::ToWidget, name.clone()),
+        );
+        self.layer_name_value = name;
+    }
+
+    fn sync_layer_buttons(&self, ui: &UserInterface) {
+        let count = self.layer_names.len();
+        for &(button, enabled) in &[
+            (self.remove_layer, self.current_layer.is_some()),
+            (self.edit_layer_material, self.current_layer.is_some()),
+            (self.layer_name, self.current_layer.is_some()),
+            (self.rename_layer, self.current_layer.is_some()),
+            (
+                self.move_layer_up,
+                self.current_layer.map_or(false, |i| i > 0),
+            ),
+            (
+                self.move_layer_down,
+                self.current_layer.map_or(false, |i| i + 1 < count),
+            ),
+        ] {
+            ui.send_message(WidgetMessage::enabled(
+                button,
+                MessageDirection::ToWidget,
+                enabled,
+            ));
+        }
+    }
+
+    /// Makes given layer current, mask brush starts drawing on it.
+    fn select_layer(&mut self, index: usize, brush: &mut Brush, ui: &mut UserInterface) {
+        self.current_layer = Some(index);
+        self.sync_layer_buttons(ui);
+        self.sync_layer_name(ui);
+        if let BrushMode::DrawOnMask { ref mut layer, .. } = brush.mode {
+            *layer = index;
+            self.sync_to_model(ui, brush);
+        }
     }
 
     fn sync_to_model(&self, ui: &mut UserInterface, brush: &Brush) {
@@ -416,16 +1614,422 @@ impl BrushPanel {
                 format!("Failed to sync BrushPanel's inspector. Reason: {:?}", e),
             )
         }
+
+        self.sync_amount(ui, brush);
+    }
+
+    /// Returns strength of flatten brush, if flattening is enabled for current brush mode.
+    fn flatten_strength(&self, brush: &Brush) -> Option<f32> {
+        match brush.mode {
+            BrushMode::ModifyHeightMap { .. } if self.flatten_enabled => {
+                Some(self.flatten_strength)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns strength of smooth brush, if smoothing is enabled for current brush mode.
+    fn smooth_strength(&self, brush: &Brush) -> Option<f32> {
+        match brush.mode {
+            BrushMode::ModifyHeightMap { .. } if self.smooth_enabled => Some(self.smooth_strength),
+            _ => None,
+        }
+    }
+
+    fn uses_strength(&self) -> bool {
+        self.flatten_enabled || self.smooth_enabled
+    }
+
+    fn sync_amount(&self, ui: &UserInterface, brush: &Brush) {
+        ui.send_message(TextMessage::text(
+            self.amount_label,
+            MessageDirection::ToWidget,
+            amount_label(&brush.mode, self.uses_strength()).to_owned(),
+        ));
+        send_sync_message(
+            ui,
+            NumericUpDownMessage::value(
+                self.amount,
+                MessageDirection::ToWidget,
+                self.flatten_strength(brush)
+                    .or_else(|| self.smooth_strength(brush))
+                    .unwrap_or_else(|| brush_amount(&brush.mode)),
+            ),
+        );
     }
 
     fn handle_ui_message(
-        &self,
+        &mut self,
         message: &UiMessage,
         brush: &mut Brush,
         terrain: Handle<Node>,
         editor_scene: &EditorScene,
-        engine: &Engine,
+        engine: &mut Engine,
+        sender: &Sender<Message>,
+        settings: &Settings,
     ) -> Option<()> {
+        if let Some(true) = self.resize_confirmation.handle_ui_message(
+            message,
+            &engine.user_interface,
+            &mut self.dont_ask_resize,
+        ) {
+            self.resize_terrain(terrain, editor_scene, engine, sender);
+        }
+
+        match message.data() {
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.add_layer =>
+            {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let Node::Terrain(terrain_ref) = &graph[terrain] {
+                    // New layer will be added to the end.
+                    let index = terrain_ref.layers().len();
+                    sender
+                        .send(Message::do_scene_command(AddTerrainLayerCommand::new(
+                            terrain, graph,
+                        )))
+                        .unwrap();
+                    self.select_layer(index, brush, &mut engine.user_interface);
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.remove_layer =>
+            {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let (Node::Terrain(terrain_ref), Some(index)) =
+                    (&graph[terrain], self.current_layer)
+                {
+                    let count = terrain_ref.layers().len();
+                    if index < count {
+                        sender
+                            .send(Message::do_scene_command(DeleteTerrainLayerCommand::new(
+                                terrain, index,
+                            )))
+                            .unwrap();
+
+                        // Keep selection on the next layer, or on the previous one if the
+                        // last layer was removed.
+                        if count > 1 {
+                            self.select_layer(
+                                index.min(count - 2),
+                                brush,
+                                &mut engine.user_interface,
+                            );
+                        } else {
+                            self.current_layer = None;
+                            self.sync_layer_buttons(&engine.user_interface);
+                        }
+                    }
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.edit_layer_material =>
+            {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let (Node::Terrain(terrain_ref), Some(index)) =
+                    (&graph[terrain], self.current_layer)
+                {
+                    if let Some(layer) = terrain_ref.layers().get(index) {
+                        // Material is shared, so changes made in the editor are visible on
+                        // the terrain right away.
+                        sender
+                            .send(Message::OpenMaterialEditor(layer.material.clone()))
+                            .unwrap();
+                    }
+                }
+            }
+            UiMessageData::TextBox(TextBoxMessage::Text(text))
+                if message.destination() == self.layer_name
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                self.layer_name_value = text.clone();
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.rename_layer =>
+            {
+                let name = self.layer_name_value.trim().to_owned();
+                if let Some(index) = self.current_layer {
+                    if self.layer_user_names.get(index) != Some(&name) {
+                        sender
+                            .send(Message::do_scene_command(RenameTerrainLayerCommand::new(
+                                terrain, index, name,
+                            )))
+                            .unwrap();
+                    }
+                }
+            }
+            UiMessageData::Widget(WidgetMessage::DoubleClick {
+                button: MouseButton::Left,
+            }) if engine
+                .user_interface
+                .is_node_child_of(message.destination(), self.layers) =>
+            {
+                // Double click on a layer starts renaming it.
+                engine.user_interface.send_message(WidgetMessage::focus(
+                    self.layer_name,
+                    MessageDirection::ToWidget,
+                ));
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.move_layer_up
+                    || message.destination() == self.move_layer_down =>
+            {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let (Node::Terrain(terrain_ref), Some(index)) =
+                    (&graph[terrain], self.current_layer)
+                {
+                    let new_index = if message.destination() == self.move_layer_up {
+                        index.checked_sub(1)
+                    } else {
+                        Some(index + 1).filter(|&i| i < terrain_ref.layers().len())
+                    };
+
+                    if let Some(new_index) = new_index {
+                        sender
+                            .send(Message::do_scene_command(MoveTerrainLayerCommand::new(
+                                terrain, index, new_index,
+                            )))
+                            .unwrap();
+                        self.select_layer(new_index, brush, &mut engine.user_interface);
+                    }
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.import_heightmap =>
+            {
+                engine
+                    .user_interface
+                    .send_message(WindowMessage::open_modal(
+                        self.import_selector,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.export_heightmap =>
+            {
+                engine
+                    .user_interface
+                    .send_message(WindowMessage::open_modal(
+                        self.export_selector,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+            }
+            UiMessageData::FileSelector(FileSelectorMessage::Commit(path))
+                if message.destination() == self.export_selector =>
+            {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let Node::Terrain(terrain_ref) = &graph[terrain] {
+                    let text = match export_heightmap(terrain_ref, path) {
+                        Ok((min, max)) => format!(
+                            "Heightmap was exported to {}, height range is [{}; {}].",
+                            path.display(),
+                            min,
+                            max
+                        ),
+                        Err(e) => format!(
+                            "Unable to export heightmap to {}. Reason: {:?}",
+                            path.display(),
+                            e
+                        ),
+                    };
+                    sender.send(Message::Log(text)).unwrap();
+                }
+            }
+            UiMessageData::FileSelector(FileSelectorMessage::Commit(path))
+                if message.destination() == self.import_selector =>
+            {
+                // Restore scale of heightmaps exported by the editor.
+                if let Some((min, max)) = read_height_range(path) {
+                    self.min_height_value = min;
+                    self.max_height_value = max;
+                    let ui = &engine.user_interface;
+                    send_sync_message(
+                        ui,
+                        NumericUpDownMessage::value(
+                            self.min_height,
+                            MessageDirection::ToWidget,
+                            min,
+                        ),
+                    );
+                    send_sync_message(
+                        ui,
+                        NumericUpDownMessage::value(
+                            self.max_height,
+                            MessageDirection::ToWidget,
+                            max,
+                        ),
+                    );
+                }
+
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let Node::Terrain(terrain_ref) = &graph[terrain] {
+                    match import_heightmap(
+                        terrain_ref,
+                        path,
+                        self.min_height_value,
+                        self.max_height_value,
+                    ) {
+                        Ok(heightmaps) => {
+                            let old_heightmaps = terrain_ref
+                                .chunks_ref()
+                                .iter()
+                                .map(|c| c.heightmap().to_vec())
+                                .collect::<Vec<_>>();
+                            let regions = diff_terrain_regions(
+                                &old_heightmaps,
+                                heightmaps.iter().map(|h| h.as_slice()),
+                            );
+                            if !regions.is_empty() {
+                                sender
+                                    .send(Message::do_scene_command(
+                                        ModifyTerrainHeightCommand::new(terrain, regions),
+                                    ))
+                                    .unwrap();
+                            }
+                        }
+                        Err(e) => sender
+                            .send(Message::Log(format!(
+                                "Unable to import heightmap {}. Reason: {:?}",
+                                path.display(),
+                                e
+                            )))
+                            .unwrap(),
+                    }
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click) if message.destination() == self.resize => {
+                let graph = &engine.scenes[editor_scene.scene].graph;
+                if let Node::Terrain(terrain_ref) = &graph[terrain] {
+                    let size = TerrainSize::of(terrain_ref);
+                    if self.size_value == size {
+                        return None;
+                    }
+
+                    if !self.size_value.is_coarser_than(&size)
+                        || self.resize_confirmation.ask(
+                            &engine.user_interface,
+                            "Terrain will have fewer height map samples, fine details will be \
+                            lost. Resize anyway?"
+                                .to_owned(),
+                            self.dont_ask_resize,
+                        )
+                    {
+                        self.resize_terrain(terrain, editor_scene, engine, sender);
+                    }
+                }
+            }
+            UiMessageData::User(msg)
+                if (message.destination() == self.terrain_width
+                    || message.destination() == self.terrain_length
+                    || message.destination() == self.terrain_resolution)
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.destination() == self.terrain_width {
+                        self.size_value.width = value;
+                    } else if message.destination() == self.terrain_length {
+                        self.size_value.length = value;
+                    } else {
+                        self.size_value.resolution = value;
+                    }
+                }
+            }
+            UiMessageData::User(msg)
+                if (message.destination() == self.min_height
+                    || message.destination() == self.max_height)
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.destination() == self.min_height {
+                        self.min_height_value = value;
+                    } else {
+                        self.max_height_value = value;
+                    }
+                }
+            }
+            UiMessageData::User(msg)
+                if message.destination() == self.amount
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    let (min, max) =
+                        amount_range(&brush.mode, self.uses_strength(), &settings.terrain_brush);
+                    let value = value.max(min).min(max);
+                    match brush.mode {
+                        BrushMode::ModifyHeightMap { .. } if self.flatten_enabled => {
+                            self.flatten_strength = value
+                        }
+                        BrushMode::ModifyHeightMap { .. } if self.smooth_enabled => {
+                            self.smooth_strength = value
+                        }
+                        BrushMode::ModifyHeightMap { ref mut amount } => *amount = value,
+                        BrushMode::DrawOnMask { ref mut alpha, .. } => *alpha = value,
+                    }
+                    // Sync inspector and the field itself, because value could be clamped.
+                    self.sync_to_model(&mut engine.user_interface, brush);
+                }
+            }
+            UiMessageData::User(msg)
+                if message.destination() == self.hardness
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    self.falloff.hardness = value.max(0.0).min(1.0);
+                }
+            }
+            &UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index)))
+                if message.destination() == self.falloff_curve =>
+            {
+                self.falloff.curve = match index {
+                    0 => FalloffCurve::Constant,
+                    1 => FalloffCurve::Linear,
+                    2 => FalloffCurve::Smooth,
+                    _ => unreachable!(),
+                };
+            }
+            &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value)))
+                if message.destination() == self.flatten
+                    || message.destination() == self.smooth =>
+            {
+                // Flattening and smoothing are exclusive, enabling one disables the other.
+                let other = if message.destination() == self.flatten {
+                    self.flatten_enabled = value;
+                    self.smooth_enabled &= !value;
+                    self.smooth
+                } else {
+                    self.smooth_enabled = value;
+                    self.flatten_enabled &= !value;
+                    self.flatten
+                };
+                if value {
+                    send_sync_message(
+                        &engine.user_interface,
+                        CheckBoxMessage::checked(other, MessageDirection::ToWidget, Some(false)),
+                    );
+                }
+                self.sync_amount(&engine.user_interface, brush);
+            }
+            &UiMessageData::ListView(ListViewMessage::SelectionChanged(Some(index)))
+                if message.destination() == self.layers
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if self.current_layer != Some(index) {
+                    self.select_layer(index, brush, &mut engine.user_interface);
+                }
+            }
+            _ => (),
+        }
+
         if message.destination() == self.inspector
             && message.direction() == MessageDirection::FromWidget
         {
@@ -434,7 +2038,11 @@ impl BrushPanel {
                 match msg.value {
                     FieldKind::Object(ref args) => match msg.name.as_ref() {
                         Brush::SHAPE => {
-                            brush.shape = args.cast_value().cloned()?;
+                            brush.shape = convert_brush_shape(
+                                &brush.shape,
+                                args.cast_value::<BrushShape>().cloned()?,
+                            );
+                            self.sync_to_model(&mut engine.user_interface, brush);
                         }
                         Brush::MODE => {
                             brush.mode = args.cast_value().cloned()?;
@@ -505,6 +2113,9 @@ impl BrushPanel {
                     }
                     _ => {}
                 }
+
+                // Mode could be changed, so the label and the range must be updated too.
+                self.sync_amount(&engine.user_interface, brush);
             }
         }
         Some(())
@@ -28,12 +28,18 @@ pub struct LightPanel {
 }
 
 impl LightPanel {
+    pub const WIDTH: f32 = 300.0;
+    pub const HEIGHT: f32 = 400.0;
+
     pub fn new(engine: &mut GameEngine) -> Self {
         let generate;
         let nud_texels_per_unit;
         let nud_spacing;
         let ctx = &mut engine.user_interface.build_ctx();
-        let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+        let window_widget = WidgetBuilder::new()
+            .with_width(Self::WIDTH)
+            .with_height(Self::HEIGHT);
+        let window = WindowBuilder::new(window_widget)
             .with_title(WindowTitle::Text("Light Settings".to_owned()))
             .open(false)
             .with_content(
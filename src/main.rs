@@ -12,9 +12,12 @@ extern crate lazy_static;
 extern crate directories;
 
 pub mod asset;
+pub mod bvh;
 pub mod camera;
+pub mod camera_bookmarks;
 pub mod command;
 pub mod configurator;
+pub mod gamepad;
 pub mod gui;
 pub mod inspector;
 pub mod interaction;
@@ -28,6 +31,7 @@ pub mod preview;
 pub mod project_dirs;
 pub mod scene;
 pub mod settings;
+pub mod toolbar;
 pub mod utils;
 pub mod world;
 
@@ -35,9 +39,11 @@ use crate::inspector::Inspector;
 use crate::menu::Panels;
 use crate::{
     asset::{AssetBrowser, AssetItem, AssetKind},
-    camera::CameraController,
+    camera::{CameraController, ViewPreset},
+    camera_bookmarks::CameraBookmarksPanel,
     command::{panel::CommandStackViewer, Command, CommandStack},
     configurator::Configurator,
+    gamepad::GamepadInput,
     gui::make_dropdown_list_option,
     interaction::{
         move_mode::MoveInteractionMode,
@@ -53,27 +59,45 @@ use crate::{
     },
     light::LightPanel,
     log::Log,
-    material::MaterialEditor,
-    menu::{Menu, MenuContext},
+    material::{MaterialEditor, MaterialSlots},
+    menu::{
+        confirm::ConfirmationDialog,
+        create::{make_cube, make_point_light, make_sphere},
+        edit::make_align_to_surface_command,
+        Menu, MenuContext,
+    },
     overlay::OverlayRenderPass,
     physics::Physics,
     scene::{
         commands::{
-            graph::LoadModelCommand, make_delete_selection_command, mesh::SetMeshTextureCommand,
-            particle_system::SetParticleSystemTextureCommand, sound::DeleteSoundSourceCommand,
-            sprite::SetSpriteTextureCommand, ChangeSelectionCommand, CommandGroup, PasteCommand,
-            SceneCommand, SceneContext,
+            cut_selection,
+            graph::{find_non_finite_transforms, AddNodeCommand, LoadModelCommand},
+            make_delete_selection_command, make_deselect_all_command,
+            make_duplicate_selection_command, make_group_selection_command,
+            make_select_all_command,
+            mesh::SetMeshTextureCommand,
+            particle_system::SetParticleSystemTextureCommand,
+            sound::DeleteSoundSourceCommand,
+            sprite::SetSpriteTextureCommand,
+            ChangeSelectionCommand, CommandGroup, PasteCommand, SceneCommand, SceneContext,
         },
         EditorScene, Selection,
     },
-    settings::{Settings, SettingsSectionKind},
-    utils::path_fixer::PathFixer,
+    settings::{
+        asset_browser::AssetDoubleClickAction, autosave::AutoSaveSettings, toolbar::ToolbarAction,
+        Settings, SettingsSectionKind,
+    },
+    toolbar::QuickToolbar,
+    utils::{
+        name_replacer::NameReplacer, open_in_external_application, path_fixer::PathFixer,
+        transform_randomizer::TransformRandomizer,
+    },
     world::WorldViewer,
 };
 use rg3d::gui::formatted_text::WrapMode;
 use rg3d::{
     core::{
-        algebra::{Point3, Vector2},
+        algebra::{Matrix4, Point3, Vector2},
         color::Color,
         math::aabb::AxisAlignedBoundingBox,
         pool::{Handle, Pool},
@@ -100,8 +124,8 @@ use rg3d::{
             ButtonMessage, FileSelectorMessage, ImageMessage, KeyCode, MessageBoxMessage,
             MessageDirection, MouseButton, UiMessageData, WidgetMessage, WindowMessage,
         },
-        message::{DropdownListMessage, TextBoxMessage},
-        messagebox::{MessageBoxBuilder, MessageBoxButtons, MessageBoxResult},
+        message::{DropdownListMessage, TextBoxMessage, TextMessage},
+        messagebox::{MessageBoxBuilder, MessageBoxButtons},
         stack_panel::StackPanelBuilder,
         text::TextBuilder,
         text_box::TextBoxBuilder,
@@ -228,6 +252,9 @@ pub struct ScenePreview {
     last_mouse_pos: Option<Vector2<f32>>,
     click_mouse_pos: Option<Vector2<f32>>,
     selection_frame: Handle<UiNode>,
+    // Transient readout of fly speed, hidden when timer runs out.
+    speed_label: Handle<UiNode>,
+    speed_label_timer: f32,
     // Side bar stuff
     select_mode: Handle<UiNode>,
     move_mode: Handle<UiNode>,
@@ -235,6 +262,7 @@ pub struct ScenePreview {
     scale_mode: Handle<UiNode>,
     navmesh_mode: Handle<UiNode>,
     terrain_mode: Handle<UiNode>,
+    pub toolbar: QuickToolbar,
     sender: Sender<Message>,
 }
 
@@ -581,16 +609,19 @@ impl ScenePreview {
         let navmesh_mode;
         let terrain_mode;
         let selection_frame;
+        let speed_label;
+        let toolbar = QuickToolbar::new(ctx, 0, 1);
         let window = WindowBuilder::new(WidgetBuilder::new())
             .can_close(false)
             .can_minimize(false)
             .with_content(
                 GridBuilder::new(
                     WidgetBuilder::new()
+                        .with_child(toolbar.panel)
                         .with_child({
                             frame = ImageBuilder::new(
                                 WidgetBuilder::new()
-                                    .on_row(0)
+                                    .on_row(1)
                                     .on_column(1)
                                     .with_allow_drop(true),
                             )
@@ -599,26 +630,44 @@ impl ScenePreview {
                             frame
                         })
                         .with_child(
-                            CanvasBuilder::new(WidgetBuilder::new().on_column(1).with_child({
-                                selection_frame = BorderBuilder::new(
-                                    WidgetBuilder::new()
-                                        .with_visibility(false)
-                                        .with_background(Brush::Solid(Color::from_rgba(
-                                            255, 255, 255, 40,
-                                        )))
-                                        .with_foreground(Brush::Solid(Color::opaque(0, 255, 0))),
-                                )
-                                .with_stroke_thickness(Thickness::uniform(1.0))
-                                .build(ctx);
-                                selection_frame
-                            }))
+                            CanvasBuilder::new(
+                                WidgetBuilder::new().on_row(1).on_column(1).with_child({
+                                    selection_frame = BorderBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_visibility(false)
+                                            .with_background(Brush::Solid(Color::from_rgba(
+                                                255, 255, 255, 40,
+                                            )))
+                                            .with_foreground(Brush::Solid(Color::opaque(
+                                                0, 255, 0,
+                                            ))),
+                                    )
+                                    .with_stroke_thickness(Thickness::uniform(1.0))
+                                    .build(ctx);
+                                    selection_frame
+                                }),
+                            )
                             .build(ctx),
                         )
+                        .with_child({
+                            speed_label = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(1)
+                                    .with_visibility(false)
+                                    .with_hit_test_visibility(false)
+                                    .with_horizontal_alignment(HorizontalAlignment::Left)
+                                    .with_vertical_alignment(VerticalAlignment::Top)
+                                    .with_margin(Thickness::uniform(5.0)),
+                            )
+                            .build(ctx);
+                            speed_label
+                        })
                         .with_child(
                             StackPanelBuilder::new(
                                 WidgetBuilder::new()
                                     .with_margin(Thickness::uniform(1.0))
-                                    .on_row(0)
+                                    .on_row(1)
                                     .on_column(0)
                                     .with_child({
                                         select_mode = make_interaction_mode_button(
@@ -672,6 +721,7 @@ impl ScenePreview {
                             .build(ctx),
                         ),
                 )
+                .add_row(Row::strict(26.0))
                 .add_row(Row::stretch())
                 .add_column(Column::auto())
                 .add_column(Column::stretch())
@@ -689,15 +739,48 @@ impl ScenePreview {
             rotate_mode,
             scale_mode,
             selection_frame,
+            speed_label,
+            speed_label_timer: 0.0,
             select_mode,
             navmesh_mode,
             terrain_mode,
+            toolbar,
             click_mouse_pos: None,
         }
     }
 }
 
 impl ScenePreview {
+    /// How long fly speed readout stays visible after the speed was changed, in seconds.
+    const SPEED_LABEL_TIME: f32 = 1.5;
+
+    fn show_fly_speed(&mut self, speed: f32, ui: &UserInterface) {
+        ui.send_message(TextMessage::text(
+            self.speed_label,
+            MessageDirection::ToWidget,
+            format!("Fly Speed: {:.2}", speed),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.speed_label,
+            MessageDirection::ToWidget,
+            true,
+        ));
+        self.speed_label_timer = Self::SPEED_LABEL_TIME;
+    }
+
+    fn update_fly_speed_label(&mut self, ui: &UserInterface, dt: f32) {
+        if self.speed_label_timer > 0.0 {
+            self.speed_label_timer -= dt;
+            if self.speed_label_timer <= 0.0 {
+                ui.send_message(WidgetMessage::visibility(
+                    self.speed_label,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
+            }
+        }
+    }
+
     fn handle_ui_message(&mut self, message: &UiMessage, ui: &UserInterface) {
         scope_profile!();
 
@@ -743,6 +826,21 @@ impl ScenePreview {
     }
 }
 
+/// Action postponed until the user decides what to do with unsaved changes.
+enum PendingAction {
+    Exit,
+    OpenScene(PathBuf),
+}
+
+impl PendingAction {
+    fn into_message(self) -> Message {
+        match self {
+            PendingAction::Exit => Message::Exit { force: true },
+            PendingAction::OpenScene(path) => Message::LoadScene(path),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Message {
     DoSceneCommand(SceneCommand),
@@ -752,17 +850,31 @@ pub enum Message {
     SelectionChanged,
     SyncToModel,
     SaveScene(PathBuf),
+    /// Writes the scene to given path without binding the scene to it.
+    SaveSceneCopy(PathBuf),
     LoadScene(PathBuf),
+    /// Loads a scene, but asks to save unsaved changes of current scene first.
+    OpenScene(PathBuf),
     CloseScene,
     SetInteractionMode(InteractionModeKind),
     Log(String),
-    Configure { working_directory: PathBuf },
+    Configure {
+        working_directory: PathBuf,
+    },
     NewScene,
-    Exit { force: bool },
+    Exit {
+        force: bool,
+    },
     OpenSettings(SettingsSectionKind),
     OpenMaterialEditor(Arc<Mutex<Material>>),
+    /// Opens material editor for several materials, shared properties are edited at once.
+    OpenMaterialEditorForMany(Vec<Arc<Mutex<Material>>>),
     ShowInAssetBrowser(PathBuf),
     SetWorldViewerFilter(String),
+    OpenAsset {
+        path: PathBuf,
+        kind: AssetKind,
+    },
 }
 
 impl Message {
@@ -781,10 +893,10 @@ pub fn make_scene_file_filter() -> Filter {
     })
 }
 
-pub fn make_save_file_selector(ctx: &mut BuildContext) -> Handle<UiNode> {
+pub fn make_save_file_selector(ctx: &mut BuildContext, title: &str) -> Handle<UiNode> {
     FileSelectorBuilder::new(
         WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
-            .with_title(WindowTitle::Text("Save Scene As".into()))
+            .with_title(WindowTitle::Text(title.into()))
             .open(false),
     )
     .with_mode(FileBrowserMode::Save {
@@ -806,9 +918,12 @@ struct Editor {
     root_grid: Handle<UiNode>,
     preview: ScenePreview,
     asset_browser: AssetBrowser,
-    exit_message_box: Handle<UiNode>,
+    exit_confirmation: ConfirmationDialog,
+    open_scene_confirmation: ConfirmationDialog,
+    pending_action: Option<PendingAction>,
     save_file_selector: Handle<UiNode>,
     light_panel: LightPanel,
+    camera_bookmarks: CameraBookmarksPanel,
     menu: Menu,
     exit: bool,
     configurator: Configurator,
@@ -819,8 +934,19 @@ struct Editor {
     settings: Settings,
     model_import_dialog: ModelImportDialog,
     path_fixer: PathFixer,
+    name_replacer: NameReplacer,
+    transform_randomizer: TransformRandomizer,
     material_editor: MaterialEditor,
     inspector: Inspector,
+    material_slots: MaterialSlots,
+    // Seconds since the scene got unsaved changes or since last auto-save.
+    autosave_timer: f32,
+    gamepad: GamepadInput,
+    // Panel windows and tiles they were docked to when the editor was started.
+    docked_panels: Vec<(Handle<UiNode>, Handle<UiNode>)>,
+    // Set when a panel window was opened, closed, moved or resized, the layout is saved on
+    // next update, when the windows have their new positions and sizes.
+    window_layout_changed: bool,
 }
 
 impl Editor {
@@ -851,6 +977,7 @@ impl Editor {
         match Settings::load() {
             Ok(s) => {
                 settings = s;
+                settings.confirmations.start_session();
 
                 println!("Editor settings were loaded successfully!");
 
@@ -875,7 +1002,7 @@ impl Editor {
         }
 
         let preview = ScenePreview::new(engine, message_sender.clone());
-        let asset_browser = AssetBrowser::new(engine);
+        let asset_browser = AssetBrowser::new(engine, message_sender.clone());
         let menu = Menu::new(engine, message_sender.clone(), &settings);
         let light_panel = LightPanel::new(engine);
 
@@ -884,6 +1011,7 @@ impl Editor {
         let world_outliner = WorldViewer::new(ctx, message_sender.clone());
         let command_stack_viewer = CommandStackViewer::new(ctx, message_sender.clone());
         let log = Log::new(ctx);
+        let camera_bookmarks = CameraBookmarksPanel::new(ctx);
         let model_import_dialog = ModelImportDialog::new(ctx);
         let inspector = Inspector::new(ctx, message_sender.clone());
 
@@ -980,18 +1108,10 @@ impl Editor {
         .add_column(Column::stretch())
         .build(ctx);
 
-        let save_file_selector = make_save_file_selector(ctx);
+        let save_file_selector = make_save_file_selector(ctx, "Save Scene As");
 
-        let exit_message_box = MessageBoxBuilder::new(
-            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(100.0))
-                .can_close(false)
-                .can_minimize(false)
-                .open(false)
-                .with_title(WindowTitle::Text("Unsaved changes".to_owned())),
-        )
-        .with_text("There are unsaved changes. Do you wish to save them before exit?")
-        .with_buttons(MessageBoxButtons::YesNoCancel)
-        .build(ctx);
+        let exit_confirmation = ConfirmationDialog::new(ctx, "Unsaved changes");
+        let open_scene_confirmation = ConfirmationDialog::new(ctx, "Unsaved changes");
 
         let validation_message_box = MessageBoxBuilder::new(
             WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
@@ -1004,6 +1124,8 @@ impl Editor {
         .build(ctx);
 
         let path_fixer = PathFixer::new(ctx);
+        let name_replacer = NameReplacer::new(ctx);
+        let transform_randomizer = TransformRandomizer::new(ctx);
 
         let material_editor = MaterialEditor::new(engine);
 
@@ -1021,25 +1143,77 @@ impl Editor {
             menu,
             exit: false,
             asset_browser,
-            exit_message_box,
+            exit_confirmation,
+            open_scene_confirmation,
+            pending_action: None,
             save_file_selector,
             configurator,
             log,
             light_panel,
+            camera_bookmarks,
             command_stack_viewer,
             validation_message_box,
             settings,
             model_import_dialog,
             path_fixer,
+            name_replacer,
+            transform_randomizer,
             material_editor,
             inspector,
+            material_slots: Default::default(),
+            autosave_timer: 0.0,
+            gamepad: GamepadInput::new(),
+            docked_panels: Default::default(),
+            window_layout_changed: false,
         };
 
+        // Remember initial docking of panels, so default layout can be restored later.
+        editor.docked_panels = [
+            editor.inspector.window,
+            editor.asset_browser.window,
+            editor.world_viewer.window,
+            editor.log.window,
+        ]
+        .iter()
+        .map(|&window| (window, engine.user_interface.node(window).parent()))
+        .collect();
+
         editor.set_interaction_mode(Some(InteractionModeKind::Move), engine);
 
+        editor
+            .settings
+            .windows
+            .apply(&editor.panels(), &engine.user_interface);
+
         editor
     }
 
+    fn panels(&self) -> Panels {
+        Panels {
+            inspector_window: self.inspector.window,
+            world_outliner_window: self.world_viewer.window,
+            asset_window: self.asset_browser.window,
+            light_panel: self.light_panel.window,
+            camera_bookmarks: self.camera_bookmarks.window,
+            log_panel: self.log.window,
+            configurator_window: self.configurator.window,
+            path_fixer: self.path_fixer.window,
+            name_replacer: self.name_replacer.window,
+            transform_randomizer: self.transform_randomizer.window,
+            docked_panels: self.docked_panels.clone(),
+        }
+    }
+
+    fn save_window_states(&mut self, engine: &GameEngine) {
+        let panels = self.panels();
+        self.settings
+            .windows
+            .capture(&panels, &engine.user_interface);
+        if let Err(e) = self.settings.save() {
+            println!("Unable to save settings! Reason: {:?}!", e);
+        }
+    }
+
     fn set_scene(&mut self, engine: &mut GameEngine, mut scene: Scene, path: Option<PathBuf>) {
         if let Some(previous_editor_scene) = self.scene.as_ref() {
             engine.scenes.remove(previous_editor_scene.scene);
@@ -1062,7 +1236,7 @@ impl Editor {
         let root = BaseBuilder::new().build(&mut scene.graph);
 
         let graph = &mut scene.graph;
-        let camera_controller = CameraController::new(graph, root);
+        let camera_controller = CameraController::new(graph, root, self.message_sender.clone());
 
         let mut navmeshes = Pool::new();
 
@@ -1096,6 +1270,10 @@ impl Editor {
             scene: engine.scenes.add(scene),
             selection: Default::default(),
             clipboard: Default::default(),
+            has_unsaved_changes: false,
+            has_changes_since_autosave: false,
+            surface_alignment: None,
+            terrain_layer_names: Default::default(),
         };
 
         for mut interaction_mode in self.interaction_modes.drain(..) {
@@ -1129,7 +1307,6 @@ impl Editor {
                 self.message_sender.clone(),
             )),
             Box::new(TerrainInteractionMode::new(
-                &editor_scene,
                 engine,
                 self.message_sender.clone(),
             )),
@@ -1155,6 +1332,72 @@ impl Editor {
         engine.renderer.flush();
     }
 
+    fn open_asset(&mut self, path: PathBuf, kind: AssetKind, engine: &mut GameEngine) {
+        if self.settings.asset_browser.double_click_action == AssetDoubleClickAction::Instantiate
+            && kind == AssetKind::Model
+            && self.scene.is_some()
+        {
+            self.model_import_dialog
+                .open(make_relative_path(&path), &engine.user_interface);
+            return;
+        }
+
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if extension == "rgs" {
+            self.message_sender.send(Message::OpenScene(path)).unwrap();
+        } else if extension == "material" {
+            self.material_editor
+                .load(&path, engine, &self.message_sender);
+
+            engine.user_interface.send_message(WindowMessage::open(
+                self.material_editor.window,
+                MessageDirection::ToWidget,
+                true,
+            ));
+        } else if let Err(e) = open_in_external_application(&path) {
+            self.message_sender
+                .send(Message::Log(format!(
+                    "Unable to open {} in external application. Reason: {:?}",
+                    path.display(),
+                    e
+                )))
+                .unwrap();
+        }
+    }
+
+    fn save_and_perform_pending_action(&mut self, engine: &GameEngine) {
+        if let Some(path) = self.scene.as_ref().and_then(|s| s.path.clone()) {
+            self.message_sender.send(Message::SaveScene(path)).unwrap();
+            if let Some(action) = self.pending_action.take() {
+                self.message_sender.send(action.into_message()).unwrap();
+            }
+        } else {
+            // Scene wasn't saved yet, open Save As dialog, pending action will be performed
+            // once the scene is saved.
+            engine
+                .user_interface
+                .send_message(WindowMessage::open_modal(
+                    self.save_file_selector,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+        }
+    }
+
+    fn add_recent_file(&mut self, path: &Path, engine: &GameEngine) {
+        self.settings.recent.push(path);
+        if let Err(e) = self.settings.save() {
+            println!("Unable to save settings! Reason: {:?}!", e);
+        }
+        self.menu
+            .file_menu
+            .sync_recent_files(&engine.user_interface, &self.settings.recent);
+    }
+
     fn set_interaction_mode(&mut self, mode: Option<InteractionModeKind>, engine: &mut GameEngine) {
         if let Some(editor_scene) = self.scene.as_ref() {
             if self.current_interaction_mode != mode {
@@ -1173,6 +1416,68 @@ impl Editor {
         }
     }
 
+    fn execute_toolbar_action(&mut self, action: ToolbarAction, engine: &mut GameEngine) {
+        let sender = &self.message_sender;
+        match action {
+            ToolbarAction::Save => self.menu.file_menu.save_current_scene(
+                self.scene.as_ref(),
+                &engine.user_interface,
+                sender,
+                &self.settings,
+            ),
+            ToolbarAction::Undo => sender.send(Message::UndoSceneCommand).unwrap(),
+            ToolbarAction::Redo => sender.send(Message::RedoSceneCommand).unwrap(),
+            ToolbarAction::CreateCube => sender
+                .send(Message::do_scene_command(AddNodeCommand::new(make_cube())))
+                .unwrap(),
+            ToolbarAction::CreateSphere => sender
+                .send(Message::do_scene_command(
+                    AddNodeCommand::new(make_sphere()),
+                ))
+                .unwrap(),
+            ToolbarAction::CreatePointLight => sender
+                .send(Message::do_scene_command(AddNodeCommand::new(
+                    make_point_light(),
+                )))
+                .unwrap(),
+            ToolbarAction::SelectMode => sender
+                .send(Message::SetInteractionMode(InteractionModeKind::Select))
+                .unwrap(),
+            ToolbarAction::MoveMode => sender
+                .send(Message::SetInteractionMode(InteractionModeKind::Move))
+                .unwrap(),
+            ToolbarAction::RotateMode => sender
+                .send(Message::SetInteractionMode(InteractionModeKind::Rotate))
+                .unwrap(),
+            ToolbarAction::ScaleMode => sender
+                .send(Message::SetInteractionMode(InteractionModeKind::Scale))
+                .unwrap(),
+            ToolbarAction::ToggleSnapping => {
+                let snapping = &mut self.settings.move_mode_settings.grid_snapping;
+                *snapping = !*snapping;
+                sender
+                    .send(Message::Log(format!(
+                        "Grid snapping is {}.",
+                        if *snapping { "on" } else { "off" }
+                    )))
+                    .unwrap();
+                if let Err(e) = self.settings.save() {
+                    println!("Unable to save settings! Reason: {:?}!", e);
+                }
+                self.menu
+                    .file_menu
+                    .settings
+                    .sync_to_model(&engine.user_interface, &self.settings);
+            }
+            ToolbarAction::LookThrough => {
+                if let Some(editor_scene) = self.scene.as_mut() {
+                    let text = editor_scene.toggle_look_through(engine);
+                    sender.send(Message::Log(text)).unwrap();
+                }
+            }
+        }
+    }
+
     pub fn handle_ui_message(&mut self, message: &UiMessage, engine: &mut GameEngine) {
         scope_profile!();
 
@@ -1181,21 +1486,39 @@ impl Editor {
             return;
         }
 
+        match message.data() {
+            UiMessageData::Window(WindowMessage::Open { .. })
+            | UiMessageData::Window(WindowMessage::Close)
+            | UiMessageData::Window(WindowMessage::MoveEnd)
+            | UiMessageData::Widget(WidgetMessage::MouseUp { .. }) => {
+                let panels = self.panels();
+                if [
+                    panels.inspector_window,
+                    panels.asset_window,
+                    panels.world_outliner_window,
+                    panels.light_panel,
+                    panels.log_panel,
+                ]
+                .iter()
+                .any(|&window| {
+                    message.destination() == window
+                        || engine
+                            .user_interface
+                            .is_node_child_of(message.destination(), window)
+                }) {
+                    self.window_layout_changed = true;
+                }
+            }
+            _ => (),
+        }
+
         self.configurator.handle_ui_message(message, engine);
         self.menu.handle_ui_message(
             message,
             MenuContext {
                 engine,
                 editor_scene: self.scene.as_mut(),
-                panels: Panels {
-                    inspector_window: self.inspector.window,
-                    world_outliner_window: self.world_viewer.window,
-                    asset_window: self.asset_browser.window,
-                    light_panel: self.light_panel.window,
-                    log_panel: self.log.window,
-                    configurator_window: self.configurator.window,
-                    path_fixer: self.path_fixer.window,
-                },
+                panels: self.panels(),
                 settings: &mut self.settings,
             },
         );
@@ -1206,6 +1529,10 @@ impl Editor {
         self.path_fixer
             .handle_ui_message(message, &mut engine.user_interface);
 
+        if let Some(action) = self.preview.toolbar.handle_ui_message(message) {
+            self.execute_toolbar_action(action, engine);
+        }
+
         if let Some(editor_scene) = self.scene.as_mut() {
             self.navmesh_panel.handle_message(
                 message,
@@ -1225,11 +1552,26 @@ impl Editor {
             self.inspector
                 .handle_ui_message(message, editor_scene, engine, &self.message_sender);
 
+            self.name_replacer.handle_ui_message(
+                message,
+                editor_scene,
+                engine,
+                &self.message_sender,
+            );
+
+            self.transform_randomizer.handle_ui_message(
+                message,
+                editor_scene,
+                engine,
+                &self.message_sender,
+            );
+
             if let Some(current_im) = self.current_interaction_mode {
                 self.interaction_modes[current_im as usize].handle_ui_message(
                     message,
                     editor_scene,
                     engine,
+                    &self.settings,
                 );
             }
 
@@ -1239,11 +1581,23 @@ impl Editor {
             self.light_panel
                 .handle_ui_message(message, editor_scene, engine);
 
+            self.camera_bookmarks.handle_ui_message(
+                message,
+                editor_scene,
+                engine,
+                &mut self.settings,
+                &self.message_sender,
+            );
+
             self.preview
                 .handle_ui_message(message, &engine.user_interface);
 
-            self.material_editor
-                .handle_ui_message(message, engine, &self.message_sender);
+            self.material_editor.handle_ui_message(
+                message,
+                editor_scene,
+                engine,
+                &self.message_sender,
+            );
 
             self.model_import_dialog.handle_ui_message(
                 message,
@@ -1257,12 +1611,95 @@ impl Editor {
                 .screen_bounds()
                 .size;
 
+            // Picking is done with the editor camera, it would not match the view of a scene
+            // camera, so mouse input is ignored while looking through it.
+            let looking_through = editor_scene.camera_controller.is_looking_through();
+
             if message.destination() == self.preview.frame {
                 if let UiMessageData::Widget(msg) = &message.data() {
                     match *msg {
+                        WidgetMessage::MouseDown { .. }
+                        | WidgetMessage::MouseUp { .. }
+                        | WidgetMessage::MouseWheel { .. }
+                        | WidgetMessage::MouseMove { .. }
+                            if looking_through => {}
                         WidgetMessage::MouseDown { button, pos, .. } => {
                             engine.user_interface.capture_mouse(self.preview.frame);
-                            if button == MouseButton::Left {
+                            if button == MouseButton::Left
+                                && self.settings.camera.alt_drag_orbit
+                                && engine.user_interface.keyboard_modifiers().alt
+                            {
+                                let aabb = match &editor_scene.selection {
+                                    Selection::Graph(selection) if !selection.is_empty() => {
+                                        selection.world_bounding_box(
+                                            &engine.scenes[editor_scene.scene].graph,
+                                        )
+                                    }
+                                    _ => editor_scene.content_bounding_box(engine),
+                                };
+                                editor_scene.camera_controller.begin_orbit(
+                                    aabb.map(|aabb| aabb.center()).unwrap_or_default(),
+                                );
+                            } else if button == MouseButton::Left
+                                && editor_scene.surface_alignment.is_some()
+                            {
+                                let alignment = editor_scene.surface_alignment.take().unwrap();
+                                let screen_bounds = engine
+                                    .user_interface
+                                    .node(self.preview.frame)
+                                    .screen_bounds();
+                                let graph = &engine.scenes[editor_scene.scene].graph;
+                                let selected = match &editor_scene.selection {
+                                    Selection::Graph(selection) => selection.nodes().to_vec(),
+                                    _ => Vec::new(),
+                                };
+                                // Only geometry can be the surface, except selected nodes and
+                                // their descendants. Closest hit is used, `pick` would cycle
+                                // through overlapping hits on repeated use.
+                                let normal = editor_scene
+                                    .camera_controller
+                                    .pick_all(
+                                        pos - screen_bounds.position,
+                                        graph,
+                                        editor_scene.root,
+                                        frame_size,
+                                        false,
+                                        |mut handle, node| {
+                                            if !matches!(node, Node::Mesh(_) | Node::Terrain(_)) {
+                                                return false;
+                                            }
+                                            while handle.is_some() {
+                                                if selected.contains(&handle) {
+                                                    return false;
+                                                }
+                                                handle = graph[handle].parent();
+                                            }
+                                            true
+                                        },
+                                    )
+                                    .first()
+                                    .map(|result| result.normal);
+                                match normal.and_then(|normal| {
+                                    make_align_to_surface_command(
+                                        editor_scene,
+                                        engine,
+                                        normal,
+                                        alignment.keep_yaw,
+                                    )
+                                }) {
+                                    Some(command) => self
+                                        .message_sender
+                                        .send(Message::DoSceneCommand(command))
+                                        .unwrap(),
+                                    None => self
+                                        .message_sender
+                                        .send(Message::Log(
+                                            "There is no surface to align to under the cursor."
+                                                .to_owned(),
+                                        ))
+                                        .unwrap(),
+                                }
+                            } else if button == MouseButton::Left {
                                 if let Some(current_im) = self.current_interaction_mode {
                                     let screen_bounds = engine
                                         .user_interface
@@ -1286,8 +1723,12 @@ impl Editor {
                         WidgetMessage::MouseUp { button, pos, .. } => {
                             engine.user_interface.release_mouse_capture();
 
-                            if button == MouseButton::Left {
-                                self.preview.click_mouse_pos = None;
+                            // Interaction mode gets mouse up only if it got mouse down, the click
+                            // could be consumed by the editor itself.
+                            if button == MouseButton::Left
+                                && !editor_scene.camera_controller.is_orbiting()
+                                && self.preview.click_mouse_pos.take().is_some()
+                            {
                                 if let Some(current_im) = self.current_interaction_mode {
                                     let screen_bounds = engine
                                         .user_interface
@@ -1305,9 +1746,19 @@ impl Editor {
                             }
                             editor_scene.camera_controller.on_mouse_button_up(button);
                         }
-                        WidgetMessage::MouseWheel { amount, .. } => {
+                        WidgetMessage::MouseWheel { amount, pos } => {
+                            let screen_bounds = engine
+                                .user_interface
+                                .node(self.preview.frame)
+                                .screen_bounds();
+                            let rel_pos = pos - screen_bounds.position;
                             let graph = &mut engine.scenes[editor_scene.scene].graph;
-                            editor_scene.camera_controller.on_mouse_wheel(amount, graph);
+                            if let Some(speed) = editor_scene
+                                .camera_controller
+                                .on_mouse_wheel(amount, rel_pos, frame_size, graph)
+                            {
+                                self.preview.show_fly_speed(speed, &engine.user_interface);
+                            }
                         }
                         WidgetMessage::MouseMove { pos, .. } => {
                             let last_pos = *self.preview.last_mouse_pos.get_or_insert(pos);
@@ -1319,7 +1770,10 @@ impl Editor {
                                 .screen_bounds();
                             let rel_pos = pos - screen_bounds.position;
 
-                            if let Some(current_im) = self.current_interaction_mode {
+                            if let Some(current_im) = self
+                                .current_interaction_mode
+                                .filter(|_| !editor_scene.camera_controller.is_orbiting())
+                            {
                                 self.interaction_modes[current_im as usize].on_mouse_move(
                                     mouse_offset,
                                     rel_pos,
@@ -1351,10 +1805,78 @@ impl Editor {
                                     key,
                                     editor_scene,
                                     engine,
+                                    &self.settings,
                                 );
                             }
 
                             match key {
+                                _ if MaterialSlots::slot_index(key).is_some()
+                                    && (engine.user_interface.keyboard_modifiers().shift
+                                        || engine.user_interface.keyboard_modifiers().alt) =>
+                                {
+                                    let index = MaterialSlots::slot_index(key).unwrap();
+                                    if let Selection::Graph(graph_selection) =
+                                        &editor_scene.selection
+                                    {
+                                        let graph = &engine.scenes[editor_scene.scene].graph;
+                                        if engine.user_interface.keyboard_modifiers().shift {
+                                            if self.material_slots.pin_from_selection(
+                                                index,
+                                                graph_selection,
+                                                graph,
+                                            ) {
+                                                self.message_sender
+                                                    .send(Message::Log(format!(
+                                                        "Material was pinned to slot {}.",
+                                                        index + 1
+                                                    )))
+                                                    .unwrap();
+                                            }
+                                        } else if let Some(command) = self
+                                            .material_slots
+                                            .make_assign_command(index, graph_selection, graph)
+                                        {
+                                            self.message_sender
+                                                .send(Message::DoSceneCommand(command))
+                                                .unwrap();
+                                        }
+                                    }
+                                }
+                                KeyCode::Numpad1 | KeyCode::Numpad3 | KeyCode::Numpad7 => {
+                                    let opposite =
+                                        engine.user_interface.keyboard_modifiers().control;
+                                    let view = match (key, opposite) {
+                                        (KeyCode::Numpad1, false) => ViewPreset::Front,
+                                        (KeyCode::Numpad1, true) => ViewPreset::Back,
+                                        (KeyCode::Numpad3, false) => ViewPreset::Right,
+                                        (KeyCode::Numpad3, true) => ViewPreset::Left,
+                                        (KeyCode::Numpad7, false) => ViewPreset::Top,
+                                        _ => ViewPreset::Bottom,
+                                    };
+                                    let aabb = match &editor_scene.selection {
+                                        Selection::Graph(selection) if !selection.is_empty() => {
+                                            selection.world_bounding_box(
+                                                &engine.scenes[editor_scene.scene].graph,
+                                            )
+                                        }
+                                        _ => editor_scene.content_bounding_box(engine),
+                                    };
+                                    let center = aabb.map(|aabb| aabb.center()).unwrap_or_default();
+                                    let graph = &mut engine.scenes[editor_scene.scene].graph;
+                                    editor_scene.camera_controller.set_view(view, center, graph);
+                                }
+                                KeyCode::Numpad0 => {
+                                    let text = editor_scene.toggle_look_through(engine);
+                                    self.message_sender.send(Message::Log(text)).unwrap();
+                                }
+                                KeyCode::F => {
+                                    if let Selection::Graph(selection) = &editor_scene.selection {
+                                        let graph = &mut engine.scenes[editor_scene.scene].graph;
+                                        if let Some(aabb) = selection.world_bounding_box(graph) {
+                                            editor_scene.camera_controller.fit_bounds(&aabb, graph);
+                                        }
+                                    }
+                                }
                                 KeyCode::Y => {
                                     if engine.user_interface.keyboard_modifiers().control {
                                         self.message_sender
@@ -1384,8 +1906,40 @@ impl Editor {
                                 KeyCode::L
                                     if engine.user_interface.keyboard_modifiers().control =>
                                 {
-                                    self.menu
-                                        .open_load_file_selector(&mut engine.user_interface);
+                                    self.menu.open_load_file_selector(
+                                        &mut engine.user_interface,
+                                        &self.settings,
+                                    );
+                                }
+                                KeyCode::C if engine.user_interface.keyboard_modifiers().alt => {
+                                    if let Some(cursor_pos) = self.preview.last_mouse_pos {
+                                        let screen_bounds = engine
+                                            .user_interface
+                                            .node(self.preview.frame)
+                                            .screen_bounds();
+                                        let graph = &mut engine.scenes[editor_scene.scene].graph;
+                                        // Closest geometry only, `pick` would cycle through
+                                        // overlapping hits on repeated presses.
+                                        let position = editor_scene
+                                            .camera_controller
+                                            .pick_all(
+                                                cursor_pos - screen_bounds.position,
+                                                graph,
+                                                editor_scene.root,
+                                                frame_size,
+                                                false,
+                                                |_, node| {
+                                                    matches!(node, Node::Mesh(_) | Node::Terrain(_))
+                                                },
+                                            )
+                                            .first()
+                                            .map(|result| result.position);
+                                        if let Some(position) = position {
+                                            editor_scene
+                                                .camera_controller
+                                                .recenter_pivot(graph, position);
+                                        }
+                                    }
                                 }
                                 KeyCode::C
                                     if engine.user_interface.keyboard_modifiers().control =>
@@ -1401,6 +1955,62 @@ impl Editor {
                                         );
                                     }
                                 }
+                                KeyCode::X
+                                    if engine.user_interface.keyboard_modifiers().control =>
+                                {
+                                    if let Some(command) = cut_selection(editor_scene, engine) {
+                                        self.message_sender
+                                            .send(Message::DoSceneCommand(command))
+                                            .unwrap();
+                                    }
+                                }
+                                KeyCode::D
+                                    if engine.user_interface.keyboard_modifiers().control =>
+                                {
+                                    if let Some(command) =
+                                        make_duplicate_selection_command(editor_scene, engine)
+                                    {
+                                        self.message_sender
+                                            .send(Message::DoSceneCommand(command))
+                                            .unwrap();
+                                    }
+                                }
+                                KeyCode::A
+                                    if engine.user_interface.keyboard_modifiers().control =>
+                                {
+                                    if let Some(command) =
+                                        make_select_all_command(editor_scene, engine)
+                                    {
+                                        self.message_sender
+                                            .send(Message::DoSceneCommand(command))
+                                            .unwrap();
+                                    }
+                                }
+                                KeyCode::Escape if editor_scene.surface_alignment.is_some() => {
+                                    editor_scene.surface_alignment = None;
+                                }
+                                KeyCode::Escape => {
+                                    if let Some(command) = make_deselect_all_command(editor_scene) {
+                                        self.message_sender
+                                            .send(Message::DoSceneCommand(command))
+                                            .unwrap();
+                                    }
+                                }
+                                KeyCode::G if engine.user_interface.keyboard_modifiers().shift => {
+                                    self.execute_toolbar_action(
+                                        ToolbarAction::ToggleSnapping,
+                                        engine,
+                                    );
+                                }
+                                KeyCode::G
+                                    if engine.user_interface.keyboard_modifiers().control =>
+                                {
+                                    self.message_sender
+                                        .send(Message::DoSceneCommand(
+                                            make_group_selection_command(editor_scene, engine),
+                                        ))
+                                        .unwrap();
+                                }
                                 KeyCode::V
                                     if engine.user_interface.keyboard_modifiers().control =>
                                 {
@@ -1542,49 +2152,43 @@ impl Editor {
                 }
             }
 
-            match message.data() {
-                UiMessageData::MessageBox(MessageBoxMessage::Close(result))
-                    if message.destination() == self.exit_message_box =>
-                {
-                    match result {
-                        MessageBoxResult::No => {
-                            self.message_sender
-                                .send(Message::Exit { force: true })
-                                .unwrap();
-                        }
-                        MessageBoxResult::Yes => {
-                            if let Some(scene) = self.scene.as_ref() {
-                                if let Some(path) = scene.path.as_ref() {
-                                    self.message_sender
-                                        .send(Message::SaveScene(path.clone()))
-                                        .unwrap();
-                                    self.message_sender
-                                        .send(Message::Exit { force: true })
-                                        .unwrap();
-                                } else {
-                                    // Scene wasn't saved yet, open Save As dialog.
-                                    engine
-                                        .user_interface
-                                        .send_message(WindowMessage::open_modal(
-                                            self.save_file_selector,
-                                            MessageDirection::ToWidget,
-                                            true,
-                                        ));
-                                }
-                            }
-                        }
-                        _ => {}
+            let answer = self
+                .exit_confirmation
+                .handle_ui_message(
+                    message,
+                    &engine.user_interface,
+                    &mut self.settings.confirmations.dont_ask_save_before_exit,
+                )
+                .or_else(|| {
+                    self.open_scene_confirmation.handle_ui_message(
+                        message,
+                        &engine.user_interface,
+                        &mut self.settings.confirmations.dont_ask_save_before_open,
+                    )
+                });
+            if let Some(save) = answer {
+                if self.settings.confirmations.remember {
+                    if let Err(e) = self.settings.save() {
+                        println!("Unable to save settings! Reason: {:?}!", e);
                     }
                 }
+                if save {
+                    self.save_and_perform_pending_action(engine);
+                } else if let Some(action) = self.pending_action.take() {
+                    self.message_sender.send(action.into_message()).unwrap();
+                }
+            }
+
+            match message.data() {
                 UiMessageData::FileSelector(FileSelectorMessage::Commit(path))
                     if message.destination() == self.save_file_selector =>
                 {
                     self.message_sender
                         .send(Message::SaveScene(path.clone()))
                         .unwrap();
-                    self.message_sender
-                        .send(Message::Exit { force: true })
-                        .unwrap();
+                    if let Some(action) = self.pending_action.take() {
+                        self.message_sender.send(action.into_message()).unwrap();
+                    }
                 }
 
                 _ => (),
@@ -1597,6 +2201,11 @@ impl Editor {
 
         self.menu
             .sync_to_model(self.scene.as_ref(), &mut engine.user_interface);
+        self.camera_bookmarks.sync_to_model(
+            self.scene.as_ref(),
+            &self.settings,
+            &mut engine.user_interface,
+        );
 
         if let Some(editor_scene) = self.scene.as_mut() {
             self.inspector.sync_to_model(editor_scene, engine);
@@ -1630,6 +2239,12 @@ impl Editor {
 
         let mut needs_sync = false;
 
+        self.preview
+            .toolbar
+            .sync_to_settings(&self.settings.toolbar, &engine.user_interface);
+        self.preview
+            .update_fly_speed_label(&engine.user_interface, dt);
+
         while let Ok(message) = self.message_receiver.try_recv() {
             self.log.handle_message(&message, engine);
             self.path_fixer
@@ -1643,6 +2258,10 @@ impl Editor {
             match message {
                 Message::DoSceneCommand(command) => {
                     if let Some(editor_scene) = self.scene.as_mut() {
+                        if !command.is_selection_only() {
+                            editor_scene.has_unsaved_changes = true;
+                            editor_scene.has_changes_since_autosave = true;
+                        }
                         self.command_stack.do_command(
                             command.into_inner(),
                             SceneContext {
@@ -1657,23 +2276,29 @@ impl Editor {
                 }
                 Message::UndoSceneCommand => {
                     if let Some(editor_scene) = self.scene.as_mut() {
-                        self.command_stack.undo(SceneContext {
+                        if self.command_stack.undo(SceneContext {
                             scene: &mut engine.scenes[editor_scene.scene],
                             message_sender: self.message_sender.clone(),
                             editor_scene,
                             resource_manager: engine.resource_manager.clone(),
-                        });
+                        }) {
+                            editor_scene.has_unsaved_changes = true;
+                            editor_scene.has_changes_since_autosave = true;
+                        }
                         needs_sync = true;
                     }
                 }
                 Message::RedoSceneCommand => {
                     if let Some(editor_scene) = self.scene.as_mut() {
-                        self.command_stack.redo(SceneContext {
+                        if self.command_stack.redo(SceneContext {
                             scene: &mut engine.scenes[editor_scene.scene],
                             message_sender: self.message_sender.clone(),
                             editor_scene,
                             resource_manager: engine.resource_manager.clone(),
-                        });
+                        }) {
+                            editor_scene.has_unsaved_changes = true;
+                            editor_scene.has_changes_since_autosave = true;
+                        }
                         needs_sync = true;
                     }
                 }
@@ -1698,6 +2323,8 @@ impl Editor {
                     if let Some(editor_scene) = self.scene.as_mut() {
                         match editor_scene.save(path.clone(), engine) {
                             Ok(message) => {
+                                self.add_recent_file(&path, engine);
+
                                 engine.user_interface.send_message(WindowMessage::title(
                                     self.preview.window,
                                     MessageDirection::ToWidget,
@@ -1708,6 +2335,30 @@ impl Editor {
                                 ));
 
                                 self.message_sender.send(Message::Log(message)).unwrap();
+
+                                // Scene got a path, so it can be reverted now.
+                                needs_sync = true;
+                            }
+                            Err(message) => {
+                                self.message_sender
+                                    .send(Message::Log(message.clone()))
+                                    .unwrap();
+
+                                engine.user_interface.send_message(MessageBoxMessage::open(
+                                    self.validation_message_box,
+                                    MessageDirection::ToWidget,
+                                    None,
+                                    Some(message),
+                                ));
+                            }
+                        }
+                    }
+                }
+                Message::SaveSceneCopy(path) => {
+                    if let Some(editor_scene) = self.scene.as_ref() {
+                        match editor_scene.save_copy(path, engine) {
+                            Ok(message) => {
+                                self.message_sender.send(Message::Log(message)).unwrap();
                             }
                             Err(message) => {
                                 self.message_sender
@@ -1734,7 +2385,23 @@ impl Editor {
                     };
                     match result {
                         Ok(scene) => {
+                            self.add_recent_file(&scene_path, engine);
                             self.set_scene(engine, scene, Some(scene_path));
+
+                            if let Some(editor_scene) = self.scene.as_ref() {
+                                let graph = &engine.scenes[editor_scene.scene].graph;
+                                for node in find_non_finite_transforms(graph, editor_scene.root) {
+                                    self.message_sender
+                                        .send(Message::Log(format!(
+                                            "Node {} ({}:{}) has non-finite transform! \
+                                            Use Utils -> Fix Non-Finite Transforms to fix it.",
+                                            graph[node].name(),
+                                            node.index(),
+                                            node.generation()
+                                        )))
+                                        .unwrap();
+                                }
+                            }
                         }
                         Err(e) => {
                             self.message_sender
@@ -1743,20 +2410,40 @@ impl Editor {
                         }
                     }
                 }
+                Message::OpenScene(path) => {
+                    if self.scene.as_ref().map_or(false, |s| s.has_unsaved_changes) {
+                        let question = format!(
+                            "Do you wish to save unsaved changes before opening {}?",
+                            path.display()
+                        );
+                        self.pending_action = Some(PendingAction::OpenScene(path));
+                        if self.open_scene_confirmation.ask(
+                            &engine.user_interface,
+                            question,
+                            self.settings.confirmations.dont_ask_save_before_open,
+                        ) {
+                            self.save_and_perform_pending_action(engine);
+                        }
+                    } else {
+                        self.message_sender.send(Message::LoadScene(path)).unwrap();
+                    }
+                }
                 Message::SetInteractionMode(mode_kind) => {
                     self.set_interaction_mode(Some(mode_kind), engine);
                 }
                 Message::Exit { force } => {
-                    if force {
-                        self.exit = true;
-                    } else if self.scene.is_some() {
-                        engine.user_interface.send_message(MessageBoxMessage::open(
-                            self.exit_message_box,
-                            MessageDirection::ToWidget,
-                            None,
-                            None,
-                        ));
+                    if !force && self.scene.as_ref().map_or(false, |s| s.has_unsaved_changes) {
+                        self.pending_action = Some(PendingAction::Exit);
+                        if self.exit_confirmation.ask(
+                            &engine.user_interface,
+                            "There are unsaved changes. Do you wish to save them before exit?"
+                                .to_owned(),
+                            self.settings.confirmations.dont_ask_save_before_exit,
+                        ) {
+                            self.save_and_perform_pending_action(engine);
+                        }
                     } else {
+                        self.save_window_states(engine);
                         self.exit = true;
                     }
                 }
@@ -1831,12 +2518,24 @@ impl Editor {
                         true,
                     ));
                 }
+                Message::OpenMaterialEditorForMany(materials) => {
+                    self.material_editor.set_materials(materials, engine);
+
+                    engine.user_interface.send_message(WindowMessage::open(
+                        self.material_editor.window,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+                }
                 Message::ShowInAssetBrowser(path) => {
                     self.asset_browser.locate_path(&engine.user_interface, path);
                 }
                 Message::SetWorldViewerFilter(filter) => {
                     self.world_viewer.set_filter(filter, &engine.user_interface);
                 }
+                Message::OpenAsset { path, kind } => {
+                    self.open_asset(path, kind, engine);
+                }
             }
         }
 
@@ -1852,8 +2551,12 @@ impl Editor {
 
             let camera = scene.graph[editor_scene.camera_controller.camera].as_camera_mut();
 
-            camera.set_z_near(self.settings.graphics.z_near);
-            camera.set_z_far(self.settings.graphics.z_far);
+            // Settings file could be edited by hand, so check values again.
+            if self.settings.graphics.has_valid_clip_planes() {
+                camera.set_z_near(self.settings.graphics.z_near);
+                camera.set_z_far(self.settings.graphics.z_far);
+            }
+            camera.set_fov(self.settings.graphics.fov.to_radians());
 
             // Create new render target if preview frame has changed its size.
             let (rt_width, rt_height) = if let TextureKind::Rectangle { width, height } =
@@ -1899,6 +2602,18 @@ impl Editor {
                 }
             }
 
+            if let Some(bounds) = self
+                .inspector
+                .surfaces_panel
+                .highlighted_surface_bounds(&scene.graph)
+            {
+                scene.drawing_context.draw_oob(
+                    &bounds,
+                    Matrix4::identity(),
+                    Color::opaque(255, 255, 0),
+                );
+            }
+
             fn draw_recursively(
                 node: Handle<Node>,
                 graph: &Graph,
@@ -2007,7 +2722,23 @@ impl Editor {
 
             let graph = &mut scene.graph;
 
-            editor_scene.camera_controller.update(graph, dt);
+            let gamepad_state = if self.settings.camera.gamepad {
+                self.gamepad
+                    .poll(self.settings.camera.gamepad_deadzone)
+                    .unwrap_or_default()
+            } else {
+                Default::default()
+            };
+            editor_scene
+                .camera_controller
+                .on_gamepad_input(gamepad_state);
+
+            editor_scene.camera_controller.update(
+                graph,
+                editor_scene.root,
+                &self.settings.camera,
+                dt,
+            );
 
             if let Some(mode) = self.current_interaction_mode {
                 self.interaction_modes[mode as usize].update(
@@ -2020,6 +2751,56 @@ impl Editor {
             self.asset_browser.update(engine);
             self.material_editor.update(engine);
         }
+
+        self.update_autosave(engine, dt);
+
+        if self.window_layout_changed {
+            self.window_layout_changed = false;
+            let old_windows = self.settings.windows.clone();
+            self.settings
+                .windows
+                .capture(&self.panels(), &engine.user_interface);
+            if self.settings.windows != old_windows {
+                if let Err(e) = self.settings.save() {
+                    println!("Unable to save settings! Reason: {:?}!", e);
+                }
+            }
+        }
+    }
+
+    /// Periodically writes the scene to a file next to it. It is done silently, so it
+    /// won't interrupt the user, results are only written to the log.
+    fn update_autosave(&mut self, engine: &mut GameEngine, dt: f32) {
+        let settings = &self.settings.autosave;
+
+        let (editor_scene, path) = match self.scene.as_mut() {
+            Some(editor_scene) if settings.enabled && editor_scene.has_changes_since_autosave => {
+                match editor_scene.path.clone() {
+                    Some(path) => (editor_scene, path),
+                    None => return,
+                }
+            }
+            // Nothing to protect, start counting from the next change.
+            _ => {
+                self.autosave_timer = 0.0;
+                return;
+            }
+        };
+
+        self.autosave_timer += dt;
+        if self.autosave_timer >= settings.interval * 60.0 {
+            self.autosave_timer = 0.0;
+
+            let message =
+                match editor_scene.save_copy(AutoSaveSettings::autosave_path(&path), engine) {
+                    Ok(message) => {
+                        editor_scene.has_changes_since_autosave = false;
+                        message
+                    }
+                    Err(reason) => format!("Auto-save failed! {}", reason),
+                };
+            self.message_sender.send(Message::Log(message)).unwrap();
+        }
     }
 }
 
@@ -1,10 +1,20 @@
-use crate::asset::AssetItem;
+use crate::asset::{AssetItem, AssetKind};
 use crate::{
     gui::make_dropdown_list_option,
-    make_relative_path,
+    load_image, make_relative_path,
     preview::PreviewPanel,
-    scene::commands::material::{SetMaterialPropertyValueCommand, SetMaterialShaderCommand},
-    send_sync_message, GameEngine, Message,
+    scene::{
+        commands::{
+            material::{SetMaterialPropertyValueCommand, SetMaterialShaderCommand},
+            mesh::SetMeshSurfaceMaterialCommand,
+            CommandGroup, SceneCommand,
+        },
+        EditorScene, Selection,
+    },
+    send_sync_message,
+    settings::make_text_mark,
+    world::graph::selection::GraphSelection,
+    GameEngine, Message,
 };
 use rg3d::gui::image::Image;
 use rg3d::gui::message::UiMessage;
@@ -13,67 +23,102 @@ use rg3d::gui::vec::vec2::Vec2EditorMessage;
 use rg3d::gui::vec::vec3::Vec3EditorMessage;
 use rg3d::gui::vec::vec4::Vec4EditorMessage;
 use rg3d::gui::{BuildContext, UiNode, UserInterface};
-use rg3d::resource::texture::TextureState;
+use rg3d::resource::texture::{Texture, TextureKind, TexturePixelKind, TextureState};
 use rg3d::{
     core::{
-        algebra::{Matrix4, Vector2, Vector3, Vector4},
+        algebra::{Matrix4, UnitQuaternion, Vector2, Vector3, Vector4},
+        color::Color,
         futures::executor::block_on,
         pool::Handle,
+        visitor::{Visit, VisitError, VisitResult, Visitor},
         BiDirHashMap,
     },
     engine::resource_manager::ResourceManager,
     gui::{
         border::BorderBuilder,
+        brush::Brush,
+        button::ButtonBuilder,
         check_box::CheckBoxBuilder,
         color::ColorFieldBuilder,
+        draw::SharedTexture,
         dropdown_list::DropdownListBuilder,
+        file_browser::{FileBrowserMode, FileSelectorBuilder, Filter},
         grid::{Column, GridBuilder, Row},
         image::ImageBuilder,
         list_view::ListViewBuilder,
         menu::{MenuItemBuilder, MenuItemContent},
         message::{
-            CheckBoxMessage, ColorFieldMessage, DropdownListMessage, ImageMessage, ListViewMessage,
-            MenuItemMessage, MessageDirection, PopupMessage, UiMessageData, WidgetMessage,
+            ButtonMessage, CheckBoxMessage, ColorFieldMessage, DropdownListMessage,
+            FileSelectorMessage, ImageMessage, KeyCode, ListViewMessage, MenuItemMessage,
+            MessageDirection, PopupMessage, TextBoxMessage, TextMessage, UiMessageData,
+            WidgetMessage, WindowMessage,
         },
         numeric::NumericUpDownBuilder,
         popup::{Placement, PopupBuilder},
         scroll_viewer::ScrollViewerBuilder,
         stack_panel::StackPanelBuilder,
         text::TextBuilder,
+        text_box::{TextBoxBuilder, TextCommitMode},
         vec::{vec2::Vec2EditorBuilder, vec3::Vec3EditorBuilder, vec4::Vec4EditorBuilder},
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
-        Thickness, VerticalAlignment,
+        HorizontalAlignment, Orientation, Thickness, VerticalAlignment,
+    },
+    material::{
+        shader::{SamplerFallback, Shader},
+        Material, PropertyValue,
     },
-    material::{shader::Shader, Material, PropertyValue},
     scene::{
         base::BaseBuilder,
+        camera::{SkyBox, SkyBoxBuilder},
+        graph::Graph,
+        light::{directional::DirectionalLightBuilder, BaseLightBuilder},
         mesh::{
             surface::{SurfaceBuilder, SurfaceData},
             MeshBuilder,
         },
+        node::Node,
+        Scene,
     },
-    utils::into_gui_texture,
+    utils::{
+        into_gui_texture,
+        log::{Log, MessageKind},
+    },
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{mpsc::Sender, Arc, Mutex, RwLock},
 };
-use std::sync::{mpsc::Sender, Arc, Mutex, RwLock};
 
 struct TextureContextMenu {
     popup: Handle<UiNode>,
     show_in_asset_browser: Handle<UiNode>,
+    clear: Handle<UiNode>,
     target: Handle<UiNode>,
 }
 
 impl TextureContextMenu {
     fn new(ctx: &mut BuildContext) -> Self {
         let show_in_asset_browser;
+        let clear;
         let popup = PopupBuilder::new(WidgetBuilder::new().with_visibility(false))
             .with_content(
-                StackPanelBuilder::new(WidgetBuilder::new().with_child({
-                    show_in_asset_browser = MenuItemBuilder::new(WidgetBuilder::new())
-                        .with_content(MenuItemContent::text("Show In Asset Browser"))
-                        .build(ctx);
-                    show_in_asset_browser
-                }))
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            show_in_asset_browser = MenuItemBuilder::new(WidgetBuilder::new())
+                                .with_content(MenuItemContent::text("Show In Asset Browser"))
+                                .build(ctx);
+                            show_in_asset_browser
+                        })
+                        .with_child({
+                            clear = MenuItemBuilder::new(WidgetBuilder::new())
+                                .with_content(MenuItemContent::text("Clear"))
+                                .build(ctx);
+                            clear
+                        }),
+                )
                 .build(ctx),
             )
             .build(ctx);
@@ -81,28 +126,568 @@ impl TextureContextMenu {
         Self {
             popup,
             show_in_asset_browser,
+            clear,
             target: Default::default(),
         }
     }
 }
 
+/// Controls of the preview scene lighting, they allow to check how material looks like
+/// under different conditions.
+struct PreviewLighting {
+    section: Handle<UiNode>,
+    light: Handle<Node>,
+    light_yaw: Handle<UiNode>,
+    light_pitch: Handle<UiNode>,
+    light_intensity: Handle<UiNode>,
+    ambient_color: Handle<UiNode>,
+    background_color: Handle<UiNode>,
+    environment: Handle<UiNode>,
+    clear_environment: Handle<UiNode>,
+    yaw: f32,
+    pitch: f32,
+}
+
+/// Makes sky box of solid color, it is used as background of the preview scene.
+fn make_background(color: Color) -> Option<SkyBox> {
+    let texture = Texture::from_bytes(
+        TextureKind::Rectangle {
+            width: 1,
+            height: 1,
+        },
+        TexturePixelKind::RGBA8,
+        vec![color.r, color.g, color.b, color.a],
+        false,
+    )?;
+
+    SkyBoxBuilder {
+        front: Some(texture.clone()),
+        back: Some(texture.clone()),
+        left: Some(texture.clone()),
+        right: Some(texture.clone()),
+        top: Some(texture.clone()),
+        bottom: Some(texture),
+    }
+    .build()
+    .ok()
+}
+
+fn make_angle_input_field(ctx: &mut BuildContext, row: usize, value: f32) -> Handle<UiNode> {
+    NumericUpDownBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_value(value)
+    .with_min_value(-360.0)
+    .with_max_value(360.0)
+    .build(ctx)
+}
+
+impl PreviewLighting {
+    const DEFAULT_AMBIENT_COLOR: Color = Color::opaque(80, 80, 80);
+    const DEFAULT_BACKGROUND_COLOR: Color = Color::opaque(60, 60, 60);
+
+    fn new(engine: &mut GameEngine, preview: &PreviewPanel) -> Self {
+        let scene = preview.scene();
+        let yaw = 45.0;
+        let pitch = 45.0;
+
+        let light = DirectionalLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()))
+            .build(&mut engine.scenes[scene].graph);
+        engine.scenes[scene].ambient_lighting_color = Self::DEFAULT_AMBIENT_COLOR;
+
+        let ctx = &mut engine.user_interface.build_ctx();
+
+        let light_yaw;
+        let light_pitch;
+        let light_intensity;
+        let ambient_color;
+        let background_color;
+        let environment;
+        let clear_environment;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_child(make_text_mark(ctx, "Light Yaw", 0))
+                .with_child({
+                    light_yaw = make_angle_input_field(ctx, 0, yaw);
+                    light_yaw
+                })
+                .with_child(make_text_mark(ctx, "Light Pitch", 1))
+                .with_child({
+                    light_pitch = make_angle_input_field(ctx, 1, pitch);
+                    light_pitch
+                })
+                .with_child(make_text_mark(ctx, "Light Intensity", 2))
+                .with_child({
+                    light_intensity = NumericUpDownBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(2)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_value(1.0)
+                    .with_min_value(0.0)
+                    .build(ctx);
+                    light_intensity
+                })
+                .with_child(make_text_mark(ctx, "Ambient Color", 3))
+                .with_child({
+                    ambient_color = ColorFieldBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(3)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_color(Self::DEFAULT_AMBIENT_COLOR)
+                    .build(ctx);
+                    ambient_color
+                })
+                .with_child(make_text_mark(ctx, "Background Color", 4))
+                .with_child({
+                    background_color = ColorFieldBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(4)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_color(Self::DEFAULT_BACKGROUND_COLOR)
+                    .build(ctx);
+                    background_color
+                })
+                .with_child(make_text_mark(ctx, "Environment", 5))
+                .with_child(
+                    GridBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(5)
+                            .on_column(1)
+                            .with_child({
+                                environment = ImageBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_width(SAMPLER_THUMBNAIL_SIZE)
+                                        .with_height(SAMPLER_THUMBNAIL_SIZE)
+                                        .with_horizontal_alignment(HorizontalAlignment::Left)
+                                        .with_allow_drop(true),
+                                )
+                                .with_opt_texture(sampler_thumbnail(&None))
+                                .build(ctx);
+                                environment
+                            })
+                            .with_child({
+                                clear_environment = ButtonBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_column(1)
+                                        .with_margin(Thickness::left(1.0)),
+                                )
+                                .with_text("Clear")
+                                .build(ctx);
+                                clear_environment
+                            }),
+                    )
+                    .add_row(Row::stretch())
+                    .add_column(Column::stretch())
+                    .add_column(Column::strict(40.0))
+                    .build(ctx),
+                ),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(SAMPLER_THUMBNAIL_SIZE + 2.0))
+        .add_column(Column::strict(150.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        let lighting = Self {
+            section,
+            light,
+            light_yaw,
+            light_pitch,
+            light_intensity,
+            ambient_color,
+            background_color,
+            environment,
+            clear_environment,
+            yaw,
+            pitch,
+        };
+
+        lighting.apply_light_rotation(&mut engine.scenes[scene]);
+        lighting.set_background(Self::DEFAULT_BACKGROUND_COLOR, preview, engine);
+
+        lighting
+    }
+
+    fn apply_light_rotation(&self, scene: &mut Scene) {
+        scene.graph[self.light].local_transform_mut().set_rotation(
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw.to_radians())
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch.to_radians()),
+        );
+    }
+
+    fn set_background(&self, color: Color, preview: &PreviewPanel, engine: &mut GameEngine) {
+        engine.scenes[preview.scene()].graph[preview.camera()]
+            .as_camera_mut()
+            .replace_skybox(make_background(color).map(Box::new));
+    }
+
+    fn set_environment(
+        &self,
+        environment: Option<Texture>,
+        preview: &PreviewPanel,
+        engine: &mut GameEngine,
+    ) {
+        engine.user_interface.send_message(ImageMessage::texture(
+            self.environment,
+            MessageDirection::ToWidget,
+            sampler_thumbnail(&environment),
+        ));
+
+        engine.scenes[preview.scene()].graph[preview.camera()]
+            .as_camera_mut()
+            .set_environment(environment);
+    }
+
+    fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        preview: &PreviewPanel,
+        engine: &mut GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        match message.data() {
+            UiMessageData::User(msg) if message.direction() == MessageDirection::FromWidget => {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    let scene = &mut engine.scenes[preview.scene()];
+                    if message.destination() == self.light_yaw {
+                        self.yaw = value;
+                        self.apply_light_rotation(scene);
+                    } else if message.destination() == self.light_pitch {
+                        self.pitch = value;
+                        self.apply_light_rotation(scene);
+                    } else if message.destination() == self.light_intensity {
+                        scene.graph[self.light].as_light_mut().set_intensity(value);
+                    }
+                }
+            }
+            UiMessageData::ColorField(ColorFieldMessage::Color(color))
+                if message.destination() == self.ambient_color
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                engine.scenes[preview.scene()].ambient_lighting_color = *color;
+            }
+            UiMessageData::ColorField(ColorFieldMessage::Color(color))
+                if message.destination() == self.background_color
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                self.set_background(*color, preview, engine);
+            }
+            UiMessageData::Widget(WidgetMessage::Drop(handle))
+                if message.destination() == self.environment =>
+            {
+                if let Some(asset_item) = engine.user_interface.node(*handle).cast::<AssetItem>() {
+                    if asset_item.kind == AssetKind::Texture {
+                        let texture = engine
+                            .resource_manager
+                            .request_texture(make_relative_path(&asset_item.path), None);
+                        self.set_environment(Some(texture), preview, engine);
+                    } else {
+                        sender
+                            .send(Message::Log(format!(
+                                "Environment map must be a texture, {} is not.",
+                                asset_item.path.display()
+                            )))
+                            .unwrap();
+                    }
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.clear_environment =>
+            {
+                self.set_environment(None, preview, engine);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Property edit that is applied directly to a material while a mouse button is held, for
+/// example while a numeric field is dragged. It is turned into a single command when the
+/// button is released, so the whole drag is reverted at once.
+struct PropertyEdit {
+    name: String,
+    /// Edited materials with values of the property they had before the edit.
+    original_values: Vec<(Arc<Mutex<Material>>, PropertyValue)>,
+}
+
 pub struct MaterialEditor {
     pub window: Handle<UiNode>,
     properties_panel: Handle<UiNode>,
     properties: BiDirHashMap<String, Handle<UiNode>>,
+    reset_buttons: BiDirHashMap<String, Handle<UiNode>>,
+    /// Tooltips of sampler thumbnails, they show file name of a texture.
+    sampler_tooltips: HashMap<String, Handle<UiNode>>,
+    /// Selectors of texture that is used by a sampler when it has no texture.
+    sampler_fallbacks: BiDirHashMap<String, Handle<UiNode>>,
+    property_filter_text: Handle<UiNode>,
+    /// Lowercase substring that names of visible properties must contain.
+    property_filter: String,
+    preview_lighting: PreviewLighting,
     preview: PreviewPanel,
     material: Option<Arc<Mutex<Material>>>,
+    /// Materials that are edited together with the main one. Only properties that all of
+    /// them have are shown, and every edit is applied to each of them.
+    other_materials: Vec<Arc<Mutex<Material>>>,
     available_shaders: Handle<UiNode>,
     shaders_list: Vec<Shader>,
     texture_context_menu: TextureContextMenu,
+    save: Handle<UiNode>,
+    save_selector: Handle<UiNode>,
+    load: Handle<UiNode>,
+    load_selector: Handle<UiNode>,
+    preview_mesh_selector: Handle<UiNode>,
+    preview_mesh: PreviewMesh,
+    mouse_pressed: bool,
+    property_edit: Option<PropertyEdit>,
+}
+
+/// Mesh on which edited material is shown in the preview panel.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum PreviewMesh {
+    Sphere,
+    Cube,
+    Plane,
+    Cylinder,
+    SelectedMesh,
+}
+
+impl PreviewMesh {
+    const ALL: [PreviewMesh; 5] = [
+        PreviewMesh::Sphere,
+        PreviewMesh::Cube,
+        PreviewMesh::Plane,
+        PreviewMesh::Cylinder,
+        PreviewMesh::SelectedMesh,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            PreviewMesh::Sphere => "Sphere",
+            PreviewMesh::Cube => "Cube",
+            PreviewMesh::Plane => "Plane",
+            PreviewMesh::Cylinder => "Cylinder",
+            PreviewMesh::SelectedMesh => "Selected Mesh",
+        }
+    }
+
+    /// Returns surface data for the preview mesh. Selected mesh shares data of the first
+    /// surface of first selected mesh in the scene, `None` is returned if there is no such mesh.
+    fn surface_data(
+        self,
+        editor_scene: &EditorScene,
+        engine: &GameEngine,
+    ) -> Option<Arc<RwLock<SurfaceData>>> {
+        let data = match self {
+            PreviewMesh::Sphere => SurfaceData::make_sphere(30, 30, 1.0, &Matrix4::identity()),
+            PreviewMesh::Cube => SurfaceData::make_cube(Matrix4::new_scaling(1.5)),
+            PreviewMesh::Plane => SurfaceData::make_quad(
+                &(Matrix4::new_rotation(Vector3::new(-std::f32::consts::FRAC_PI_2, 0.0, 0.0))
+                    * Matrix4::new_nonuniform_scaling(&Vector3::new(2.0, 2.0, 1.0))
+                    * Matrix4::new_translation(&Vector3::new(-0.5, -0.5, 0.0))),
+            ),
+            PreviewMesh::Cylinder => SurfaceData::make_cylinder(
+                30,
+                1.0,
+                2.0,
+                true,
+                &Matrix4::new_translation(&Vector3::new(0.0, -1.0, 0.0)),
+            ),
+            PreviewMesh::SelectedMesh => {
+                return if let Selection::Graph(selection) = &editor_scene.selection {
+                    let graph = &engine.scenes[editor_scene.scene].graph;
+                    selection.nodes().iter().find_map(|&node| {
+                        if let Node::Mesh(mesh) = &graph[node] {
+                            mesh.surfaces().first().map(|s| s.data())
+                        } else {
+                            None
+                        }
+                    })
+                } else {
+                    None
+                };
+            }
+        };
+        Some(Arc::new(RwLock::new(data)))
+    }
+}
+
+fn build_preview_mesh(graph: &mut Graph, data: Arc<RwLock<SurfaceData>>) -> Handle<Node> {
+    MeshBuilder::new(BaseBuilder::new())
+        .with_surfaces(vec![SurfaceBuilder::new(data).build()])
+        .build(graph)
+}
+
+fn make_material_file_filter() -> Filter {
+    Filter::new(|p: &Path| {
+        if let Some(ext) = p.extension() {
+            ext.to_string_lossy().as_ref() == "material"
+        } else {
+            p.is_dir()
+        }
+    })
+}
+
+/// Writes material to a file, shader and textures are stored as paths to resources.
+fn save_material(material: &mut Material, path: &Path) -> VisitResult {
+    let mut visitor = Visitor::new();
+    material.visit("Material", &mut visitor)?;
+    visitor.save_binary(path)
+}
+
+/// Reads material from a file and re-binds its shader and textures to actual resources.
+/// Textures that cannot be loaded anymore are left empty, a warning is written to the log.
+fn load_material(
+    path: &Path,
+    shaders: &[Shader],
+    resource_manager: ResourceManager,
+) -> Result<Material, VisitError> {
+    let mut loaded = Material::default();
+    let mut visitor = block_on(Visitor::load_binary(path))?;
+    loaded.visit("Material", &mut visitor)?;
+
+    let shader_path = loaded.shader().state().path().to_path_buf();
+    let shader = match shaders.iter().find(|s| s.state().path() == shader_path) {
+        Some(shader) => shader.clone(),
+        None => block_on(resource_manager.request_shader(&shader_path)).map_err(|e| {
+            VisitError::User(format!(
+                "Unable to load shader {}. Reason: {:?}",
+                shader_path.display(),
+                e
+            ))
+        })?,
+    };
+
+    let mut material = Material::from_shader(shader, Some(resource_manager.clone()));
+
+    for (name, value) in loaded.properties() {
+        let value = match value {
+            PropertyValue::Sampler {
+                value: Some(texture),
+                fallback,
+            } => {
+                let texture_path = texture.state().path().to_path_buf();
+                let texture = match block_on(resource_manager.request_texture(&texture_path, None))
+                {
+                    Ok(texture) => Some(texture),
+                    Err(e) => {
+                        Log::writeln(
+                            MessageKind::Warning,
+                            format!(
+                                "Unable to load texture {} for property {} of material {}, \
+                                 the sampler will be left empty. Reason: {:?}",
+                                texture_path.display(),
+                                name,
+                                path.display(),
+                                e
+                            ),
+                        );
+                        None
+                    }
+                };
+                PropertyValue::Sampler {
+                    value: texture,
+                    fallback: *fallback,
+                }
+            }
+            _ => value.clone(),
+        };
+
+        if let Err(e) = material.set_property(name, value) {
+            Log::writeln(
+                MessageKind::Warning,
+                format!(
+                    "Unable to set property {} of material {}. Reason: {:?}",
+                    name,
+                    path.display(),
+                    e
+                ),
+            );
+        }
+    }
+
+    Ok(material)
+}
+
+/// Checks whether two properties have the same type, so they can be edited by one widget.
+fn is_same_property_kind(a: &PropertyValue, b: &PropertyValue) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn property_values_equal(a: &PropertyValue, b: &PropertyValue) -> bool {
+    match (a, b) {
+        (PropertyValue::Float(a), PropertyValue::Float(b)) => a == b,
+        (PropertyValue::FloatArray(a), PropertyValue::FloatArray(b)) => a == b,
+        (PropertyValue::Int(a), PropertyValue::Int(b)) => a == b,
+        (PropertyValue::IntArray(a), PropertyValue::IntArray(b)) => a == b,
+        (PropertyValue::UInt(a), PropertyValue::UInt(b)) => a == b,
+        (PropertyValue::UIntArray(a), PropertyValue::UIntArray(b)) => a == b,
+        (PropertyValue::Vector2(a), PropertyValue::Vector2(b)) => a == b,
+        (PropertyValue::Vector2Array(a), PropertyValue::Vector2Array(b)) => a == b,
+        (PropertyValue::Vector3(a), PropertyValue::Vector3(b)) => a == b,
+        (PropertyValue::Vector3Array(a), PropertyValue::Vector3Array(b)) => a == b,
+        (PropertyValue::Vector4(a), PropertyValue::Vector4(b)) => a == b,
+        (PropertyValue::Vector4Array(a), PropertyValue::Vector4Array(b)) => a == b,
+        (PropertyValue::Matrix2(a), PropertyValue::Matrix2(b)) => a == b,
+        (PropertyValue::Matrix2Array(a), PropertyValue::Matrix2Array(b)) => a == b,
+        (PropertyValue::Matrix3(a), PropertyValue::Matrix3(b)) => a == b,
+        (PropertyValue::Matrix3Array(a), PropertyValue::Matrix3Array(b)) => a == b,
+        (PropertyValue::Matrix4(a), PropertyValue::Matrix4(b)) => a == b,
+        (PropertyValue::Matrix4Array(a), PropertyValue::Matrix4Array(b)) => a == b,
+        (PropertyValue::Bool(a), PropertyValue::Bool(b)) => a == b,
+        (PropertyValue::Color(a), PropertyValue::Color(b)) => a == b,
+        (
+            PropertyValue::Sampler {
+                value: value_a,
+                fallback: fallback_a,
+            },
+            PropertyValue::Sampler {
+                value: value_b,
+                fallback: fallback_b,
+            },
+        ) => value_a == value_b && fallback_a == fallback_b,
+        _ => false,
+    }
+}
+
+/// Single command is sent as is to keep its name in the undo history, several commands are
+/// grouped.
+fn make_command(mut commands: Vec<SceneCommand>) -> Option<SceneCommand> {
+    if commands.len() > 1 {
+        Some(SceneCommand::new(CommandGroup::from(commands)))
+    } else {
+        commands.pop()
+    }
+}
+
+/// Returns name text of a property row, it is the first child of the row.
+fn property_name_text(ui: &UserInterface, item: Handle<UiNode>) -> Handle<UiNode> {
+    ui.node(ui.node(item).parent()).children()[0]
 }
 
 fn create_item_container(
     ctx: &mut BuildContext,
     name: &str,
     item: Handle<UiNode>,
+    reset: Handle<UiNode>,
+    height: f32,
 ) -> Handle<UiNode> {
     ctx[item].set_column(1);
+    ctx[reset].set_column(2);
 
     GridBuilder::new(
         WidgetBuilder::new()
@@ -113,14 +698,105 @@ fn create_item_container(
                     .with_vertical_text_alignment(VerticalAlignment::Center)
                     .build(ctx),
             )
-            .with_child(item),
+            .with_child(item)
+            .with_child(reset),
     )
-    .add_row(Row::strict(24.0))
+    .add_row(Row::strict(height))
     .add_column(Column::strict(150.0))
     .add_column(Column::stretch())
+    .add_column(Column::strict(40.0))
+    .build(ctx)
+}
+
+const SAMPLER_THUMBNAIL_SIZE: f32 = 48.0;
+
+const SAMPLER_FALLBACKS: [SamplerFallback; 3] = [
+    SamplerFallback::White,
+    SamplerFallback::Normal,
+    SamplerFallback::Black,
+];
+
+fn sampler_fallback_index(fallback: SamplerFallback) -> Option<usize> {
+    SAMPLER_FALLBACKS.iter().position(|&f| f == fallback)
+}
+
+fn create_sampler_fallback_selector(ctx: &mut BuildContext) -> Handle<UiNode> {
+    DropdownListBuilder::new(
+        WidgetBuilder::new()
+            .on_column(1)
+            .with_width(90.0)
+            .with_height(24.0)
+            .with_horizontal_alignment(HorizontalAlignment::Right)
+            .with_vertical_alignment(VerticalAlignment::Center)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_items(vec![
+        make_dropdown_list_option(ctx, "White"),
+        make_dropdown_list_option(ctx, "Normal"),
+        make_dropdown_list_option(ctx, "Black"),
+    ])
+    .with_close_on_selection(true)
+    .build(ctx)
+}
+
+fn sampler_thumbnail(texture: &Option<Texture>) -> Option<SharedTexture> {
+    match texture {
+        Some(texture) => Some(into_gui_texture(texture.clone())),
+        None => load_image(include_bytes!("../resources/embed/no_texture.png")),
+    }
+}
+
+/// Shows texture of a sampler property in its image, the image must never be changed directly,
+/// so it stays in sync with the material on undo and redo.
+fn sync_sampler_thumbnail(ui: &UserInterface, image: Handle<UiNode>, texture: &Option<Texture>) {
+    send_sync_message(
+        ui,
+        ImageMessage::texture(
+            image,
+            MessageDirection::ToWidget,
+            sampler_thumbnail(texture),
+        ),
+    );
+}
+
+fn sampler_tooltip_text(texture: &Option<Texture>) -> String {
+    match texture {
+        Some(texture) => texture
+            .state()
+            .path()
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        None => "No Texture".to_owned(),
+    }
+}
+
+fn create_sampler_tooltip(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    BorderBuilder::new(
+        WidgetBuilder::new()
+            .with_visibility(false)
+            .with_foreground(Brush::Solid(Color::opaque(160, 160, 160)))
+            .with_child(
+                TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(2.0)))
+                    .with_text(text)
+                    .build(ctx),
+            ),
+    )
     .build(ctx)
 }
 
+/// Returns value of a property as it is defined in the shader of the material.
+fn default_property_value(
+    material: &Material,
+    name: &str,
+    resource_manager: ResourceManager,
+) -> Option<PropertyValue> {
+    Material::from_shader(material.shader().clone(), Some(resource_manager))
+        .properties()
+        .get(name)
+        .cloned()
+}
+
 fn create_array_view<T, B>(
     ctx: &mut BuildContext,
     value: &[T],
@@ -154,6 +830,40 @@ where
         .build(ctx)
 }
 
+/// Creates NxN grid of numeric fields, `value` is a column-major matrix storage. Position
+/// of each cell in the grid matches position of respective element in the matrix.
+fn create_matrix_view(ctx: &mut BuildContext, n: usize, value: &[f32]) -> Handle<UiNode> {
+    let mut grid = GridBuilder::new(WidgetBuilder::new().with_children((0..n * n).map(|i| {
+        let (row, column) = (i % n, i / n);
+        NumericUpDownBuilder::new(
+            WidgetBuilder::new()
+                .on_row(row)
+                .on_column(column)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .with_value(value[i])
+        .build(ctx)
+    })));
+    for _ in 0..n {
+        grid = grid
+            .add_row(Row::strict(24.0))
+            .add_column(Column::stretch());
+    }
+    grid.build(ctx)
+}
+
+fn sync_matrix_view(ui: &UserInterface, handle: Handle<UiNode>, value: &[f32]) {
+    let n = (value.len() as f32).sqrt() as usize;
+    for &cell in ui.node(handle).children() {
+        let cell_ref = ui.node(cell);
+        let index = cell_ref.column() * n + cell_ref.row();
+        send_sync_message(
+            ui,
+            NumericUpDownMessage::value(cell, MessageDirection::ToWidget, value[index]),
+        );
+    }
+}
+
 fn create_float_view(ctx: &mut BuildContext, value: f32) -> Handle<UiNode> {
     NumericUpDownBuilder::new(WidgetBuilder::new().with_height(24.0))
         .with_value(value)
@@ -196,6 +906,32 @@ fn create_vec4_view(ctx: &mut BuildContext, value: Vector4<f32>) -> Handle<UiNod
         .build(ctx)
 }
 
+/// Shaders often store colors in `Vector4` properties, such properties are edited using
+/// color field instead of raw vec4 editor. Property is treated as color if its name says so.
+fn is_color_property(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("color") || name.contains("colour")
+}
+
+fn vector4_to_color(value: Vector4<f32>) -> Color {
+    let component = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::from_rgba(
+        component(value.x),
+        component(value.y),
+        component(value.z),
+        component(value.w),
+    )
+}
+
+fn color_to_vector4(color: Color) -> Vector4<f32> {
+    Vector4::new(
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+        color.a as f32 / 255.0,
+    )
+}
+
 fn sync_array<T, B>(
     ui: &mut UserInterface,
     handle: Handle<UiNode>,
@@ -241,20 +977,28 @@ impl MaterialEditor {
     pub fn new(engine: &mut GameEngine) -> Self {
         let mut preview = PreviewPanel::new(engine, 300, 400);
 
-        let graph = &mut engine.scenes[preview.scene()].graph;
-        let sphere = MeshBuilder::new(BaseBuilder::new())
-            .with_surfaces(vec![SurfaceBuilder::new(Arc::new(RwLock::new(
-                SurfaceData::make_sphere(30, 30, 1.0, &Matrix4::identity()),
-            )))
-            .build()])
-            .build(graph);
+        let sphere = build_preview_mesh(
+            &mut engine.scenes[preview.scene()].graph,
+            Arc::new(RwLock::new(SurfaceData::make_sphere(
+                30,
+                30,
+                1.0,
+                &Matrix4::identity(),
+            ))),
+        );
         preview.set_model(sphere, engine);
 
+        let preview_lighting = PreviewLighting::new(engine, &preview);
+
         let ctx = &mut engine.user_interface.build_ctx();
 
         let panel;
         let properties_panel;
         let available_shaders;
+        let save;
+        let load;
+        let preview_mesh_selector;
+        let property_filter_text;
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0))
             .open(false)
             .with_title(WindowTitle::text("Material Editor"))
@@ -280,6 +1024,87 @@ impl MaterialEditor {
                                         .with_close_on_selection(true)
                                         .build(ctx);
                                         available_shaders
+                                    })
+                                    .with_child(
+                                        TextBuilder::new(
+                                            WidgetBuilder::new().on_row(1).on_column(0),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .with_text("Preview Mesh")
+                                        .build(ctx),
+                                    )
+                                    .with_child({
+                                        preview_mesh_selector = DropdownListBuilder::new(
+                                            WidgetBuilder::new().on_row(1).on_column(1),
+                                        )
+                                        .with_items(
+                                            PreviewMesh::ALL
+                                                .iter()
+                                                .map(|m| make_dropdown_list_option(ctx, m.name()))
+                                                .collect(),
+                                        )
+                                        .with_selected(0)
+                                        .with_close_on_selection(true)
+                                        .build(ctx);
+                                        preview_mesh_selector
+                                    }),
+                            )
+                            .add_column(Column::strict(150.0))
+                            .add_column(Column::stretch())
+                            .add_row(Row::strict(25.0))
+                            .add_row(Row::strict(25.0))
+                            .build(ctx),
+                        )
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_child({
+                                        save = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Save...")
+                                        .build(ctx);
+                                        save
+                                    })
+                                    .with_child({
+                                        load = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Load...")
+                                        .build(ctx);
+                                        load
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .with_child(
+                                        TextBuilder::new(
+                                            WidgetBuilder::new().on_row(0).on_column(0),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .with_text("Filter")
+                                        .build(ctx),
+                                    )
+                                    .with_child({
+                                        property_filter_text = TextBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text_commit_mode(TextCommitMode::Immediate)
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .build(ctx);
+                                        property_filter_text
                                     }),
                             )
                             .add_column(Column::strict(150.0))
@@ -288,7 +1113,7 @@ impl MaterialEditor {
                             .build(ctx),
                         )
                         .with_child(
-                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(1))
+                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(3))
                                 .with_content({
                                     properties_panel =
                                         StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
@@ -297,14 +1122,22 @@ impl MaterialEditor {
                                 .build(ctx),
                         )
                         .with_child({
-                            panel = BorderBuilder::new(WidgetBuilder::new().on_row(2).on_column(0))
+                            panel = BorderBuilder::new(WidgetBuilder::new().on_row(4).on_column(0))
                                 .build(ctx);
                             panel
-                        }),
+                        })
+                        .with_child(
+                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(5))
+                                .with_content(preview_lighting.section)
+                                .build(ctx),
+                        ),
                 )
+                .add_row(Row::strict(51.0))
+                .add_row(Row::strict(26.0))
                 .add_row(Row::strict(26.0))
                 .add_row(Row::stretch())
                 .add_row(Row::strict(300.0))
+                .add_row(Row::strict(150.0))
                 .add_column(Column::stretch())
                 .build(ctx),
             )
@@ -312,15 +1145,52 @@ impl MaterialEditor {
 
         ctx.link(preview.root, panel);
 
+        let save_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::text("Save Material As")),
+        )
+        .with_mode(FileBrowserMode::Save {
+            default_file_name: PathBuf::from("unnamed.material"),
+        })
+        .with_path("./")
+        .with_filter(make_material_file_filter())
+        .build(ctx);
+
+        let load_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::text("Load Material")),
+        )
+        .with_mode(FileBrowserMode::Open)
+        .with_path("./")
+        .with_filter(make_material_file_filter())
+        .build(ctx);
+
         let mut editor = Self {
             texture_context_menu: TextureContextMenu::new(ctx),
             window,
             preview,
             properties_panel,
             properties: Default::default(),
+            reset_buttons: Default::default(),
+            sampler_tooltips: Default::default(),
+            sampler_fallbacks: Default::default(),
+            property_filter_text,
+            property_filter: Default::default(),
+            preview_lighting,
             material: None,
+            other_materials: Default::default(),
             available_shaders,
             shaders_list: Default::default(),
+            save,
+            save_selector,
+            load,
+            load_selector,
+            preview_mesh_selector,
+            preview_mesh: PreviewMesh::Sphere,
+            mouse_pressed: false,
+            property_edit: None,
         };
 
         editor.sync_available_shaders_list(engine.resource_manager.clone());
@@ -379,7 +1249,46 @@ impl MaterialEditor {
         material: Option<Arc<Mutex<Material>>>,
         engine: &mut GameEngine,
     ) {
-        self.material = material;
+        self.set_materials(material.into_iter().collect(), engine);
+    }
+
+    /// Loads material from a file and starts editing it.
+    pub fn load(&mut self, path: &Path, engine: &mut GameEngine, sender: &Sender<Message>) {
+        match load_material(path, &self.shaders_list, engine.resource_manager.clone()) {
+            Ok(material) => {
+                self.set_material(Some(Arc::new(Mutex::new(material))), engine);
+                sender
+                    .send(Message::Log(format!(
+                        "Material was loaded from {}",
+                        path.display()
+                    )))
+                    .unwrap();
+            }
+            Err(e) => {
+                sender
+                    .send(Message::Log(format!(
+                        "Unable to load material from {}. Reason: {:?}",
+                        path.display(),
+                        e
+                    )))
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Edits several materials at once. The first one is shown in the preview, its shader
+    /// defines which properties can be edited.
+    pub fn set_materials(&mut self, materials: Vec<Arc<Mutex<Material>>>, engine: &mut GameEngine) {
+        let mut unique = Vec::<Arc<Mutex<Material>>>::new();
+        for material in materials {
+            if !unique.iter().any(|m| Arc::ptr_eq(m, &material)) {
+                unique.push(material);
+            }
+        }
+        let mut materials = unique.into_iter();
+
+        self.material = materials.next();
+        self.other_materials = materials.collect();
 
         if let Some(material) = self.material.clone() {
             engine.scenes[self.preview.scene()].graph[self.preview.model()]
@@ -396,6 +1305,26 @@ impl MaterialEditor {
     pub fn sync_to_model(&mut self, ui: &mut UserInterface) {
         if let Some(material) = self.material.as_ref() {
             let material = material.lock().unwrap();
+            let others = self
+                .other_materials
+                .iter()
+                .map(|m| m.lock().unwrap())
+                .collect::<Vec<_>>();
+
+            // Only properties that all edited materials have can be edited together.
+            let mut sorted_properties = material
+                .properties()
+                .iter()
+                .filter(|(name, value)| {
+                    others.iter().all(|other| {
+                        other
+                            .property_ref(name)
+                            .map_or(false, |v| is_same_property_kind(v, value))
+                    })
+                })
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>();
+            sorted_properties.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
 
             // Remove properties from ui.
             for name in self
@@ -405,7 +1334,17 @@ impl MaterialEditor {
                 .cloned()
                 .collect::<Vec<_>>()
             {
-                if !material.properties().contains_key(&name) {
+                if !sorted_properties.iter().any(|(n, _)| *n == name) {
+                    self.reset_buttons.remove_by_key(&name);
+                    self.sampler_fallbacks.remove_by_key(&name);
+
+                    if let Some(tooltip) = self.sampler_tooltips.remove(&name) {
+                        send_sync_message(
+                            ui,
+                            WidgetMessage::remove(tooltip, MessageDirection::ToWidget),
+                        );
+                    }
+
                     let item_to_delete = ui
                         .node(
                             self.properties
@@ -421,13 +1360,6 @@ impl MaterialEditor {
                 }
             }
 
-            let mut sorted_properties = material
-                .properties()
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect::<Vec<_>>();
-            sorted_properties.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
-
             // Add missing properties.
             for (name, property_value) in sorted_properties.iter() {
                 if !self.properties.contains_key(name) {
@@ -454,12 +1386,17 @@ impl MaterialEditor {
                         PropertyValue::Vector3Array(value) => {
                             create_array_view(ctx, value, create_vec3_view)
                         }
+                        PropertyValue::Vector4(value) if is_color_property(name) => {
+                            ColorFieldBuilder::new(WidgetBuilder::new())
+                                .with_color(vector4_to_color(*value))
+                                .build(ctx)
+                        }
                         PropertyValue::Vector4(value) => create_vec4_view(ctx, *value),
                         PropertyValue::Vector4Array(value) => {
                             create_array_view(ctx, value, create_vec4_view)
                         }
                         PropertyValue::Matrix2(value) => {
-                            create_array_view(ctx, value.data.as_slice(), create_float_view)
+                            create_matrix_view(ctx, 2, value.as_slice())
                         }
                         PropertyValue::Matrix2Array(value) => create_array_of_array_view(
                             ctx,
@@ -467,7 +1404,7 @@ impl MaterialEditor {
                             create_float_view,
                         ),
                         PropertyValue::Matrix3(value) => {
-                            create_array_view(ctx, value.data.as_slice(), create_float_view)
+                            create_matrix_view(ctx, 3, value.as_slice())
                         }
                         PropertyValue::Matrix3Array(value) => create_array_of_array_view(
                             ctx,
@@ -475,7 +1412,7 @@ impl MaterialEditor {
                             create_float_view,
                         ),
                         PropertyValue::Matrix4(value) => {
-                            create_array_view(ctx, value.data.as_slice(), create_float_view)
+                            create_matrix_view(ctx, 4, value.as_slice())
                         }
                         PropertyValue::Matrix4Array(value) => create_array_of_array_view(
                             ctx,
@@ -488,18 +1425,50 @@ impl MaterialEditor {
                         PropertyValue::Color(value) => ColorFieldBuilder::new(WidgetBuilder::new())
                             .with_color(*value)
                             .build(ctx),
-                        PropertyValue::Sampler { value, .. } => ImageBuilder::new(
-                            WidgetBuilder::new()
-                                .with_allow_drop(true)
-                                .with_context_menu(self.texture_context_menu.popup),
-                        )
-                        .with_opt_texture(value.clone().map(into_gui_texture))
-                        .build(ctx),
+                        PropertyValue::Sampler { value, .. } => {
+                            let tooltip = create_sampler_tooltip(ctx, &sampler_tooltip_text(value));
+                            self.sampler_tooltips.insert(name.to_owned(), tooltip);
+
+                            ImageBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_width(SAMPLER_THUMBNAIL_SIZE)
+                                    .with_height(SAMPLER_THUMBNAIL_SIZE)
+                                    .with_horizontal_alignment(HorizontalAlignment::Left)
+                                    .with_allow_drop(true)
+                                    .with_tooltip(tooltip)
+                                    .with_context_menu(self.texture_context_menu.popup),
+                            )
+                            .with_opt_texture(sampler_thumbnail(value))
+                            .build(ctx)
+                        }
+                    };
+
+                    let height = match property_value {
+                        PropertyValue::Sampler { .. } => SAMPLER_THUMBNAIL_SIZE + 2.0,
+                        PropertyValue::Matrix2(_) => 2.0 * 24.0,
+                        PropertyValue::Matrix3(_) => 3.0 * 24.0,
+                        PropertyValue::Matrix4(_) => 4.0 * 24.0,
+                        _ => 24.0,
                     };
 
                     self.properties.insert(name.to_owned(), item);
 
-                    let container = create_item_container(ctx, name, item);
+                    let reset =
+                        ButtonBuilder::new(WidgetBuilder::new().with_margin(Thickness::left(1.0)))
+                            .with_text("Reset")
+                            .build(ctx);
+
+                    self.reset_buttons.insert(name.to_owned(), reset);
+
+                    let container = create_item_container(ctx, name, item, reset, height);
+
+                    if let PropertyValue::Sampler { .. } = property_value {
+                        // Selector shares the cell with the thumbnail, it is placed to the
+                        // right of it.
+                        let fallback = create_sampler_fallback_selector(ctx);
+                        ctx.link(fallback, container);
+                        self.sampler_fallbacks.insert(name.to_owned(), fallback);
+                    }
 
                     send_sync_message(
                         ui,
@@ -512,13 +1481,34 @@ impl MaterialEditor {
                 }
             }
 
+            self.apply_property_filter(ui);
+
             // Sync values.
-            for (name, property_value) in material.properties() {
+            for (name, property_value) in sorted_properties.iter() {
                 let item = *self
                     .properties
                     .value_of(name)
                     .unwrap_or_else(|| panic!("Property not found {}", name));
 
+                let mixed = others.iter().any(|other| {
+                    other
+                        .property_ref(name)
+                        .map_or(false, |v| !property_values_equal(v, property_value))
+                });
+                let name_text = property_name_text(ui, item);
+                send_sync_message(
+                    ui,
+                    TextMessage::text(
+                        name_text,
+                        MessageDirection::ToWidget,
+                        if mixed {
+                            format!("{} (mixed)", name)
+                        } else {
+                            name.clone()
+                        },
+                    ),
+                );
+
                 match property_value {
                     PropertyValue::Float(value) => {
                         send_sync_message(
@@ -567,6 +1557,14 @@ impl MaterialEditor {
                     PropertyValue::Vector3Array(value) => {
                         sync_array(ui, item, value, create_vec3_view)
                     }
+                    PropertyValue::Vector4(value) if is_color_property(name) => send_sync_message(
+                        ui,
+                        ColorFieldMessage::color(
+                            item,
+                            MessageDirection::ToWidget,
+                            vector4_to_color(*value),
+                        ),
+                    ),
                     PropertyValue::Vector4(value) => send_sync_message(
                         ui,
                         Vec4EditorMessage::value(item, MessageDirection::ToWidget, *value),
@@ -574,27 +1572,21 @@ impl MaterialEditor {
                     PropertyValue::Vector4Array(value) => {
                         sync_array(ui, item, value, create_vec4_view)
                     }
-                    PropertyValue::Matrix2(value) => {
-                        sync_array(ui, item, value.as_slice(), create_float_view)
-                    }
+                    PropertyValue::Matrix2(value) => sync_matrix_view(ui, item, value.as_slice()),
                     PropertyValue::Matrix2Array(value) => sync_array_of_arrays(
                         ui,
                         item,
                         value.iter().map(|m| m.as_slice()),
                         create_float_view,
                     ),
-                    PropertyValue::Matrix3(value) => {
-                        sync_array(ui, item, value.as_slice(), create_float_view)
-                    }
+                    PropertyValue::Matrix3(value) => sync_matrix_view(ui, item, value.as_slice()),
                     PropertyValue::Matrix3Array(value) => sync_array_of_arrays(
                         ui,
                         item,
                         value.iter().map(|m| m.as_slice()),
                         create_float_view,
                     ),
-                    PropertyValue::Matrix4(value) => {
-                        sync_array(ui, item, value.as_slice(), create_float_view)
-                    }
+                    PropertyValue::Matrix4(value) => sync_matrix_view(ui, item, value.as_slice()),
                     PropertyValue::Matrix4Array(value) => sync_array_of_arrays(
                         ui,
                         item,
@@ -617,14 +1609,32 @@ impl MaterialEditor {
                             ColorFieldMessage::color(item, MessageDirection::ToWidget, *value),
                         );
                     }
-                    PropertyValue::Sampler { value, .. } => send_sync_message(
-                        ui,
-                        ImageMessage::texture(
-                            item,
-                            MessageDirection::ToWidget,
-                            value.clone().map(into_gui_texture),
-                        ),
-                    ),
+                    PropertyValue::Sampler { value, fallback } => {
+                        if let Some(&selector) = self.sampler_fallbacks.value_of(name) {
+                            send_sync_message(
+                                ui,
+                                DropdownListMessage::selection(
+                                    selector,
+                                    MessageDirection::ToWidget,
+                                    sampler_fallback_index(*fallback),
+                                ),
+                            );
+                        }
+
+                        sync_sampler_thumbnail(ui, item, value);
+
+                        if let Some(&tooltip) = self.sampler_tooltips.get(name) {
+                            let text = ui.node(tooltip).children()[0];
+                            send_sync_message(
+                                ui,
+                                TextMessage::text(
+                                    text,
+                                    MessageDirection::ToWidget,
+                                    sampler_tooltip_text(value),
+                                ),
+                            );
+                        }
+                    }
                 }
             }
 
@@ -637,28 +1647,226 @@ impl MaterialEditor {
         }
     }
 
+    /// Shows only property rows whose names contain current filter string.
+    fn apply_property_filter(&self, ui: &UserInterface) {
+        for (name, &item) in self.properties.forward_map() {
+            ui.send_message(WidgetMessage::visibility(
+                ui.node(item).parent(),
+                MessageDirection::ToWidget,
+                name.to_lowercase().contains(&self.property_filter),
+            ));
+        }
+    }
+
+    fn set_preview_mesh(
+        &mut self,
+        preview_mesh: PreviewMesh,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        let data = match preview_mesh.surface_data(editor_scene, engine) {
+            Some(data) => data,
+            None => {
+                sender
+                    .send(Message::Log(
+                        "Unable to preview material on selected mesh: no mesh is selected."
+                            .to_owned(),
+                    ))
+                    .unwrap();
+                // Previous mesh is still shown, so the selector must show it too.
+                send_sync_message(
+                    &engine.user_interface,
+                    DropdownListMessage::selection(
+                        self.preview_mesh_selector,
+                        MessageDirection::ToWidget,
+                        PreviewMesh::ALL
+                            .iter()
+                            .position(|&m| m == self.preview_mesh),
+                    ),
+                );
+                return;
+            }
+        };
+        self.preview_mesh = preview_mesh;
+
+        let mesh = build_preview_mesh(&mut engine.scenes[self.preview.scene()].graph, data);
+        // Fits camera to the new mesh.
+        self.preview.set_model(mesh, engine);
+        // Re-assigns current material to the new mesh.
+        self.set_materials(self.edited_materials(), engine);
+    }
+
+    /// Turns in-progress property edit into a command, the material gets its original value
+    /// back and the command applies the final one, so it can be undone.
+    fn commit_property_edit(&mut self, sender: &Sender<Message>) {
+        if let Some(edit) = self.property_edit.take() {
+            let commands = edit
+                .original_values
+                .into_iter()
+                .map(|(material, original_value)| {
+                    let value = {
+                        let mut material = material.lock().unwrap();
+                        let value = material.property_ref(&edit.name).unwrap().clone();
+                        material.set_property(&edit.name, original_value).unwrap();
+                        value
+                    };
+
+                    SceneCommand::new(SetMaterialPropertyValueCommand::new(
+                        material,
+                        edit.name.clone(),
+                        value,
+                    ))
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(command) = make_command(commands) {
+                sender.send(Message::DoSceneCommand(command)).unwrap();
+            }
+        }
+    }
+
+    /// Returns main material followed by other edited materials.
+    fn edited_materials(&self) -> Vec<Arc<Mutex<Material>>> {
+        self.material
+            .iter()
+            .chain(self.other_materials.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Sends a command that sets a property of every edited material, new value is made from
+    /// each material separately, so parts of the value that were not edited are preserved.
+    fn set_property_value<F>(&self, name: &str, mut make_value: F, sender: &Sender<Message>)
+    where
+        F: FnMut(&Material) -> Option<PropertyValue>,
+    {
+        let commands = self
+            .edited_materials()
+            .into_iter()
+            .filter_map(|material| {
+                let value = make_value(&material.lock().unwrap())?;
+                Some(SceneCommand::new(SetMaterialPropertyValueCommand::new(
+                    material,
+                    name.to_owned(),
+                    value,
+                )))
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(command) = make_command(commands) {
+            sender.send(Message::DoSceneCommand(command)).unwrap();
+        }
+    }
+
     pub fn handle_ui_message(
         &mut self,
         message: &UiMessage,
+        editor_scene: &EditorScene,
         engine: &mut GameEngine,
         sender: &Sender<Message>,
     ) {
         self.preview.handle_message(message, engine);
+        self.preview_lighting
+            .handle_ui_message(message, &self.preview, engine, sender);
+
+        match message.data() {
+            UiMessageData::Widget(WidgetMessage::MouseDown { .. }) => {
+                self.mouse_pressed = true;
+            }
+            UiMessageData::Widget(WidgetMessage::MouseUp { .. }) => {
+                self.mouse_pressed = false;
+                self.commit_property_edit(sender);
+            }
+            _ => {}
+        }
+
+        match message.data() {
+            UiMessageData::TextBox(TextBoxMessage::Text(text))
+                if message.destination() == self.property_filter_text
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                self.property_filter = text.to_lowercase();
+                self.apply_property_filter(&engine.user_interface);
+            }
+            UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index)))
+                if message.destination() == self.preview_mesh_selector
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                if let Some(&preview_mesh) = PreviewMesh::ALL.get(*index) {
+                    self.set_preview_mesh(preview_mesh, editor_scene, engine, sender);
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click) if message.destination() == self.load => {
+                engine
+                    .user_interface
+                    .send_message(WindowMessage::open_modal(
+                        self.load_selector,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+            }
+            UiMessageData::FileSelector(FileSelectorMessage::Commit(path))
+                if message.destination() == self.load_selector =>
+            {
+                self.load(path, engine, sender);
+            }
+            _ => {}
+        }
 
         if let Some(material) = self.material.clone() {
             match message.data() {
+                UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index)))
+                    if message.direction() == MessageDirection::FromWidget
+                        && self
+                            .sampler_fallbacks
+                            .contains_value(&message.destination()) =>
+                {
+                    let property_name = self
+                        .sampler_fallbacks
+                        .key_of(&message.destination())
+                        .unwrap();
+
+                    if let Some(&new_fallback) = SAMPLER_FALLBACKS.get(*index) {
+                        // Texture is kept, only the fallback is changed.
+                        self.set_property_value(
+                            property_name,
+                            |material| match material.property_ref(property_name) {
+                                Some(PropertyValue::Sampler { value, fallback })
+                                    if *fallback != new_fallback =>
+                                {
+                                    Some(PropertyValue::Sampler {
+                                        value: value.clone(),
+                                        fallback: new_fallback,
+                                    })
+                                }
+                                _ => None,
+                            },
+                            sender,
+                        );
+                    }
+                }
                 UiMessageData::DropdownList(msg) => {
                     if message.destination() == self.available_shaders
                         && message.direction() == MessageDirection::FromWidget
                     {
                         match msg {
                             DropdownListMessage::SelectionChanged(Some(value)) => {
-                                sender
-                                    .send(Message::do_scene_command(SetMaterialShaderCommand::new(
-                                        material.clone(),
-                                        self.shaders_list[*value].clone(),
-                                    )))
-                                    .unwrap();
+                                let shader = &self.shaders_list[*value];
+                                let commands = self
+                                    .edited_materials()
+                                    .into_iter()
+                                    .map(|material| {
+                                        SceneCommand::new(SetMaterialShaderCommand::new(
+                                            material,
+                                            shader.clone(),
+                                        ))
+                                    })
+                                    .collect::<Vec<_>>();
+
+                                if let Some(command) = make_command(commands) {
+                                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                                }
                             }
                             DropdownListMessage::Open => {
                                 self.sync_available_shaders_list(engine.resource_manager.clone());
@@ -671,6 +1879,45 @@ impl MaterialEditor {
                         }
                     }
                 }
+                UiMessageData::Button(ButtonMessage::Click)
+                    if message.destination() == self.save =>
+                {
+                    engine
+                        .user_interface
+                        .send_message(WindowMessage::open_modal(
+                            self.save_selector,
+                            MessageDirection::ToWidget,
+                            true,
+                        ));
+                }
+                UiMessageData::Button(ButtonMessage::Click) => {
+                    if let Some(property_name) = self.reset_buttons.key_of(&message.destination()) {
+                        self.set_property_value(
+                            property_name,
+                            |material| {
+                                default_property_value(
+                                    material,
+                                    property_name,
+                                    engine.resource_manager.clone(),
+                                )
+                            },
+                            sender,
+                        );
+                    }
+                }
+                UiMessageData::FileSelector(FileSelectorMessage::Commit(path))
+                    if message.destination() == self.save_selector =>
+                {
+                    let text = match save_material(&mut material.lock().unwrap(), path) {
+                        Ok(_) => format!("Material was saved to {}", path.display()),
+                        Err(e) => format!(
+                            "Unable to save material to {}. Reason: {:?}",
+                            path.display(),
+                            e
+                        ),
+                    };
+                    sender.send(Message::Log(text)).unwrap();
+                }
                 UiMessageData::Popup(PopupMessage::Placement(Placement::Cursor(target))) => {
                     if message.destination() == self.texture_context_menu.popup {
                         self.texture_context_menu.target = *target;
@@ -695,11 +1942,70 @@ impl MaterialEditor {
                         if let Some(path) = path {
                             sender.send(Message::ShowInAssetBrowser(path)).unwrap();
                         }
+                    } else if message.destination() == self.texture_context_menu.clear {
+                        if let Some(property_name) =
+                            self.properties.key_of(&self.texture_context_menu.target)
+                        {
+                            // Image will be cleared in `sync_to_model` once the command is
+                            // executed, the preview will use the fallback texture.
+                            self.set_property_value(
+                                property_name,
+                                |material| match material.property_ref(property_name) {
+                                    Some(PropertyValue::Sampler { fallback, .. }) => {
+                                        Some(PropertyValue::Sampler {
+                                            value: None,
+                                            fallback: *fallback,
+                                        })
+                                    }
+                                    _ => None,
+                                },
+                                sender,
+                            );
+                        }
                     }
                 }
                 _ => {}
             }
 
+            // Cells of matrix views are direct children of the property item.
+            if let UiMessageData::User(msg) = message.data() {
+                if let Some(NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.direction() == MessageDirection::FromWidget {
+                        let cell = engine.user_interface.node(message.destination());
+                        if let Some(property_name) = self.properties.key_of(&cell.parent()) {
+                            let index = (cell.row(), cell.column());
+
+                            // Only edited cell is changed, other cells keep values of each
+                            // material.
+                            self.set_property_value(
+                                property_name,
+                                |material| match material.property_ref(property_name)? {
+                                    PropertyValue::Matrix2(matrix) => {
+                                        let mut matrix = *matrix;
+                                        matrix[index] = *value;
+                                        Some(PropertyValue::Matrix2(matrix))
+                                    }
+                                    PropertyValue::Matrix3(matrix) => {
+                                        let mut matrix = *matrix;
+                                        matrix[index] = *value;
+                                        Some(PropertyValue::Matrix3(matrix))
+                                    }
+                                    PropertyValue::Matrix4(matrix) => {
+                                        let mut matrix = *matrix;
+                                        matrix[index] = *value;
+                                        Some(PropertyValue::Matrix4(matrix))
+                                    }
+                                    _ => None,
+                                },
+                                sender,
+                            );
+                        }
+                    }
+                }
+            }
+
             if let Some(property_name) = self.properties.key_of(&message.destination()) {
                 let property_value = match message.data() {
                     UiMessageData::User(msg)
@@ -740,7 +2046,13 @@ impl MaterialEditor {
                     UiMessageData::ColorField(ColorFieldMessage::Color(color))
                         if message.direction() == MessageDirection::FromWidget =>
                     {
-                        Some(PropertyValue::Color(*color))
+                        // Color field is also used for color-like Vector4 properties.
+                        match material.lock().unwrap().property_ref(property_name) {
+                            Some(PropertyValue::Vector4(_)) => {
+                                Some(PropertyValue::Vector4(color_to_vector4(*color)))
+                            }
+                            _ => Some(PropertyValue::Color(*color)),
+                        }
                     }
                     UiMessageData::Widget(WidgetMessage::Drop(handle)) => {
                         if let Some(asset_item) =
@@ -751,16 +2063,24 @@ impl MaterialEditor {
                             let texture =
                                 Some(engine.resource_manager.request_texture(relative_path, None));
 
-                            engine.user_interface.send_message(ImageMessage::texture(
-                                message.destination(),
-                                MessageDirection::ToWidget,
-                                texture.clone().map(into_gui_texture),
-                            ));
+                            // Image will be updated in `sync_to_model` once the command is
+                            // executed, this keeps it in sync with the material on undo/redo.
+                            // Each material keeps its own fallback.
+                            self.set_property_value(
+                                property_name,
+                                |material| match material.property_ref(property_name) {
+                                    Some(PropertyValue::Sampler { fallback, .. }) => {
+                                        Some(PropertyValue::Sampler {
+                                            value: texture.clone(),
+                                            fallback: *fallback,
+                                        })
+                                    }
+                                    _ => None,
+                                },
+                                sender,
+                            );
 
-                            Some(PropertyValue::Sampler {
-                                value: texture,
-                                fallback: Default::default(),
-                            })
+                            None
                         } else {
                             None
                         }
@@ -769,15 +2089,52 @@ impl MaterialEditor {
                 };
 
                 if let Some(property_value) = property_value {
-                    sender
-                        .send(Message::do_scene_command(
-                            SetMaterialPropertyValueCommand::new(
-                                material,
-                                property_name.clone(),
-                                property_value,
-                            ),
-                        ))
-                        .unwrap();
+                    if self.mouse_pressed {
+                        let property_name = property_name.clone();
+
+                        let same_edit = self.property_edit.as_ref().map_or(false, |edit| {
+                            edit.name == property_name
+                                && edit
+                                    .original_values
+                                    .first()
+                                    .map_or(false, |(m, _)| Arc::ptr_eq(m, &material))
+                        });
+                        if !same_edit {
+                            self.commit_property_edit(sender);
+
+                            let original_values = self
+                                .edited_materials()
+                                .into_iter()
+                                .map(|material| {
+                                    let value = material
+                                        .lock()
+                                        .unwrap()
+                                        .property_ref(&property_name)
+                                        .unwrap()
+                                        .clone();
+                                    (material, value)
+                                })
+                                .collect();
+                            self.property_edit = Some(PropertyEdit {
+                                name: property_name.clone(),
+                                original_values,
+                            });
+                        }
+
+                        for material in self.edited_materials() {
+                            material
+                                .lock()
+                                .unwrap()
+                                .set_property(&property_name, property_value.clone())
+                                .unwrap();
+                        }
+                    } else {
+                        self.set_property_value(
+                            property_name,
+                            |_| Some(property_value.clone()),
+                            sender,
+                        );
+                    }
                 }
             }
         }
@@ -787,3 +2144,264 @@ impl MaterialEditor {
         self.preview.update(engine)
     }
 }
+
+/// Small palette of pinned materials which could be assigned to selected meshes using
+/// number keys. Slots are bound to materials with Shift+[1..9] and assigned with Alt+[1..9].
+#[derive(Default)]
+pub struct MaterialSlots {
+    slots: [Option<Arc<Mutex<Material>>>; 9],
+}
+
+impl MaterialSlots {
+    pub fn slot_index(key: KeyCode) -> Option<usize> {
+        match key {
+            KeyCode::Key1 => Some(0),
+            KeyCode::Key2 => Some(1),
+            KeyCode::Key3 => Some(2),
+            KeyCode::Key4 => Some(3),
+            KeyCode::Key5 => Some(4),
+            KeyCode::Key6 => Some(5),
+            KeyCode::Key7 => Some(6),
+            KeyCode::Key8 => Some(7),
+            KeyCode::Key9 => Some(8),
+            _ => None,
+        }
+    }
+
+    pub fn pin(&mut self, index: usize, material: Arc<Mutex<Material>>) {
+        self.slots[index] = Some(material);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Arc<Mutex<Material>>> {
+        self.slots.get(index).and_then(|s| s.as_ref())
+    }
+
+    /// Pins material of first surface of first selected mesh.
+    pub fn pin_from_selection(
+        &mut self,
+        index: usize,
+        selection: &GraphSelection,
+        graph: &Graph,
+    ) -> bool {
+        for &node in selection.nodes() {
+            if let Node::Mesh(mesh) = &graph[node] {
+                if let Some(surface) = mesh.surfaces().first() {
+                    self.pin(index, surface.material().clone());
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Creates command that assigns material of given slot to every surface of every
+    /// selected mesh. Returns `None` if slot is empty or there are no meshes in selection.
+    pub fn make_assign_command(
+        &self,
+        index: usize,
+        selection: &GraphSelection,
+        graph: &Graph,
+    ) -> Option<SceneCommand> {
+        let material = self.get(index)?;
+
+        let commands = selection
+            .nodes()
+            .iter()
+            .filter_map(|&node| {
+                if let Node::Mesh(mesh) = &graph[node] {
+                    Some((node, mesh.surfaces().len()))
+                } else {
+                    None
+                }
+            })
+            .flat_map(|(node, surface_count)| {
+                (0..surface_count).map(move |surface_index| {
+                    SceneCommand::new(SetMeshSurfaceMaterialCommand::new(
+                        node,
+                        surface_index,
+                        material.clone(),
+                    ))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if commands.is_empty() {
+            None
+        } else {
+            Some(SceneCommand::new(CommandGroup::from(commands)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        asset::AssetItemBuilder, camera::CameraController, command::CommandStack, physics::Physics,
+        scene::commands::SceneContext,
+    };
+    use rg3d::{core::pool::Pool, event_loop::EventLoop, window::WindowBuilder};
+    use std::sync::mpsc::{self, Receiver};
+
+    const PROPERTY: &str = "diffuseTexture";
+    const ORIGINAL_TEXTURE: &str = "resources/embed/sound.png";
+    const DROPPED_TEXTURE: &str = "resources/embed/model.png";
+
+    // Tests are not run on the main thread.
+    #[cfg(target_os = "linux")]
+    fn make_event_loop() -> EventLoop<()> {
+        rg3d::platform::unix::EventLoopExtUnix::new_any_thread()
+    }
+
+    #[cfg(windows)]
+    fn make_event_loop() -> EventLoop<()> {
+        rg3d::platform::windows::EventLoopExtWindows::new_any_thread()
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    fn make_event_loop() -> EventLoop<()> {
+        EventLoop::new()
+    }
+
+    fn make_editor_scene(engine: &mut GameEngine, sender: Sender<Message>) -> EditorScene {
+        let mut scene = Scene::new();
+        let root = BaseBuilder::new().build(&mut scene.graph);
+        let camera_controller = CameraController::new(&mut scene.graph, root, sender);
+        EditorScene {
+            path: None,
+            root,
+            camera_controller,
+            physics: Physics::new(&scene),
+            navmeshes: Pool::new(),
+            scene: engine.scenes.add(scene),
+            selection: Default::default(),
+            clipboard: Default::default(),
+            has_unsaved_changes: false,
+            has_changes_since_autosave: false,
+            surface_alignment: None,
+            terrain_layer_names: Default::default(),
+        }
+    }
+
+    fn poll_ui_messages(engine: &mut GameEngine) {
+        while engine.user_interface.poll_message().is_some() {}
+    }
+
+    /// Does commands sent by the material editor the same way the editor does.
+    fn do_commands(
+        receiver: &Receiver<Message>,
+        command_stack: &mut CommandStack,
+        editor_scene: &mut EditorScene,
+        engine: &mut GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        for message in receiver.try_iter() {
+            if let Message::DoSceneCommand(command) = message {
+                command_stack.do_command(
+                    command.into_inner(),
+                    SceneContext {
+                        scene: &mut engine.scenes[editor_scene.scene],
+                        message_sender: sender.clone(),
+                        editor_scene,
+                        resource_manager: engine.resource_manager.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Returns path of the texture the image widget shows.
+    fn displayed_texture(engine: &GameEngine, image: Handle<UiNode>) -> Option<PathBuf> {
+        engine
+            .user_interface
+            .node(image)
+            .cast::<Image>()
+            .unwrap()
+            .texture()
+            .and_then(|t| {
+                t.0.downcast::<Mutex<TextureState>>()
+                    .map(|t| t.lock().unwrap().path().to_path_buf())
+                    .ok()
+            })
+    }
+
+    #[test]
+    fn undo_of_texture_drop_restores_thumbnail() {
+        let event_loop = make_event_loop();
+        let window_builder = WindowBuilder::new().with_visible(false);
+        let mut engine = GameEngine::new(window_builder, &event_loop, false).unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let mut editor_scene = make_editor_scene(&mut engine, sender.clone());
+        let mut command_stack = CommandStack::new(false);
+        let mut material_editor = MaterialEditor::new(&mut engine);
+
+        let mut material = Material::standard();
+        material
+            .set_property(
+                PROPERTY,
+                PropertyValue::Sampler {
+                    value: Some(
+                        engine
+                            .resource_manager
+                            .request_texture(ORIGINAL_TEXTURE, None),
+                    ),
+                    fallback: Default::default(),
+                },
+            )
+            .unwrap();
+        material_editor.set_material(Some(Arc::new(Mutex::new(material))), &mut engine);
+        poll_ui_messages(&mut engine);
+
+        let image = *material_editor.properties.value_of(PROPERTY).unwrap();
+        assert_eq!(
+            displayed_texture(&engine, image),
+            Some(PathBuf::from(ORIGINAL_TEXTURE))
+        );
+
+        let asset_item = AssetItemBuilder::new(WidgetBuilder::new())
+            .with_path(DROPPED_TEXTURE)
+            .build(
+                &mut engine.user_interface.build_ctx(),
+                engine.resource_manager.clone(),
+            );
+        material_editor.handle_ui_message(
+            &WidgetMessage::drop(image, MessageDirection::FromWidget, asset_item),
+            &editor_scene,
+            &mut engine,
+            &sender,
+        );
+        poll_ui_messages(&mut engine);
+        // Image is changed by the command only.
+        assert_eq!(
+            displayed_texture(&engine, image),
+            Some(PathBuf::from(ORIGINAL_TEXTURE))
+        );
+
+        do_commands(
+            &receiver,
+            &mut command_stack,
+            &mut editor_scene,
+            &mut engine,
+            &sender,
+        );
+        material_editor.sync_to_model(&mut engine.user_interface);
+        poll_ui_messages(&mut engine);
+        assert_eq!(
+            displayed_texture(&engine, image),
+            Some(PathBuf::from(DROPPED_TEXTURE))
+        );
+
+        command_stack.undo(SceneContext {
+            scene: &mut engine.scenes[editor_scene.scene],
+            message_sender: sender.clone(),
+            editor_scene: &mut editor_scene,
+            resource_manager: engine.resource_manager.clone(),
+        });
+        material_editor.sync_to_model(&mut engine.user_interface);
+        poll_ui_messages(&mut engine);
+        assert_eq!(
+            displayed_texture(&engine, image),
+            Some(PathBuf::from(ORIGINAL_TEXTURE))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        button::ButtonBuilder,
+        check_box::CheckBoxBuilder,
+        formatted_text::WrapMode,
+        grid::{Column, GridBuilder, Row},
+        message::{
+            ButtonMessage, CheckBoxMessage, MessageDirection, TextMessage, UiMessage,
+            UiMessageData, WindowMessage,
+        },
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+};
+
+/// Yes/No question with "Don't ask again" check box. The dialog does not store whether the
+/// question was suppressed, the flag is owned by the caller (see `ConfirmationSettings`), so it
+/// can be persisted. Once the question was suppressed, [`ConfirmationDialog::ask`] answers "Yes"
+/// immediately without showing the window. Closing the window cancels the action.
+pub struct ConfirmationDialog {
+    pub window: Handle<UiNode>,
+    text: Handle<UiNode>,
+    dont_ask: Handle<UiNode>,
+    yes: Handle<UiNode>,
+    no: Handle<UiNode>,
+    dont_ask_checked: bool,
+}
+
+impl ConfirmationDialog {
+    pub fn new(ctx: &mut BuildContext, title: &str) -> Self {
+        let text;
+        let dont_ask;
+        let yes;
+        let no;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(150.0))
+            .open(false)
+            .can_minimize(false)
+            .with_title(WindowTitle::text(title))
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            text = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .with_margin(Thickness::uniform(4.0)),
+                            )
+                            .with_wrap(WrapMode::Word)
+                            .build(ctx);
+                            text
+                        })
+                        .with_child({
+                            dont_ask = CheckBoxBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_content(
+                                TextBuilder::new(WidgetBuilder::new())
+                                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                                    .with_text("Don't ask again")
+                                    .build(ctx),
+                            )
+                            .checked(Some(false))
+                            .build(ctx);
+                            dont_ask
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .with_child({
+                                        yes = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(80.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Yes")
+                                        .build(ctx);
+                                        yes
+                                    })
+                                    .with_child({
+                                        no = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(80.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("No")
+                                        .build(ctx);
+                                        no
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::stretch())
+                .add_row(Row::strict(24.0))
+                .add_row(Row::strict(25.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            text,
+            dont_ask,
+            yes,
+            no,
+            dont_ask_checked: false,
+        }
+    }
+
+    /// Shows the question. Returns `true` if the question was suppressed and the action
+    /// should be performed right away.
+    pub fn ask(&mut self, ui: &UserInterface, question: String, suppressed: bool) -> bool {
+        if suppressed {
+            return true;
+        }
+
+        ui.send_message(TextMessage::text(
+            self.text,
+            MessageDirection::ToWidget,
+            question,
+        ));
+        self.dont_ask_checked = false;
+        ui.send_message(CheckBoxMessage::checked(
+            self.dont_ask,
+            MessageDirection::ToWidget,
+            Some(false),
+        ));
+        ui.send_message(WindowMessage::open_modal(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+
+        false
+    }
+
+    /// Returns `Some(true)` when the user has answered "Yes", `Some(false)` for "No". Sets
+    /// `suppressed` flag if the question should not be asked again.
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        ui: &UserInterface,
+        suppressed: &mut bool,
+    ) -> Option<bool> {
+        match message.data() {
+            &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value)))
+                if message.destination() == self.dont_ask
+                    && message.direction() == MessageDirection::FromWidget =>
+            {
+                self.dont_ask_checked = value;
+                None
+            }
+            UiMessageData::Button(ButtonMessage::Click)
+                if message.destination() == self.yes || message.destination() == self.no =>
+            {
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+
+                let confirmed = message.destination() == self.yes;
+                // Only positive answer is remembered, otherwise the action could not be
+                // performed anymore.
+                if confirmed && self.dont_ask_checked {
+                    *suppressed = true;
+                }
+
+                Some(confirmed)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,14 +1,32 @@
-use crate::menu::physics::PhysicsMenu;
+use crate::menu::{physics::PhysicsMenu, plane::PlaneDialog};
 use crate::{
-    create_terrain_layer_material,
-    menu::{create_menu_item, create_root_menu_item},
-    scene::commands::{graph::AddNodeCommand, sound::AddSoundSourceCommand},
-    Message,
+    create_terrain_layer_material, make_scene_file_filter,
+    menu::{create_menu_item, create_menu_item_shortcut, create_root_menu_item},
+    scene::{
+        commands::{
+            graph::{AddNodeCommand, LoadModelCommand},
+            make_group_selection_command,
+            sound::AddSoundSourceCommand,
+        },
+        EditorScene,
+    },
+    settings::Settings,
+    GameEngine, Message,
 };
 use rg3d::{
-    core::{algebra::Matrix4, pool::Handle},
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        pool::Handle,
+    },
+    engine::resource_manager::MaterialSearchOptions,
     gui::{
-        message::{MenuItemMessage, UiMessage, UiMessageData},
+        file_browser::FileSelectorBuilder,
+        message::{
+            FileSelectorMessage, MenuItemMessage, MessageDirection, UiMessage, UiMessageData,
+            WindowMessage,
+        },
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
         BuildContext, UiNode,
     },
     scene::{
@@ -21,6 +39,7 @@ use rg3d::{
         },
         mesh::{
             surface::{Surface, SurfaceData},
+            vertex::StaticVertex,
             Mesh, MeshBuilder,
         },
         node::Node,
@@ -30,30 +49,100 @@ use rg3d::{
         },
         sprite::SpriteBuilder,
         terrain::{LayerDefinition, TerrainBuilder},
+        transform::TransformBuilder,
     },
     sound::source::{generic::GenericSourceBuilder, spatial::SpatialSourceBuilder},
+    utils::raw_mesh::RawMeshBuilder,
 };
 use std::sync::{mpsc::Sender, Arc, RwLock};
 
 pub struct CreateEntityMenu {
     pub menu: Handle<UiNode>,
     create_pivot: Handle<UiNode>,
+    group_selection: Handle<UiNode>,
     create_cube: Handle<UiNode>,
     create_cone: Handle<UiNode>,
     create_sphere: Handle<UiNode>,
     create_cylinder: Handle<UiNode>,
     create_quad: Handle<UiNode>,
+    create_torus: Handle<UiNode>,
+    create_plane: Handle<UiNode>,
     create_decal: Handle<UiNode>,
     create_point_light: Handle<UiNode>,
     create_spot_light: Handle<UiNode>,
     create_directional_light: Handle<UiNode>,
     create_terrain: Handle<UiNode>,
     create_camera: Handle<UiNode>,
+    create_camera_at_view: Handle<UiNode>,
     create_sprite: Handle<UiNode>,
     create_particle_system: Handle<UiNode>,
     create_sound_source: Handle<UiNode>,
     create_spatial_sound_source: Handle<UiNode>,
+    instantiate_prefab: Handle<UiNode>,
+    prefab_selector: Handle<UiNode>,
     physics_menu: PhysicsMenu,
+    plane_dialog: PlaneDialog,
+}
+
+pub fn make_cube() -> Node {
+    let mut mesh = Mesh::default();
+    mesh.set_name("Cube");
+    mesh.add_surface(Surface::new(Arc::new(RwLock::new(SurfaceData::make_cube(
+        Matrix4::identity(),
+    )))));
+    Node::Mesh(mesh)
+}
+
+pub fn make_sphere() -> Node {
+    MeshBuilder::new(BaseBuilder::new().with_name("Sphere"))
+        .with_surfaces(vec![Surface::new(Arc::new(RwLock::new(
+            SurfaceData::make_sphere(16, 16, 0.5, &Matrix4::identity()),
+        )))])
+        .build_node()
+}
+
+/// Creates torus lying in XZ plane, `segments` is used both for the amount of rings
+/// around the major radius and the amount of sides of each ring.
+fn make_torus_surface_data(major_radius: f32, minor_radius: f32, segments: usize) -> SurfaceData {
+    let vertex = |i: usize, j: usize| {
+        let u = (i % segments) as f32 / segments as f32 * std::f32::consts::TAU;
+        let v = (j % segments) as f32 / segments as f32 * std::f32::consts::TAU;
+        let normal = Vector3::new(v.cos() * u.cos(), v.sin(), v.cos() * u.sin());
+        let center = Vector3::new(major_radius * u.cos(), 0.0, major_radius * u.sin());
+        StaticVertex::from_pos_uv_normal(
+            center + normal.scale(minor_radius),
+            Vector2::new(i as f32 / segments as f32, j as f32 / segments as f32),
+            normal,
+        )
+    };
+
+    let mut builder = RawMeshBuilder::<StaticVertex>::new(
+        (segments + 1) * (segments + 1),
+        segments * segments * 2,
+    );
+    for i in 0..segments {
+        for j in 0..segments {
+            builder.insert(vertex(i, j));
+            builder.insert(vertex(i, j + 1));
+            builder.insert(vertex(i + 1, j + 1));
+
+            builder.insert(vertex(i, j));
+            builder.insert(vertex(i + 1, j + 1));
+            builder.insert(vertex(i + 1, j));
+        }
+    }
+
+    let mut data = SurfaceData::from_raw_mesh(builder.build(), false);
+    data.calculate_tangents().unwrap();
+    data
+}
+
+pub fn make_point_light() -> Node {
+    PointLightBuilder::new(BaseLightBuilder::new(
+        BaseBuilder::new().with_name("PointLight"),
+    ))
+    .with_radius(10.0)
+    .build_node()
 }
 
 impl CreateEntityMenu {
@@ -63,19 +152,24 @@ impl CreateEntityMenu {
         let create_sphere;
         let create_cylinder;
         let create_quad;
+        let create_torus;
+        let create_plane;
         let create_point_light;
         let create_spot_light;
         let create_directional_light;
         let create_camera;
+        let create_camera_at_view;
         let create_sprite;
         let create_decal;
         let create_particle_system;
         let create_terrain;
         let create_pivot;
+        let group_selection;
         let create_sound_source;
         let create_spatial_sound_source;
 
         let physics_menu = PhysicsMenu::new(ctx);
+        let plane_dialog = PlaneDialog::new(ctx);
 
         let menu = create_root_menu_item(
             "Create",
@@ -84,6 +178,11 @@ impl CreateEntityMenu {
                     create_pivot = create_menu_item("Pivot", vec![], ctx);
                     create_pivot
                 },
+                {
+                    group_selection =
+                        create_menu_item_shortcut("Group Selection", "Ctrl+G", vec![], ctx);
+                    group_selection
+                },
                 create_menu_item(
                     "Mesh",
                     vec![
@@ -107,6 +206,14 @@ impl CreateEntityMenu {
                             create_quad = create_menu_item("Quad", vec![], ctx);
                             create_quad
                         },
+                        {
+                            create_torus = create_menu_item("Torus", vec![], ctx);
+                            create_torus
+                        },
+                        {
+                            create_plane = create_menu_item("Plane...", vec![], ctx);
+                            create_plane
+                        },
                     ],
                     ctx,
                 ),
@@ -149,6 +256,10 @@ impl CreateEntityMenu {
                     create_camera = create_menu_item("Camera", vec![], ctx);
                     create_camera
                 },
+                {
+                    create_camera_at_view = create_menu_item("Camera at Current View", vec![], ctx);
+                    create_camera_at_view
+                },
                 {
                     create_sprite = create_menu_item("Sprite", vec![], ctx);
                     create_sprite
@@ -165,10 +276,22 @@ impl CreateEntityMenu {
                     create_decal = create_menu_item("Decal", vec![], ctx);
                     create_decal
                 },
+                {
+                    instantiate_prefab = create_menu_item("Prefab...", vec![], ctx);
+                    instantiate_prefab
+                },
             ],
             ctx,
         );
 
+        let prefab_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::Text("Select a Prefab To Instantiate".into())),
+        )
+        .with_filter(make_scene_file_filter())
+        .build(ctx);
+
         Self {
             menu,
             create_cube,
@@ -176,32 +299,70 @@ impl CreateEntityMenu {
             create_sphere,
             create_cylinder,
             create_quad,
+            create_torus,
+            create_plane,
+            plane_dialog,
             create_point_light,
             create_spot_light,
             create_directional_light,
             create_camera,
+            create_camera_at_view,
             create_sprite,
             create_particle_system,
             create_pivot,
+            group_selection,
             create_terrain,
             create_sound_source,
             create_spatial_sound_source,
             create_decal,
+            instantiate_prefab,
+            prefab_selector,
             physics_menu,
         }
     }
 
-    pub fn handle_ui_message(&mut self, message: &UiMessage, sender: &Sender<Message>) {
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_scene: Option<&EditorScene>,
+        engine: &GameEngine,
+        sender: &Sender<Message>,
+        settings: &Settings,
+    ) {
+        let ui = &engine.user_interface;
+
         self.physics_menu.handle_ui_message(message, sender);
+        self.plane_dialog.handle_ui_message(message, ui, sender);
+
+        if let UiMessageData::FileSelector(FileSelectorMessage::Commit(path)) = message.data() {
+            if message.destination() == self.prefab_selector {
+                if let Some(editor_scene) = editor_scene {
+                    // Put the prefab where the camera looks at, so it is visible right away.
+                    let position = settings.move_mode_settings.snap_position(
+                        editor_scene
+                            .camera_controller
+                            .ground_look_at_point(&engine.scenes[editor_scene.scene].graph)
+                            .unwrap_or_default(),
+                    );
+                    sender
+                        .send(Message::do_scene_command(
+                            LoadModelCommand::new(path.clone(), MaterialSearchOptions::RecursiveUp)
+                                .with_position(position),
+                        ))
+                        .unwrap();
+                }
+            }
+        }
 
         if let UiMessageData::MenuItem(MenuItemMessage::Click) = message.data() {
-            if message.destination() == self.create_cube {
-                let mut mesh = Mesh::default();
-                mesh.set_name("Cube");
-                mesh.add_surface(Surface::new(Arc::new(RwLock::new(SurfaceData::make_cube(
-                    Matrix4::identity(),
-                )))));
-                let node = Node::Mesh(mesh);
+            if message.destination() == self.instantiate_prefab {
+                ui.send_message(WindowMessage::open_modal(
+                    self.prefab_selector,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+            } else if message.destination() == self.create_cube {
+                let node = make_cube();
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(node)))
                     .unwrap();
@@ -217,6 +378,15 @@ impl CreateEntityMenu {
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(node)))
                     .unwrap();
+            } else if message.destination() == self.group_selection {
+                if let Some(editor_scene) = editor_scene {
+                    sender
+                        .send(Message::DoSceneCommand(make_group_selection_command(
+                            editor_scene,
+                            engine,
+                        )))
+                        .unwrap();
+                }
             } else if message.destination() == self.create_pivot {
                 let node = BaseBuilder::new().with_name("Pivot").build_node();
 
@@ -224,11 +394,7 @@ impl CreateEntityMenu {
                     .send(Message::do_scene_command(AddNodeCommand::new(node)))
                     .unwrap();
             } else if message.destination() == self.create_point_light {
-                let node = PointLightBuilder::new(BaseLightBuilder::new(
-                    BaseBuilder::new().with_name("PointLight"),
-                ))
-                .with_radius(10.0)
-                .build_node();
+                let node = make_point_light();
 
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(node)))
@@ -261,11 +427,7 @@ impl CreateEntityMenu {
                     .send(Message::do_scene_command(AddNodeCommand::new(mesh)))
                     .unwrap();
             } else if message.destination() == self.create_sphere {
-                let mesh = MeshBuilder::new(BaseBuilder::new().with_name("Sphere"))
-                    .with_surfaces(vec![Surface::new(Arc::new(RwLock::new(
-                        SurfaceData::make_sphere(16, 16, 0.5, &Matrix4::identity()),
-                    )))])
-                    .build_node();
+                let mesh = make_sphere();
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(mesh)))
                     .unwrap();
@@ -278,6 +440,17 @@ impl CreateEntityMenu {
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(mesh)))
                     .unwrap();
+            } else if message.destination() == self.create_plane {
+                self.plane_dialog.open(ui);
+            } else if message.destination() == self.create_torus {
+                let mesh = MeshBuilder::new(BaseBuilder::new().with_name("Torus"))
+                    .with_surfaces(vec![Surface::new(Arc::new(RwLock::new(
+                        make_torus_surface_data(1.0, 0.25, 24),
+                    )))])
+                    .build_node();
+                sender
+                    .send(Message::do_scene_command(AddNodeCommand::new(mesh)))
+                    .unwrap();
             } else if message.destination() == self.create_camera {
                 let node = CameraBuilder::new(BaseBuilder::new().with_name("Camera"))
                     .enabled(false)
@@ -286,6 +459,29 @@ impl CreateEntityMenu {
                 sender
                     .send(Message::do_scene_command(AddNodeCommand::new(node)))
                     .unwrap();
+            } else if message.destination() == self.create_camera_at_view {
+                if let Some(editor_scene) = editor_scene {
+                    let graph = &engine.scenes[editor_scene.scene].graph;
+                    let editor_camera = editor_scene.camera_controller.camera;
+
+                    // New nodes are attached to the root of the scene, so global transform of
+                    // the editor camera can be used as local transform as is.
+                    let node = CameraBuilder::new(
+                        BaseBuilder::new().with_name("Camera").with_local_transform(
+                            TransformBuilder::new()
+                                .with_local_position(graph[editor_camera].global_position())
+                                .with_local_rotation(graph.global_rotation(editor_camera))
+                                .build(),
+                        ),
+                    )
+                    .with_fov(graph[editor_camera].as_camera().fov())
+                    .enabled(false)
+                    .build_node();
+
+                    sender
+                        .send(Message::do_scene_command(AddNodeCommand::new(node)))
+                        .unwrap();
+                }
             } else if message.destination() == self.create_sprite {
                 let node = SpriteBuilder::new(BaseBuilder::new().with_name("Sprite")).build_node();
 
@@ -1,13 +1,23 @@
 use crate::{
-    menu::{create_menu_item_shortcut, create_root_menu_item},
-    scene::{commands::PasteCommand, EditorScene, Selection},
-    GameEngine, Message,
+    menu::{create_menu_item, create_menu_item_shortcut, create_root_menu_item},
+    scene::{
+        commands::{
+            cut_selection, graph::RotateNodeCommand, make_deselect_all_command,
+            make_duplicate_selection_command, make_invert_selection_command,
+            make_select_all_command, CommandGroup, PasteCommand, SceneCommand,
+        },
+        EditorScene, Selection, SurfaceAlignment,
+    },
+    send_sync_message, GameEngine, Message,
 };
 use rg3d::{
-    core::pool::Handle,
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
     gui::{
-        message::{MenuItemMessage, UiMessage, UiMessageData},
-        BuildContext, UiNode,
+        message::{MenuItemMessage, MessageDirection, UiMessage, UiMessageData, WidgetMessage},
+        BuildContext, UiNode, UserInterface,
     },
 };
 use std::sync::mpsc::Sender;
@@ -17,7 +27,126 @@ pub struct EditMenu {
     undo: Handle<UiNode>,
     redo: Handle<UiNode>,
     copy: Handle<UiNode>,
+    cut: Handle<UiNode>,
     paste: Handle<UiNode>,
+    duplicate: Handle<UiNode>,
+    select_all: Handle<UiNode>,
+    deselect_all: Handle<UiNode>,
+    invert_selection: Handle<UiNode>,
+    face_camera: Handle<UiNode>,
+    face_camera_upright: Handle<UiNode>,
+    align_to_surface: Handle<UiNode>,
+    align_to_surface_keep_yaw: Handle<UiNode>,
+}
+
+/// Creates command that orients every selected node so its look vector points towards the
+/// editor camera. `upright` variant rotates nodes around Y axis only.
+fn make_face_camera_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+    upright: bool,
+) -> Option<SceneCommand> {
+    if let Selection::Graph(selection) = &editor_scene.selection {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+        let camera_position = graph[editor_scene.camera_controller.camera].global_position();
+
+        let commands = selection
+            .nodes()
+            .iter()
+            .filter_map(|&handle| {
+                let node = &graph[handle];
+
+                let mut direction = camera_position - node.global_position();
+                if upright {
+                    direction.y = 0.0;
+                }
+                if direction.norm() <= f32::EPSILON {
+                    return None;
+                }
+
+                // Y can't be used as up vector when node is right above or below the camera.
+                let up = if direction.normalize().dot(&Vector3::y()).abs() > 0.999 {
+                    Vector3::z()
+                } else {
+                    Vector3::y()
+                };
+                let global_rotation = UnitQuaternion::face_towards(&direction, &up);
+                let parent_rotation = if node.parent().is_some() {
+                    graph.global_rotation(node.parent())
+                } else {
+                    UnitQuaternion::identity()
+                };
+                let new_rotation = parent_rotation.inverse() * global_rotation;
+                let old_rotation = **node.local_transform().rotation();
+
+                Some(SceneCommand::new(RotateNodeCommand::new(
+                    handle,
+                    old_rotation,
+                    new_rotation,
+                )))
+            })
+            .collect::<Vec<_>>();
+
+        if !commands.is_empty() {
+            return Some(SceneCommand::new(CommandGroup::from(commands)));
+        }
+    }
+    None
+}
+
+/// Creates command that orients every selected node so its up axis matches given world-space
+/// surface normal. If `keep_yaw` is set, nodes keep their heading and are only tilted to the
+/// slope, otherwise their rotation is replaced entirely.
+pub fn make_align_to_surface_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+    normal: Vector3<f32>,
+    keep_yaw: bool,
+) -> Option<SceneCommand> {
+    let normal = normal.try_normalize(f32::EPSILON)?;
+    let tilt = UnitQuaternion::rotation_between(&Vector3::y(), &normal).unwrap_or_else(|| {
+        // Normal points straight down, any axis in XZ plane will do.
+        UnitQuaternion::from_axis_angle(&Vector3::x_axis(), std::f32::consts::PI)
+    });
+
+    if let Selection::Graph(selection) = &editor_scene.selection {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+
+        let commands = selection
+            .nodes()
+            .iter()
+            .map(|&handle| {
+                let node = &graph[handle];
+
+                let parent_rotation = if node.parent().is_some() {
+                    graph.global_rotation(node.parent())
+                } else {
+                    UnitQuaternion::identity()
+                };
+                let old_rotation = **node.local_transform().rotation();
+
+                let global_rotation = if keep_yaw {
+                    let look = (parent_rotation * old_rotation) * Vector3::z();
+                    let yaw = if look.x.abs() + look.z.abs() > f32::EPSILON {
+                        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), look.x.atan2(look.z))
+                    } else {
+                        UnitQuaternion::identity()
+                    };
+                    tilt * yaw
+                } else {
+                    tilt
+                };
+                let new_rotation = parent_rotation.inverse() * global_rotation;
+
+                SceneCommand::new(RotateNodeCommand::new(handle, old_rotation, new_rotation))
+            })
+            .collect::<Vec<_>>();
+
+        if !commands.is_empty() {
+            return Some(SceneCommand::new(CommandGroup::from(commands)));
+        }
+    }
+    None
 }
 
 impl EditMenu {
@@ -25,7 +154,16 @@ impl EditMenu {
         let redo;
         let undo;
         let copy;
+        let cut;
         let paste;
+        let duplicate;
+        let select_all;
+        let deselect_all;
+        let invert_selection;
+        let face_camera;
+        let face_camera_upright;
+        let align_to_surface;
+        let align_to_surface_keep_yaw;
         let menu = create_root_menu_item(
             "Edit",
             vec![
@@ -41,10 +179,47 @@ impl EditMenu {
                     copy = create_menu_item_shortcut("Copy", "Ctrl+C", vec![], ctx);
                     copy
                 },
+                {
+                    cut = create_menu_item_shortcut("Cut", "Ctrl+X", vec![], ctx);
+                    cut
+                },
                 {
                     paste = create_menu_item_shortcut("Paste", "Ctrl+V", vec![], ctx);
                     paste
                 },
+                {
+                    duplicate = create_menu_item_shortcut("Duplicate", "Ctrl+D", vec![], ctx);
+                    duplicate
+                },
+                {
+                    select_all = create_menu_item_shortcut("Select All", "Ctrl+A", vec![], ctx);
+                    select_all
+                },
+                {
+                    deselect_all = create_menu_item_shortcut("Deselect All", "Esc", vec![], ctx);
+                    deselect_all
+                },
+                {
+                    invert_selection = create_menu_item("Invert Selection", vec![], ctx);
+                    invert_selection
+                },
+                {
+                    face_camera = create_menu_item("Face Camera", vec![], ctx);
+                    face_camera
+                },
+                {
+                    face_camera_upright = create_menu_item("Face Camera (Upright)", vec![], ctx);
+                    face_camera_upright
+                },
+                {
+                    align_to_surface = create_menu_item("Align to Surface", vec![], ctx);
+                    align_to_surface
+                },
+                {
+                    align_to_surface_keep_yaw =
+                        create_menu_item("Align to Surface (Keep Yaw)", vec![], ctx);
+                    align_to_surface_keep_yaw
+                },
             ],
             ctx,
         );
@@ -54,10 +229,31 @@ impl EditMenu {
             undo,
             redo,
             copy,
+            cut,
             paste,
+            duplicate,
+            select_all,
+            deselect_all,
+            invert_selection,
+            face_camera,
+            face_camera_upright,
+            align_to_surface,
+            align_to_surface_keep_yaw,
         }
     }
 
+    pub fn sync_to_model(&self, editor_scene: Option<&EditorScene>, ui: &UserInterface) {
+        let has_selection = matches!(
+            editor_scene.map(|s| &s.selection),
+            Some(Selection::Graph(selection)) if !selection.is_empty()
+        );
+
+        send_sync_message(
+            ui,
+            WidgetMessage::enabled(self.cut, MessageDirection::ToWidget, has_selection),
+        );
+    }
+
     pub fn handle_ui_message(
         &mut self,
         message: &UiMessage,
@@ -75,16 +271,64 @@ impl EditMenu {
                         engine,
                     );
                 }
+            } else if message.destination() == self.cut {
+                if let Some(command) = cut_selection(editor_scene, engine) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
             } else if message.destination() == self.paste {
                 if !editor_scene.clipboard.is_empty() {
                     sender
                         .send(Message::do_scene_command(PasteCommand::new()))
                         .unwrap();
                 }
+            } else if message.destination() == self.duplicate {
+                if let Some(command) = make_duplicate_selection_command(editor_scene, engine) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
+            } else if message.destination() == self.select_all {
+                if let Some(command) = make_select_all_command(editor_scene, engine) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
+            } else if message.destination() == self.deselect_all {
+                if let Some(command) = make_deselect_all_command(editor_scene) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
+            } else if message.destination() == self.invert_selection {
+                if let Some(command) = make_invert_selection_command(editor_scene, engine) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
             } else if message.destination() == self.undo {
                 sender.send(Message::UndoSceneCommand).unwrap();
             } else if message.destination() == self.redo {
                 sender.send(Message::RedoSceneCommand).unwrap();
+            } else if message.destination() == self.face_camera
+                || message.destination() == self.face_camera_upright
+            {
+                if let Some(command) = make_face_camera_command(
+                    editor_scene,
+                    engine,
+                    message.destination() == self.face_camera_upright,
+                ) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
+                }
+            } else if message.destination() == self.align_to_surface
+                || message.destination() == self.align_to_surface_keep_yaw
+            {
+                let has_selection = matches!(
+                    &editor_scene.selection,
+                    Selection::Graph(selection) if !selection.is_empty()
+                );
+                if has_selection {
+                    editor_scene.surface_alignment = Some(SurfaceAlignment {
+                        keep_yaw: message.destination() == self.align_to_surface_keep_yaw,
+                    });
+                    sender
+                        .send(Message::Log(
+                            "Click on a surface to align selected nodes to it, press Esc to cancel."
+                                .to_owned(),
+                        ))
+                        .unwrap();
+                }
             }
         }
     }
@@ -1,22 +1,27 @@
 use crate::{
     make_save_file_selector, make_scene_file_filter,
-    menu::{create_menu_item, create_menu_item_shortcut, create_root_menu_item},
-    scene::EditorScene,
-    settings::{Settings, SettingsWindow},
+    menu::{
+        confirm::ConfirmationDialog, create_menu_item, create_menu_item_shortcut,
+        create_root_menu_item,
+    },
+    scene::{EditorScene, Selection},
+    settings::{recent::RecentFiles, Settings, SettingsWindow},
     GameEngine, Message,
 };
 use rg3d::{
-    core::pool::Handle,
+    core::{algebra::Vector2, pool::Handle},
     gui::{
         file_browser::FileSelectorBuilder,
+        menu::{MenuItemBuilder, MenuItemContent},
         message::{
-            FileSelectorMessage, MenuItemMessage, MessageBoxMessage, MessageDirection, UiMessage,
-            UiMessageData, WindowMessage,
+            FileSelectorMessage, MenuItemMessage, MessageBoxMessage, MessageDirection, TextMessage,
+            UiMessage, UiMessageData, WidgetMessage, WindowMessage,
         },
         messagebox::{MessageBoxBuilder, MessageBoxButtons},
+        text::TextBuilder,
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
-        UiNode, UserInterface,
+        BuildContext, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
 };
 use std::sync::mpsc::Sender;
@@ -26,18 +31,85 @@ pub struct FileMenu {
     new_scene: Handle<UiNode>,
     pub save: Handle<UiNode>,
     pub save_as: Handle<UiNode>,
+    pub save_copy_as: Handle<UiNode>,
+    pub export_selection: Handle<UiNode>,
     load: Handle<UiNode>,
+    pub revert: Handle<UiNode>,
     pub close_scene: Handle<UiNode>,
     exit: Handle<UiNode>,
     pub open_settings: Handle<UiNode>,
     configure: Handle<UiNode>,
     pub save_file_selector: Handle<UiNode>,
+    save_copy_file_selector: Handle<UiNode>,
+    export_selection_file_selector: Handle<UiNode>,
     pub load_file_selector: Handle<UiNode>,
     configure_message: Handle<UiNode>,
+    revert_confirmation: ConfirmationDialog,
     pub settings: SettingsWindow,
+    recent_files: Handle<UiNode>,
+    recent_file_items: Vec<RecentFileItem>,
+}
+
+/// Menu item in "Recent Files" sub-menu, there is a fixed amount of them and they're
+/// re-used when the list of recent files changes.
+struct RecentFileItem {
+    item: Handle<UiNode>,
+    text: Handle<UiNode>,
+}
+
+impl RecentFileItem {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let text = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::left(4.0))
+                .with_vertical_alignment(VerticalAlignment::Center),
+        )
+        .build(ctx);
+        let item = MenuItemBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_min_size(Vector2::new(120.0, 22.0)),
+        )
+        .with_content(MenuItemContent::Node(text))
+        .build(ctx);
+        Self { item, text }
+    }
+}
+
+fn open_file_selector(file_selector: Handle<UiNode>, ui: &UserInterface, settings: &Settings) {
+    ui.send_message(WindowMessage::open_modal(
+        file_selector,
+        MessageDirection::ToWidget,
+        true,
+    ));
+    ui.send_message(FileSelectorMessage::path(
+        file_selector,
+        MessageDirection::ToWidget,
+        settings.recent.initial_directory(),
+    ));
 }
 
 impl FileMenu {
+    /// Saves current scene to its path, or opens Save As dialog if the scene wasn't saved yet.
+    pub fn save_current_scene(
+        &self,
+        editor_scene: Option<&EditorScene>,
+        ui: &UserInterface,
+        sender: &Sender<Message>,
+        settings: &Settings,
+    ) {
+        if let Some(scene_path) = editor_scene.map(|s| s.path.as_ref()).flatten() {
+            sender.send(Message::SaveScene(scene_path.clone())).unwrap();
+        } else {
+            // If scene wasn't saved yet - open Save As window.
+            self.open_save_file_selector(ui, settings);
+        }
+    }
+
+    pub fn open_save_file_selector(&self, ui: &UserInterface, settings: &Settings) {
+        open_file_selector(self.save_file_selector, ui, settings);
+    }
+
     pub fn new(
         engine: &mut GameEngine,
         message_sender: &Sender<Message>,
@@ -46,14 +118,22 @@ impl FileMenu {
         let new_scene;
         let save;
         let save_as;
+        let save_copy_as;
+        let export_selection;
         let close_scene;
         let load;
+        let revert;
         let open_settings;
         let configure;
         let exit;
+        let recent_files;
 
         let ctx = &mut engine.user_interface.build_ctx();
 
+        let recent_file_items = (0..RecentFiles::MAX_ENTRIES)
+            .map(|_| RecentFileItem::new(ctx))
+            .collect::<Vec<_>>();
+
         let configure_message = MessageBoxBuilder::new(
             WindowBuilder::new(WidgetBuilder::new().with_width(250.0).with_height(150.0))
                 .open(false)
@@ -63,6 +143,8 @@ impl FileMenu {
         .with_buttons(MessageBoxButtons::Ok)
         .build(ctx);
 
+        let revert_confirmation = ConfirmationDialog::new(ctx, "Revert Scene");
+
         let menu = create_root_menu_item(
             "File",
             vec![
@@ -79,10 +161,31 @@ impl FileMenu {
                         create_menu_item_shortcut("Save Scene As...", "Ctrl+Shift+S", vec![], ctx);
                     save_as
                 },
+                {
+                    save_copy_as = create_menu_item("Save a Copy As...", vec![], ctx);
+                    save_copy_as
+                },
+                {
+                    export_selection =
+                        create_menu_item("Export Selection As Prefab...", vec![], ctx);
+                    export_selection
+                },
                 {
                     load = create_menu_item_shortcut("Load Scene...", "Ctrl+L", vec![], ctx);
                     load
                 },
+                {
+                    revert = create_menu_item("Revert Scene", vec![], ctx);
+                    revert
+                },
+                {
+                    recent_files = create_menu_item(
+                        "Recent Files",
+                        recent_file_items.iter().map(|i| i.item).collect(),
+                        ctx,
+                    );
+                    recent_files
+                },
                 {
                     close_scene = create_menu_item_shortcut("Close Scene", "Ctrl+Q", vec![], ctx);
                     close_scene
@@ -103,7 +206,10 @@ impl FileMenu {
             ctx,
         );
 
-        let save_file_selector = make_save_file_selector(ctx);
+        let save_file_selector = make_save_file_selector(ctx, "Save Scene As");
+        let save_copy_file_selector = make_save_file_selector(ctx, "Save a Copy As");
+        let export_selection_file_selector =
+            make_save_file_selector(ctx, "Export Selection As Prefab");
 
         let load_file_selector = FileSelectorBuilder::new(
             WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
@@ -113,24 +219,69 @@ impl FileMenu {
         .with_filter(make_scene_file_filter())
         .build(ctx);
 
-        Self {
+        let file_menu = Self {
             save_file_selector,
+            save_copy_file_selector,
+            export_selection_file_selector,
             load_file_selector,
             menu,
             new_scene,
             save,
             save_as,
+            save_copy_as,
+            export_selection,
             close_scene,
             load,
+            revert,
             exit,
             open_settings,
             configure,
             configure_message,
+            revert_confirmation,
             settings: SettingsWindow::new(engine, message_sender.clone(), settings),
+            recent_files,
+            recent_file_items,
+        };
+
+        file_menu.sync_recent_files(&engine.user_interface, &settings.recent);
+
+        file_menu
+    }
+
+    /// Shows paths of recent scenes in "Recent Files" sub-menu, paths that do not exist
+    /// anymore are greyed out.
+    pub fn sync_recent_files(&self, ui: &UserInterface, recent: &RecentFiles) {
+        ui.send_message(WidgetMessage::enabled(
+            self.recent_files,
+            MessageDirection::ToWidget,
+            !recent.scenes.is_empty(),
+        ));
+
+        for (i, entry) in self.recent_file_items.iter().enumerate() {
+            let path = recent.scenes.get(i);
+
+            ui.send_message(WidgetMessage::visibility(
+                entry.item,
+                MessageDirection::ToWidget,
+                path.is_some(),
+            ));
+
+            if let Some(path) = path {
+                ui.send_message(TextMessage::text(
+                    entry.text,
+                    MessageDirection::ToWidget,
+                    path.display().to_string(),
+                ));
+                ui.send_message(WidgetMessage::enabled(
+                    entry.item,
+                    MessageDirection::ToWidget,
+                    path.exists(),
+                ));
+            }
         }
     }
 
-    pub fn open_load_file_selector(&self, ui: &mut UserInterface) {
+    pub fn open_load_file_selector(&self, ui: &mut UserInterface, settings: &Settings) {
         ui.send_message(WindowMessage::open_modal(
             self.load_file_selector,
             MessageDirection::ToWidget,
@@ -141,6 +292,11 @@ impl FileMenu {
             MessageDirection::ToWidget,
             Some(std::env::current_dir().unwrap()),
         ));
+        ui.send_message(FileSelectorMessage::path(
+            self.load_file_selector,
+            MessageDirection::ToWidget,
+            settings.recent.initial_directory(),
+        ));
     }
 
     pub fn handle_ui_message(
@@ -159,52 +315,75 @@ impl FileMenu {
 
         match message.data() {
             UiMessageData::FileSelector(FileSelectorMessage::Commit(path)) => {
+                if message.destination() == self.save_file_selector
+                    || message.destination() == self.save_copy_file_selector
+                    || message.destination() == self.export_selection_file_selector
+                    || message.destination() == self.load_file_selector
+                {
+                    settings.recent.last_directory = path.parent().map(|p| p.to_owned());
+                    if let Err(e) = settings.save() {
+                        println!("Unable to save settings! Reason: {:?}!", e);
+                    }
+                }
+
                 if message.destination() == self.save_file_selector {
                     sender.send(Message::SaveScene(path.to_owned())).unwrap();
+                } else if message.destination() == self.save_copy_file_selector {
+                    sender
+                        .send(Message::SaveSceneCopy(path.to_owned()))
+                        .unwrap();
                 } else if message.destination() == self.load_file_selector {
-                    sender.send(Message::LoadScene(path.to_owned())).unwrap();
+                    sender.send(Message::OpenScene(path.to_owned())).unwrap();
+                } else if message.destination() == self.export_selection_file_selector {
+                    if let Some(editor_scene) = editor_scene.as_ref() {
+                        if let Selection::Graph(selection) = &editor_scene.selection {
+                            let message =
+                                match editor_scene.export_selection(selection, path, engine) {
+                                    Ok(message) | Err(message) => message,
+                                };
+                            sender.send(Message::Log(message)).unwrap();
+                        }
+                    }
                 }
             }
             UiMessageData::MenuItem(MenuItemMessage::Click) => {
                 if message.destination() == self.save {
-                    if let Some(scene_path) =
-                        editor_scene.as_ref().map(|s| s.path.as_ref()).flatten()
-                    {
-                        sender.send(Message::SaveScene(scene_path.clone())).unwrap();
-                    } else {
-                        // If scene wasn't saved yet - open Save As window.
-                        engine
-                            .user_interface
-                            .send_message(WindowMessage::open_modal(
-                                self.save_file_selector,
-                                MessageDirection::ToWidget,
-                                true,
-                            ));
-                        engine
-                            .user_interface
-                            .send_message(FileSelectorMessage::path(
-                                self.save_file_selector,
-                                MessageDirection::ToWidget,
-                                std::env::current_dir().unwrap(),
-                            ));
-                    }
+                    self.save_current_scene(
+                        editor_scene.as_deref(),
+                        &engine.user_interface,
+                        sender,
+                        settings,
+                    );
                 } else if message.destination() == self.save_as {
-                    engine
-                        .user_interface
-                        .send_message(WindowMessage::open_modal(
-                            self.save_file_selector,
-                            MessageDirection::ToWidget,
-                            true,
-                        ));
-                    engine
-                        .user_interface
-                        .send_message(FileSelectorMessage::path(
-                            self.save_file_selector,
-                            MessageDirection::ToWidget,
-                            std::env::current_dir().unwrap(),
-                        ));
+                    self.open_save_file_selector(&engine.user_interface, settings);
+                } else if message.destination() == self.save_copy_as {
+                    open_file_selector(
+                        self.save_copy_file_selector,
+                        &engine.user_interface,
+                        settings,
+                    );
+                } else if message.destination() == self.export_selection {
+                    open_file_selector(
+                        self.export_selection_file_selector,
+                        &engine.user_interface,
+                        settings,
+                    );
                 } else if message.destination() == self.load {
-                    self.open_load_file_selector(&mut engine.user_interface);
+                    self.open_load_file_selector(&mut engine.user_interface, settings);
+                } else if message.destination() == self.revert {
+                    if let Some(path) = editor_scene.as_ref().and_then(|s| s.path.as_ref()) {
+                        let confirmed = self.revert_confirmation.ask(
+                            &engine.user_interface,
+                            format!(
+                                "Reload {} from disk? All unsaved changes will be lost.",
+                                path.display()
+                            ),
+                            settings.confirmations.dont_ask_revert_scene,
+                        );
+                        if confirmed {
+                            sender.send(Message::LoadScene(path.clone())).unwrap();
+                        }
+                    }
                 } else if message.destination() == self.close_scene {
                     sender.send(Message::CloseScene).unwrap();
                 } else if message.destination() == self.exit {
@@ -230,9 +409,32 @@ impl FileMenu {
                     }
                 } else if message.destination() == self.open_settings {
                     self.settings.open(&engine.user_interface, settings, None);
+                } else if let Some(index) = self
+                    .recent_file_items
+                    .iter()
+                    .position(|i| i.item == message.destination())
+                {
+                    if let Some(path) = settings.recent.scenes.get(index) {
+                        sender.send(Message::OpenScene(path.clone())).unwrap();
+                    }
                 }
             }
             _ => {}
         }
+
+        if let Some(true) = self.revert_confirmation.handle_ui_message(
+            message,
+            &engine.user_interface,
+            &mut settings.confirmations.dont_ask_revert_scene,
+        ) {
+            if settings.confirmations.remember {
+                if let Err(e) = settings.save() {
+                    println!("Unable to save settings! Reason: {:?}!", e);
+                }
+            }
+            if let Some(path) = editor_scene.as_ref().and_then(|s| s.path.as_ref()) {
+                sender.send(Message::LoadScene(path.clone())).unwrap();
+            }
+        }
     }
 }
@@ -2,7 +2,7 @@ use crate::{
     menu::{
         create::CreateEntityMenu, edit::EditMenu, file::FileMenu, utils::UtilsMenu, view::ViewMenu,
     },
-    scene::EditorScene,
+    scene::{EditorScene, Selection},
     send_sync_message,
     settings::Settings,
     GameEngine, Message,
@@ -18,10 +18,12 @@ use rg3d::{
 };
 use std::sync::mpsc::Sender;
 
+pub mod confirm;
 pub mod create;
 pub mod edit;
 pub mod file;
 pub mod physics;
+pub mod plane;
 pub mod utils;
 pub mod view;
 
@@ -37,12 +39,17 @@ pub struct Menu {
 
 pub struct Panels {
     pub light_panel: Handle<UiNode>,
+    pub camera_bookmarks: Handle<UiNode>,
     pub log_panel: Handle<UiNode>,
     pub inspector_window: Handle<UiNode>,
     pub world_outliner_window: Handle<UiNode>,
     pub asset_window: Handle<UiNode>,
     pub configurator_window: Handle<UiNode>,
     pub path_fixer: Handle<UiNode>,
+    pub name_replacer: Handle<UiNode>,
+    pub transform_randomizer: Handle<UiNode>,
+    /// Panel windows and tiles they were docked to initially.
+    pub docked_panels: Vec<(Handle<UiNode>, Handle<UiNode>)>,
 }
 
 pub struct MenuContext<'a, 'b> {
@@ -120,8 +127,8 @@ impl Menu {
         }
     }
 
-    pub fn open_load_file_selector(&self, ui: &mut UserInterface) {
-        self.file_menu.open_load_file_selector(ui)
+    pub fn open_load_file_selector(&self, ui: &mut UserInterface, settings: &Settings) {
+        self.file_menu.open_load_file_selector(ui, settings)
     }
 
     pub fn sync_to_model(&mut self, editor_scene: Option<&EditorScene>, ui: &mut UserInterface) {
@@ -131,6 +138,7 @@ impl Menu {
             self.file_menu.close_scene,
             self.file_menu.save,
             self.file_menu.save_as,
+            self.file_menu.save_copy_as,
             self.create_entity_menu.menu,
             self.edit_menu.menu,
         ]
@@ -141,6 +149,29 @@ impl Menu {
                 WidgetMessage::enabled(widget, MessageDirection::ToWidget, editor_scene.is_some()),
             );
         }
+
+        send_sync_message(
+            ui,
+            WidgetMessage::enabled(
+                self.file_menu.export_selection,
+                MessageDirection::ToWidget,
+                editor_scene.map_or(false, |s| {
+                    matches!(&s.selection, Selection::Graph(selection) if !selection.is_empty())
+                }),
+            ),
+        );
+
+        // Scene can be reverted only to a saved state.
+        send_sync_message(
+            ui,
+            WidgetMessage::enabled(
+                self.file_menu.revert,
+                MessageDirection::ToWidget,
+                editor_scene.map_or(false, |s| s.path.is_some()),
+            ),
+        );
+
+        self.edit_menu.sync_to_model(editor_scene, ui);
     }
 
     pub fn handle_ui_message(&mut self, message: &UiMessage, mut ctx: MenuContext) {
@@ -155,10 +186,20 @@ impl Menu {
             );
         }
 
-        self.create_entity_menu
-            .handle_ui_message(message, &self.message_sender);
-        self.utils_menu
-            .handle_ui_message(message, &ctx.panels, &ctx.engine.user_interface);
+        self.create_entity_menu.handle_ui_message(
+            message,
+            ctx.editor_scene.as_deref(),
+            ctx.engine,
+            &self.message_sender,
+            ctx.settings,
+        );
+        self.utils_menu.handle_ui_message(
+            message,
+            &ctx.panels,
+            &self.message_sender,
+            ctx.editor_scene.as_deref(),
+            ctx.engine,
+        );
         self.file_menu.handle_ui_message(
             message,
             &self.message_sender,
@@ -167,7 +208,11 @@ impl Menu {
             ctx.settings,
             ctx.panels.configurator_window,
         );
-        self.view_menu
-            .handle_ui_message(message, &ctx.engine.user_interface, &ctx.panels);
+        self.view_menu.handle_ui_message(
+            message,
+            &ctx.engine.user_interface,
+            &ctx.panels,
+            ctx.settings,
+        );
     }
 }
@@ -0,0 +1,235 @@
+//! Dialog that creates a flat mesh subdivided into a grid of quads.
+
+use crate::{
+    gui::{make_button, make_f32_field},
+    scene::commands::graph::AddNodeCommand,
+    settings::make_text_mark,
+    Message,
+};
+use rg3d::{
+    core::{
+        algebra::{Vector2, Vector3},
+        pool::Handle,
+    },
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{ButtonMessage, MessageDirection, UiMessage, UiMessageData, WindowMessage},
+        numeric::NumericUpDownMessage,
+        stack_panel::StackPanelBuilder,
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, UiNode, UserInterface,
+    },
+    scene::{
+        base::BaseBuilder,
+        mesh::{
+            surface::{Surface, SurfaceData},
+            vertex::StaticVertex,
+            MeshBuilder,
+        },
+    },
+    utils::raw_mesh::RawMeshBuilder,
+};
+use std::sync::{mpsc::Sender, Arc, RwLock};
+
+pub struct PlaneDialog {
+    pub window: Handle<UiNode>,
+    width: Handle<UiNode>,
+    length: Handle<UiNode>,
+    width_segments: Handle<UiNode>,
+    length_segments: Handle<UiNode>,
+    create: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+    width_value: f32,
+    length_value: f32,
+    width_segments_value: usize,
+    length_segments_value: usize,
+}
+
+/// Creates plane lying in XZ plane and facing up, its center is at the origin.
+pub fn make_plane_surface_data(
+    width: f32,
+    length: f32,
+    width_segments: usize,
+    length_segments: usize,
+) -> SurfaceData {
+    let vertex = |i: usize, j: usize| {
+        let u = i as f32 / width_segments as f32;
+        let v = j as f32 / length_segments as f32;
+        StaticVertex::from_pos_uv_normal(
+            Vector3::new((u - 0.5) * width, 0.0, (v - 0.5) * length),
+            Vector2::new(u, v),
+            Vector3::y(),
+        )
+    };
+
+    let mut builder = RawMeshBuilder::<StaticVertex>::new(
+        (width_segments + 1) * (length_segments + 1),
+        width_segments * length_segments * 2,
+    );
+    for i in 0..width_segments {
+        for j in 0..length_segments {
+            builder.insert(vertex(i, j));
+            builder.insert(vertex(i, j + 1));
+            builder.insert(vertex(i + 1, j + 1));
+
+            builder.insert(vertex(i, j));
+            builder.insert(vertex(i + 1, j + 1));
+            builder.insert(vertex(i + 1, j));
+        }
+    }
+
+    let mut data = SurfaceData::from_raw_mesh(builder.build(), false);
+    data.calculate_tangents().unwrap();
+    data
+}
+
+impl PlaneDialog {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let width_value = 10.0;
+        let length_value = 10.0;
+        let width_segments_value = 10;
+        let length_segments_value = 10;
+
+        let width;
+        let length;
+        let width_segments;
+        let length_segments;
+        let create;
+        let cancel;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(170.0))
+            .with_title(WindowTitle::text("Create Plane"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(make_text_mark(ctx, "Width", 0))
+                        .with_child({
+                            width = make_f32_field(ctx, 0, width_value, 0.001, 3);
+                            width
+                        })
+                        .with_child(make_text_mark(ctx, "Length", 1))
+                        .with_child({
+                            length = make_f32_field(ctx, 1, length_value, 0.001, 3);
+                            length
+                        })
+                        .with_child(make_text_mark(ctx, "Width Segments", 2))
+                        .with_child({
+                            width_segments =
+                                make_f32_field(ctx, 2, width_segments_value as f32, 1.0, 0);
+                            width_segments
+                        })
+                        .with_child(make_text_mark(ctx, "Length Segments", 3))
+                        .with_child({
+                            length_segments =
+                                make_f32_field(ctx, 3, length_segments_value as f32, 1.0, 0);
+                            length_segments
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .on_row(5)
+                                    .on_column(1)
+                                    .with_child({
+                                        create = make_button(ctx, "Create");
+                                        create
+                                    })
+                                    .with_child({
+                                        cancel = make_button(ctx, "Cancel");
+                                        cancel
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::stretch())
+                .add_row(Row::strict(28.0))
+                .add_column(Column::strict(110.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            width,
+            length,
+            width_segments,
+            length_segments,
+            create,
+            cancel,
+            width_value,
+            length_value,
+            width_segments_value,
+            length_segments_value,
+        }
+    }
+
+    pub fn open(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::open_modal(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    fn close(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::close(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        ui: &UserInterface,
+        sender: &Sender<Message>,
+    ) {
+        match message.data() {
+            UiMessageData::User(msg) if message.direction() == MessageDirection::FromWidget => {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    let destination = message.destination();
+                    if destination == self.width {
+                        self.width_value = value;
+                    } else if destination == self.length {
+                        self.length_value = value;
+                    } else if destination == self.width_segments {
+                        self.width_segments_value = (value as usize).max(1);
+                    } else if destination == self.length_segments {
+                        self.length_segments_value = (value as usize).max(1);
+                    }
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click) => {
+                if message.destination() == self.create {
+                    let mesh = MeshBuilder::new(BaseBuilder::new().with_name("Plane"))
+                        .with_surfaces(vec![Surface::new(Arc::new(RwLock::new(
+                            make_plane_surface_data(
+                                self.width_value,
+                                self.length_value,
+                                self.width_segments_value,
+                                self.length_segments_value,
+                            ),
+                        )))])
+                        .build_node();
+                    sender
+                        .send(Message::do_scene_command(AddNodeCommand::new(mesh)))
+                        .unwrap();
+                    self.close(ui);
+                } else if message.destination() == self.cancel {
+                    self.close(ui);
+                }
+            }
+            _ => {}
+        }
+    }
+}
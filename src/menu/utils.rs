@@ -1,5 +1,12 @@
-use crate::menu::{create_menu_item, create_root_menu_item, Panels};
-use rg3d::gui::UserInterface;
+use crate::{
+    menu::{create_menu_item, create_root_menu_item, Panels},
+    scene::{
+        commands::graph::{find_non_finite_transforms, make_fix_non_finite_transforms_command},
+        EditorScene, Selection,
+    },
+    utils::make_transform_report,
+    GameEngine, Message,
+};
 use rg3d::{
     asset::core::pool::Handle,
     gui::{
@@ -7,38 +14,140 @@ use rg3d::{
         BuildContext, UiNode,
     },
 };
+use std::sync::mpsc::Sender;
 
 pub struct UtilsMenu {
     pub menu: Handle<UiNode>,
     open_path_fixer: Handle<UiNode>,
+    open_name_replacer: Handle<UiNode>,
+    open_transform_randomizer: Handle<UiNode>,
+    fix_non_finite_transforms: Handle<UiNode>,
+    log_selection_transforms: Handle<UiNode>,
 }
 
 impl UtilsMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let open_path_fixer;
+        let open_name_replacer;
+        let open_transform_randomizer;
+        let fix_non_finite_transforms;
+        let log_selection_transforms;
         let menu = create_root_menu_item(
             "Utils",
-            vec![{
-                open_path_fixer = create_menu_item("Path Fixer", vec![], ctx);
-                open_path_fixer
-            }],
+            vec![
+                {
+                    open_path_fixer = create_menu_item("Path Fixer", vec![], ctx);
+                    open_path_fixer
+                },
+                {
+                    open_name_replacer = create_menu_item("Find and Replace Names...", vec![], ctx);
+                    open_name_replacer
+                },
+                {
+                    open_transform_randomizer =
+                        create_menu_item("Randomize Transforms...", vec![], ctx);
+                    open_transform_randomizer
+                },
+                {
+                    fix_non_finite_transforms =
+                        create_menu_item("Fix Non-Finite Transforms", vec![], ctx);
+                    fix_non_finite_transforms
+                },
+                {
+                    log_selection_transforms =
+                        create_menu_item("Log Selection Transforms", vec![], ctx);
+                    log_selection_transforms
+                },
+            ],
             ctx,
         );
 
         Self {
             menu,
             open_path_fixer,
+            open_name_replacer,
+            open_transform_randomizer,
+            fix_non_finite_transforms,
+            log_selection_transforms,
         }
     }
 
-    pub fn handle_ui_message(&mut self, message: &UiMessage, panels: &Panels, ui: &UserInterface) {
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        panels: &Panels,
+        sender: &Sender<Message>,
+        editor_scene: Option<&EditorScene>,
+        engine: &GameEngine,
+    ) {
         if let UiMessageData::MenuItem(MenuItemMessage::Click) = message.data() {
             if message.destination() == self.open_path_fixer {
-                ui.send_message(WindowMessage::open_modal(
-                    panels.path_fixer,
-                    MessageDirection::ToWidget,
-                    true,
-                ));
+                engine
+                    .user_interface
+                    .send_message(WindowMessage::open_modal(
+                        panels.path_fixer,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+            } else if message.destination() == self.open_name_replacer {
+                if editor_scene.is_some() {
+                    engine
+                        .user_interface
+                        .send_message(WindowMessage::open_modal(
+                            panels.name_replacer,
+                            MessageDirection::ToWidget,
+                            true,
+                        ));
+                }
+            } else if message.destination() == self.open_transform_randomizer {
+                if editor_scene.is_some() {
+                    engine
+                        .user_interface
+                        .send_message(WindowMessage::open_modal(
+                            panels.transform_randomizer,
+                            MessageDirection::ToWidget,
+                            true,
+                        ));
+                }
+            } else if message.destination() == self.fix_non_finite_transforms {
+                if let Some(editor_scene) = editor_scene {
+                    let graph = &engine.scenes[editor_scene.scene].graph;
+                    let nodes = find_non_finite_transforms(graph, editor_scene.root);
+                    if nodes.is_empty() {
+                        sender
+                            .send(Message::Log(
+                                "No nodes with non-finite transform were found.".to_owned(),
+                            ))
+                            .unwrap();
+                    } else {
+                        for &node in nodes.iter() {
+                            sender
+                                .send(Message::Log(format!(
+                                    "Node {} ({}:{}) has non-finite transform, resetting it.",
+                                    graph[node].name(),
+                                    node.index(),
+                                    node.generation()
+                                )))
+                                .unwrap();
+                        }
+                        sender
+                            .send(Message::DoSceneCommand(
+                                make_fix_non_finite_transforms_command(graph, &nodes),
+                            ))
+                            .unwrap();
+                    }
+                }
+            } else if message.destination() == self.log_selection_transforms {
+                if let Some(editor_scene) = editor_scene {
+                    if let Selection::Graph(selection) = &editor_scene.selection {
+                        let graph = &engine.scenes[editor_scene.scene].graph;
+                        for &node in selection.nodes() {
+                            sender
+                                .send(Message::Log(make_transform_report(graph, node)))
+                                .unwrap();
+                        }
+                    }
+                }
             }
         }
     }
@@ -1,8 +1,14 @@
-use crate::menu::{create_menu_item, create_root_menu_item, Panels};
+use crate::{
+    menu::{create_menu_item, create_root_menu_item, Panels},
+    settings::Settings,
+};
 use rg3d::{
     core::pool::Handle,
     gui::{
-        message::{MenuItemMessage, MessageDirection, UiMessage, UiMessageData, WindowMessage},
+        message::{
+            MenuItemMessage, MessageDirection, UiMessage, UiMessageData, WidgetMessage,
+            WindowMessage,
+        },
         BuildContext, UiNode, UserInterface,
     },
 };
@@ -13,7 +19,9 @@ pub struct ViewMenu {
     world_outliner: Handle<UiNode>,
     asset_browser: Handle<UiNode>,
     light_panel: Handle<UiNode>,
+    camera_bookmarks: Handle<UiNode>,
     log_panel: Handle<UiNode>,
+    reset_layout: Handle<UiNode>,
 }
 
 fn switch_window_state(window: Handle<UiNode>, ui: &UserInterface, center: bool) {
@@ -32,7 +40,9 @@ impl ViewMenu {
         let world_outliner;
 
         let light_panel;
+        let camera_bookmarks;
         let log_panel;
+        let reset_layout;
 
         let menu = create_root_menu_item(
             "View",
@@ -53,10 +63,18 @@ impl ViewMenu {
                     light_panel = create_menu_item("Light Panel", vec![], ctx);
                     light_panel
                 },
+                {
+                    camera_bookmarks = create_menu_item("Camera Bookmarks", vec![], ctx);
+                    camera_bookmarks
+                },
                 {
                     log_panel = create_menu_item("Log Panel", vec![], ctx);
                     log_panel
                 },
+                {
+                    reset_layout = create_menu_item("Reset Layout", vec![], ctx);
+                    reset_layout
+                },
             ],
             ctx,
         );
@@ -67,23 +85,63 @@ impl ViewMenu {
             world_outliner,
             asset_browser,
             light_panel,
+            camera_bookmarks,
             log_panel,
+            reset_layout,
         }
     }
 
-    pub fn handle_ui_message(&mut self, message: &UiMessage, ui: &UserInterface, panels: &Panels) {
-        if let UiMessageData::MenuItem(MenuItemMessage::Click) = message.data() {
-            if message.destination() == self.asset_browser {
-                switch_window_state(panels.asset_window, ui, false);
-            } else if message.destination() == self.light_panel {
-                switch_window_state(panels.light_panel, ui, true);
-            } else if message.destination() == self.world_outliner {
-                switch_window_state(panels.world_outliner_window, ui, false);
-            } else if message.destination() == self.sidebar {
-                switch_window_state(panels.inspector_window, ui, false);
-            } else if message.destination() == self.log_panel {
-                switch_window_state(panels.log_panel, ui, false);
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        ui: &UserInterface,
+        panels: &Panels,
+        settings: &mut Settings,
+    ) {
+        match message.data() {
+            UiMessageData::MenuItem(MenuItemMessage::Click) => {
+                if message.destination() == self.asset_browser {
+                    switch_window_state(panels.asset_window, ui, false);
+                } else if message.destination() == self.light_panel {
+                    switch_window_state(panels.light_panel, ui, true);
+                } else if message.destination() == self.camera_bookmarks {
+                    switch_window_state(panels.camera_bookmarks, ui, true);
+                } else if message.destination() == self.world_outliner {
+                    switch_window_state(panels.world_outliner_window, ui, false);
+                } else if message.destination() == self.sidebar {
+                    switch_window_state(panels.inspector_window, ui, false);
+                } else if message.destination() == self.log_panel {
+                    switch_window_state(panels.log_panel, ui, false);
+                } else if message.destination() == self.reset_layout {
+                    settings.windows.reset(panels, ui);
+                    if let Err(e) = settings.save() {
+                        println!("Unable to save settings! Reason: {:?}!", e);
+                    }
+                }
+            }
+            &UiMessageData::Widget(WidgetMessage::KeyDown(key))
+                if message.direction() == MessageDirection::FromWidget =>
+            {
+                let shortcuts = &settings.panel_shortcuts;
+                for (shortcut, window, center) in [
+                    (shortcuts.sidebar, panels.inspector_window, false),
+                    (shortcuts.asset_browser, panels.asset_window, false),
+                    (
+                        shortcuts.world_outliner,
+                        panels.world_outliner_window,
+                        false,
+                    ),
+                    (shortcuts.light_panel, panels.light_panel, true),
+                    (shortcuts.camera_bookmarks, panels.camera_bookmarks, true),
+                    (shortcuts.log_panel, panels.log_panel, false),
+                ] {
+                    if shortcut.matches(key) {
+                        switch_window_state(window, ui, center);
+                        break;
+                    }
+                }
             }
+            _ => {}
         }
     }
 }
@@ -336,4 +336,8 @@ impl PreviewPanel {
     pub fn model(&self) -> Handle<Node> {
         self.model
     }
+
+    pub fn camera(&self) -> Handle<Node> {
+        self.camera
+    }
 }
@@ -15,6 +15,14 @@ pub struct Clipboard {
     empty: bool,
 }
 
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("empty", &self.empty)
+            .finish()
+    }
+}
+
 impl Default for Clipboard {
     fn default() -> Self {
         Self {
@@ -1,19 +1,28 @@
 use crate::{
-    command::Command, define_node_command, get_set_swap, physics::Physics,
-    scene::commands::SceneContext,
+    command::Command,
+    define_node_command, get_set_swap,
+    physics::Physics,
+    scene::{
+        commands::{CommandGroup, SceneCommand, SceneContext},
+        GraphSelection, Selection,
+    },
+    Message,
 };
 use rg3d::scene::base::Mobility;
 use rg3d::{
     animation::Animation,
     core::{
         algebra::{UnitQuaternion, Vector3},
+        futures::executor::block_on,
         pool::{Handle, Ticket},
     },
     engine::resource_manager::MaterialSearchOptions,
+    material::PropertyValue,
     scene::{
-        base::PhysicsBinding,
+        base::{BaseBuilder, PhysicsBinding},
         graph::{Graph, SubGraph},
         node::Node,
+        transform::TransformBuilder,
     },
 };
 use std::path::PathBuf;
@@ -41,12 +50,7 @@ impl MoveNodeCommand {
     }
 
     fn set_position(&self, graph: &mut Graph, physics: &mut Physics, position: Vector3<f32>) {
-        graph[self.node]
-            .local_transform_mut()
-            .set_position(position);
-        if let Some(&body) = physics.binder.value_of(&self.node) {
-            physics.bodies[body].position = position;
-        }
+        set_node_position(graph, physics, self.node, position);
     }
 }
 
@@ -268,6 +272,7 @@ pub struct LoadModelCommand {
     sub_graph: Option<SubGraph>,
     animations_container: Vec<(Ticket<Animation>, Animation)>,
     materials_search_options: MaterialSearchOptions,
+    position: Option<Vector3<f32>>,
 }
 
 impl LoadModelCommand {
@@ -279,6 +284,45 @@ impl LoadModelCommand {
             sub_graph: None,
             animations_container: Default::default(),
             materials_search_options,
+            position: None,
+        }
+    }
+
+    /// Places root of the instance at given position, otherwise it keeps position from the
+    /// model file.
+    pub fn with_position(mut self, position: Vector3<f32>) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+/// Logs textures of instantiated model that cannot be loaded, the model is kept as is.
+fn report_missing_textures(root: Handle<Node>, context: &SceneContext) {
+    let graph = &context.scene.graph;
+    for handle in graph.traverse_handle_iter(root) {
+        if let Node::Mesh(mesh) = &graph[handle] {
+            for surface in mesh.surfaces() {
+                let material = surface.material().lock().unwrap();
+                for (name, value) in material.properties() {
+                    if let PropertyValue::Sampler {
+                        value: Some(texture),
+                        ..
+                    } = value
+                    {
+                        if block_on(texture.clone()).is_err() {
+                            context
+                                .message_sender
+                                .send(Message::Log(format!(
+                                    "Warning: texture {} of property {} of node {} is missing!",
+                                    texture.state().path().display(),
+                                    name,
+                                    mesh.name()
+                                )))
+                                .unwrap();
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -291,18 +335,38 @@ impl Command for LoadModelCommand {
     fn execute(&mut self, context: &mut SceneContext) {
         if self.model.is_none() {
             // No model was loaded yet, do it.
-            if let Ok(model) = rg3d::core::futures::executor::block_on(
+            match block_on(
                 context
                     .resource_manager
                     .request_model(&self.path, self.materials_search_options.clone()),
             ) {
-                let instance = model.instantiate(context.scene);
-                self.model = instance.root;
-                self.animations = instance.animations;
-
-                // Enable instantiated animations.
-                for &animation in self.animations.iter() {
-                    context.scene.animations[animation].set_enabled(true);
+                Ok(model) => {
+                    let instance = model.instantiate(context.scene);
+                    self.model = instance.root;
+                    self.animations = instance.animations;
+
+                    if let Some(position) = self.position {
+                        context.scene.graph[self.model]
+                            .local_transform_mut()
+                            .set_position(position);
+                    }
+
+                    // Enable instantiated animations.
+                    for &animation in self.animations.iter() {
+                        context.scene.animations[animation].set_enabled(true);
+                    }
+
+                    report_missing_textures(self.model, context);
+                }
+                Err(e) => {
+                    context
+                        .message_sender
+                        .send(Message::Log(format!(
+                            "Unable to load model {}! Reason: {:?}",
+                            self.path.display(),
+                            e
+                        )))
+                        .unwrap();
                 }
             }
         } else {
@@ -455,6 +519,50 @@ define_node_command!(SetVisibleCommand("Set Visible", bool) where fn swap(self,
     get_set_swap!(self, node, visibility, set_visibility)
 });
 
+/// Returns `true` if node is enabled. Cameras and particle systems have their own
+/// enabled flag, for every other node visibility is used instead.
+pub fn is_node_enabled(node: &Node) -> bool {
+    match node {
+        Node::Camera(camera) => camera.is_enabled(),
+        Node::ParticleSystem(particle_system) => particle_system.is_enabled(),
+        _ => node.visibility(),
+    }
+}
+
+fn set_node_enabled(node: &mut Node, enabled: bool) {
+    match node {
+        Node::Camera(camera) => camera.set_enabled(enabled),
+        Node::ParticleSystem(particle_system) => particle_system.set_enabled(enabled),
+        _ => node.set_visibility(enabled),
+    }
+}
+
+define_node_command!(SetNodeEnabledCommand("Set Node Enabled", bool) where fn swap(self, node) {
+    let old = is_node_enabled(node);
+    set_node_enabled(node, self.value);
+    self.value = old;
+});
+
+/// Creates command that sets enabled state of every given node to `enabled`. Nodes that
+/// already are in desired state are skipped.
+pub fn make_set_enabled_command(
+    graph: &Graph,
+    nodes: &[Handle<Node>],
+    enabled: bool,
+) -> Option<SceneCommand> {
+    let commands = nodes
+        .iter()
+        .filter(|&&handle| is_node_enabled(&graph[handle]) != enabled)
+        .map(|&handle| SceneCommand::new(SetNodeEnabledCommand::new(handle, enabled)))
+        .collect::<Vec<_>>();
+
+    if commands.is_empty() {
+        None
+    } else {
+        Some(SceneCommand::new(CommandGroup::from(commands)))
+    }
+}
+
 define_node_command!(SetLifetimeCommand("Set Lifetime", Option<f32>) where fn swap(self, node) {
     get_set_swap!(self, node, lifetime, set_lifetime)
 });
@@ -502,3 +610,234 @@ define_node_command!(SetScalePivotCommand("Set Scaling Pivot", Vector3<f32>) whe
     node.local_transform_mut().set_scaling_pivot(self.value);
     self.value = temp;
 });
+
+fn is_vector_finite(v: &Vector3<f32>) -> bool {
+    v.iter().all(|c| c.is_finite())
+}
+
+fn is_rotation_finite(q: &UnitQuaternion<f32>) -> bool {
+    q.coords.iter().all(|c| c.is_finite())
+}
+
+/// Searches for scene nodes with NaN or infinite position, rotation or scale. Such values
+/// could come from bad imports or physics "explosions" and they break rendering and picking
+/// of the whole scene. Editor nodes are ignored.
+pub fn find_non_finite_transforms(graph: &Graph, editor_root: Handle<Node>) -> Vec<Handle<Node>> {
+    let mut result = Vec::new();
+    let mut stack = vec![graph.get_root()];
+    while let Some(handle) = stack.pop() {
+        if handle == editor_root {
+            continue;
+        }
+
+        let node = &graph[handle];
+        let transform = node.local_transform();
+        if !is_vector_finite(transform.position())
+            || !is_rotation_finite(transform.rotation())
+            || !is_vector_finite(transform.scale())
+        {
+            result.push(handle);
+        }
+
+        stack.extend_from_slice(node.children());
+    }
+    result
+}
+
+/// Creates command that resets every non-finite transform component of given nodes to
+/// identity (zero position, identity rotation and unit scale).
+pub fn make_fix_non_finite_transforms_command(
+    graph: &Graph,
+    nodes: &[Handle<Node>],
+) -> SceneCommand {
+    let mut group = CommandGroup::from(Vec::new());
+
+    for &handle in nodes {
+        let transform = graph[handle].local_transform();
+
+        let position = **transform.position();
+        if !is_vector_finite(&position) {
+            group.push(SceneCommand::new(MoveNodeCommand::new(
+                handle,
+                position,
+                Vector3::default(),
+            )));
+        }
+
+        let rotation = **transform.rotation();
+        if !is_rotation_finite(&rotation) {
+            group.push(SceneCommand::new(RotateNodeCommand::new(
+                handle,
+                rotation,
+                UnitQuaternion::identity(),
+            )));
+        }
+
+        let scale = **transform.scale();
+        if !is_vector_finite(&scale) {
+            group.push(SceneCommand::new(ScaleNodeCommand::new(
+                handle,
+                scale,
+                Vector3::new(1.0, 1.0, 1.0),
+            )));
+        }
+    }
+
+    SceneCommand::new(group)
+}
+
+#[derive(Debug)]
+struct GroupedNode {
+    handle: Handle<Node>,
+    parent: Handle<Node>,
+    old_position: Vector3<f32>,
+    new_position: Vector3<f32>,
+}
+
+/// Creates new pivot node and attaches given nodes to it, the pivot becomes selected.
+/// Positions of the nodes are adjusted so they stay where they were.
+#[derive(Debug)]
+pub struct GroupNodesCommand {
+    ticket: Option<Ticket<Node>>,
+    pivot: Handle<Node>,
+    pivot_node: Option<Node>,
+    parent: Handle<Node>,
+    nodes: Vec<GroupedNode>,
+    old_selection: Selection,
+}
+
+impl GroupNodesCommand {
+    pub fn new(graph: &Graph, nodes: &[Handle<Node>], old_selection: Selection) -> Self {
+        // Keep the pivot in the same space as the nodes if they share a parent, otherwise
+        // put it on the root and work with global positions.
+        let common_parent = nodes.first().map(|&n| graph[n].parent());
+        let parent = match common_parent {
+            Some(parent) if nodes.iter().all(|&n| graph[n].parent() == parent) => parent,
+            _ => graph.get_root(),
+        };
+
+        let position_in_parent = |handle: Handle<Node>| {
+            if graph[handle].parent() == parent {
+                **graph[handle].local_transform().position()
+            } else {
+                graph[handle].global_position()
+            }
+        };
+
+        let pivot_position = if nodes.is_empty() {
+            Vector3::default()
+        } else {
+            nodes
+                .iter()
+                .map(|&n| position_in_parent(n))
+                .sum::<Vector3<f32>>()
+                .scale(1.0 / nodes.len() as f32)
+        };
+
+        let pivot_node = BaseBuilder::new()
+            .with_name("Pivot")
+            .with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(pivot_position)
+                    .build(),
+            )
+            .build_node();
+
+        Self {
+            ticket: None,
+            pivot: Default::default(),
+            pivot_node: Some(pivot_node),
+            parent,
+            nodes: nodes
+                .iter()
+                .map(|&handle| GroupedNode {
+                    handle,
+                    parent: graph[handle].parent(),
+                    old_position: **graph[handle].local_transform().position(),
+                    new_position: position_in_parent(handle) - pivot_position,
+                })
+                .collect(),
+            old_selection,
+        }
+    }
+
+    fn set_selection(&self, context: &mut SceneContext, selection: Selection) {
+        if selection != context.editor_scene.selection {
+            context.editor_scene.selection = selection;
+            context
+                .message_sender
+                .send(Message::SelectionChanged)
+                .unwrap();
+        }
+    }
+}
+
+fn set_node_position(
+    graph: &mut Graph,
+    physics: &mut Physics,
+    node: Handle<Node>,
+    position: Vector3<f32>,
+) {
+    graph[node].local_transform_mut().set_position(position);
+    if let Some(&body) = physics.binder.value_of(&node) {
+        physics.bodies[body].position = position;
+    }
+}
+
+impl Command for GroupNodesCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Group Nodes".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        let graph = &mut context.scene.graph;
+        match self.ticket.take() {
+            None => {
+                self.pivot = graph.add_node(self.pivot_node.take().unwrap());
+            }
+            Some(ticket) => {
+                let handle = graph.put_back(ticket, self.pivot_node.take().unwrap());
+                assert_eq!(handle, self.pivot);
+            }
+        }
+        graph.link_nodes(self.pivot, self.parent);
+
+        for node in self.nodes.iter() {
+            graph.link_nodes(node.handle, self.pivot);
+            set_node_position(
+                graph,
+                &mut context.editor_scene.physics,
+                node.handle,
+                node.new_position,
+            );
+        }
+
+        let selection = Selection::Graph(GraphSelection::single_or_empty(self.pivot));
+        self.set_selection(context, selection);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        let graph = &mut context.scene.graph;
+        for node in self.nodes.iter().rev() {
+            graph.link_nodes(node.handle, node.parent);
+            set_node_position(
+                graph,
+                &mut context.editor_scene.physics,
+                node.handle,
+                node.old_position,
+            );
+        }
+
+        let (ticket, pivot_node) = graph.take_reserve(self.pivot);
+        self.ticket = Some(ticket);
+        self.pivot_node = Some(pivot_node);
+
+        self.set_selection(context, self.old_selection.clone());
+    }
+
+    fn finalize(&mut self, context: &mut SceneContext) {
+        if let Some(ticket) = self.ticket.take() {
+            context.scene.graph.forget_ticket(ticket)
+        }
+    }
+}
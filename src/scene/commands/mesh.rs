@@ -1,6 +1,6 @@
 use crate::{command::Command, define_node_command, get_set_swap, scene::commands::SceneContext};
 use rg3d::material::shader::SamplerFallback;
-use rg3d::material::PropertyValue;
+use rg3d::material::{Material, PropertyValue};
 use rg3d::{
     core::pool::Handle,
     resource::texture::Texture,
@@ -10,6 +10,7 @@ use rg3d::{
         node::Node,
     },
 };
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug)]
 enum TextureSet {
@@ -126,3 +127,80 @@ define_node_command!(SetMeshRenderPathCommand("Set Mesh Render Path", RenderPath
 define_node_command!(SetMeshDecalLayerIndexCommand("Set Mesh Decal Layer Index", u8) where fn swap(self, node) {
     get_set_swap!(self, node.as_mesh_mut(), decal_layer_index, set_decal_layer_index);
 });
+
+#[derive(Debug)]
+pub struct SetMeshSurfaceMaterialCommand {
+    node: Handle<Node>,
+    surface_index: usize,
+    material: Arc<Mutex<Material>>,
+}
+
+impl SetMeshSurfaceMaterialCommand {
+    pub fn new(node: Handle<Node>, surface_index: usize, material: Arc<Mutex<Material>>) -> Self {
+        Self {
+            node,
+            surface_index,
+            material,
+        }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        let surface =
+            &mut context.scene.graph[self.node].as_mesh_mut().surfaces_mut()[self.surface_index];
+        let old_material = surface.material().clone();
+        surface.set_material(std::mem::replace(&mut self.material, old_material));
+    }
+}
+
+impl Command for SetMeshSurfaceMaterialCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Surface Material".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
+
+/// Sets the same material on every surface of a mesh.
+#[derive(Debug)]
+pub struct SetMeshSurfacesMaterialCommand {
+    node: Handle<Node>,
+    // One material per surface, old materials are stored here after execution.
+    materials: Vec<Arc<Mutex<Material>>>,
+}
+
+impl SetMeshSurfacesMaterialCommand {
+    pub fn new(node: Handle<Node>, material: Arc<Mutex<Material>>, surface_count: usize) -> Self {
+        Self {
+            node,
+            materials: vec![material; surface_count],
+        }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        let surfaces = context.scene.graph[self.node].as_mesh_mut().surfaces_mut();
+        for (surface, material) in surfaces.iter_mut().zip(self.materials.iter_mut()) {
+            let old_material = surface.material().clone();
+            surface.set_material(std::mem::replace(material, old_material));
+        }
+    }
+}
+
+impl Command for SetMeshSurfacesMaterialCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Material For All Surfaces".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
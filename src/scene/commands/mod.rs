@@ -3,9 +3,9 @@ use crate::{
     command::Command,
     physics::{Collider, Joint, RigidBody},
     scene::{
-        clipboard::DeepCloneResult,
+        clipboard::{Clipboard, DeepCloneResult},
         commands::{
-            graph::DeleteSubGraphCommand,
+            graph::{DeleteSubGraphCommand, GroupNodesCommand},
             physics::{DeleteBodyCommand, DeleteColliderCommand, DeleteJointCommand},
         },
         EditorScene, GraphSelection, Selection,
@@ -130,6 +130,163 @@ impl Command for CommandGroup {
             cmd.finalize(context);
         }
     }
+
+    fn is_selection_only(&self) -> bool {
+        self.commands.iter().all(|cmd| cmd.is_selection_only())
+    }
+}
+
+/// Creates command that makes a copy of every selected node (with its physics entities)
+/// and selects the copies. Returns `None` if there is nothing to duplicate.
+pub fn make_duplicate_selection_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+) -> Option<SceneCommand> {
+    if let Selection::Graph(selection) = &editor_scene.selection {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+
+        // Graph's root cannot be duplicated.
+        let mut selection = selection.clone();
+        selection.nodes.retain(|&n| n != graph.get_root());
+
+        if !selection.is_empty() {
+            let mut clipboard = Clipboard::default();
+            clipboard.fill_from_selection(
+                &selection,
+                editor_scene.scene,
+                &editor_scene.physics,
+                engine,
+            );
+            return Some(SceneCommand::new(PasteCommand::duplicate(clipboard)));
+        }
+    }
+    None
+}
+
+/// Creates command that attaches selected nodes to a new pivot placed at their average
+/// position. Creates just a pivot if nothing is selected.
+pub fn make_group_selection_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+) -> SceneCommand {
+    let graph = &engine.scenes[editor_scene.scene].graph;
+
+    let nodes = if let Selection::Graph(selection) = &editor_scene.selection {
+        // Graph's root cannot be attached to anything.
+        let mut selection = selection.clone();
+        selection.nodes.retain(|&n| n != graph.get_root());
+        selection.root_nodes(graph)
+    } else {
+        Default::default()
+    };
+
+    SceneCommand::new(GroupNodesCommand::new(
+        graph,
+        &nodes,
+        editor_scene.selection.clone(),
+    ))
+}
+
+/// Returns every node of the scene except graph's root and the nodes of the editor itself.
+fn selectable_nodes(editor_scene: &EditorScene, engine: &GameEngine) -> Vec<Handle<Node>> {
+    let graph = &engine.scenes[editor_scene.scene].graph;
+
+    let mut nodes = Vec::new();
+    let mut stack = vec![graph.get_root()];
+    while let Some(handle) = stack.pop() {
+        // Editor's hierarchy (camera, gizmos, etc.) is not a part of the scene.
+        if handle == editor_scene.root {
+            continue;
+        }
+        if handle != graph.get_root() {
+            nodes.push(handle);
+        }
+        stack.extend_from_slice(graph[handle].children());
+    }
+    nodes
+}
+
+/// Creates command that replaces current selection with given one. Returns `None` if the
+/// selection would not change.
+fn make_change_selection_command(
+    editor_scene: &EditorScene,
+    new_selection: Selection,
+) -> Option<SceneCommand> {
+    if new_selection == editor_scene.selection {
+        None
+    } else {
+        Some(SceneCommand::new(ChangeSelectionCommand::new(
+            new_selection,
+            editor_scene.selection.clone(),
+        )))
+    }
+}
+
+/// Creates command that selects every node of the scene except graph's root and the nodes
+/// of the editor itself. Returns `None` if everything is selected already.
+pub fn make_select_all_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+) -> Option<SceneCommand> {
+    let nodes = selectable_nodes(editor_scene, engine);
+    make_change_selection_command(
+        editor_scene,
+        Selection::Graph(GraphSelection::from_list(nodes)),
+    )
+}
+
+/// Creates command that selects every selectable node that is not selected now and
+/// deselects the rest. Returns `None` if the selection would not change.
+pub fn make_invert_selection_command(
+    editor_scene: &EditorScene,
+    engine: &GameEngine,
+) -> Option<SceneCommand> {
+    let mut nodes = selectable_nodes(editor_scene, engine);
+    if let Selection::Graph(selection) = &editor_scene.selection {
+        nodes.retain(|n| !selection.contains(*n));
+    }
+
+    let new_selection = if nodes.is_empty() {
+        Selection::None
+    } else {
+        Selection::Graph(GraphSelection::from_list(nodes))
+    };
+    make_change_selection_command(editor_scene, new_selection)
+}
+
+/// Creates command that clears selection. Returns `None` if nothing is selected.
+pub fn make_deselect_all_command(editor_scene: &EditorScene) -> Option<SceneCommand> {
+    if editor_scene.selection.is_empty() {
+        None
+    } else {
+        Some(SceneCommand::new(ChangeSelectionCommand::new(
+            Selection::None,
+            editor_scene.selection.clone(),
+        )))
+    }
+}
+
+/// Puts selected nodes to the editor's clipboard and creates command that removes them
+/// from the scene. Returns `None` if there is nothing to cut.
+pub fn cut_selection(editor_scene: &mut EditorScene, engine: &GameEngine) -> Option<SceneCommand> {
+    if let Selection::Graph(selection) = &editor_scene.selection {
+        let graph = &engine.scenes[editor_scene.scene].graph;
+
+        // Graph's root is non-deletable, so it cannot be cut either.
+        let mut selection = selection.clone();
+        selection.nodes.retain(|&n| n != graph.get_root());
+
+        if !selection.is_empty() {
+            editor_scene.clipboard.fill_from_selection(
+                &selection,
+                editor_scene.scene,
+                &editor_scene.physics,
+                engine,
+            );
+            return Some(make_delete_selection_command(editor_scene, engine));
+        }
+    }
+    None
 }
 
 /// Creates scene command (command group) which removes current selection in editor's scene.
@@ -271,6 +428,10 @@ impl Command for ChangeSelectionCommand {
                 .unwrap();
         }
     }
+
+    fn is_selection_only(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug)]
@@ -294,6 +455,8 @@ enum PasteCommandState {
 #[derive(Debug)]
 pub struct PasteCommand {
     state: PasteCommandState,
+    /// Private clipboard to paste from instead of the editor's one, used for duplication.
+    clipboard: Option<Clipboard>,
 }
 
 impl Default for PasteCommand {
@@ -306,22 +469,38 @@ impl PasteCommand {
     pub fn new() -> Self {
         Self {
             state: PasteCommandState::NonExecuted,
+            clipboard: None,
+        }
+    }
+
+    /// Creates command that pastes contents of given clipboard, editor's clipboard
+    /// stays untouched.
+    pub fn duplicate(clipboard: Clipboard) -> Self {
+        Self {
+            state: PasteCommandState::NonExecuted,
+            clipboard: Some(clipboard),
         }
     }
 }
 
 impl Command for PasteCommand {
     fn name(&mut self, _context: &SceneContext) -> String {
-        "Paste".to_owned()
+        if self.clipboard.is_some() {
+            "Duplicate".to_owned()
+        } else {
+            "Paste".to_owned()
+        }
     }
 
     fn execute(&mut self, context: &mut SceneContext) {
         match std::mem::replace(&mut self.state, PasteCommandState::Undefined) {
             PasteCommandState::NonExecuted => {
-                let paste_result = context
-                    .editor_scene
-                    .clipboard
-                    .paste(&mut context.scene.graph, &mut context.editor_scene.physics);
+                let clipboard = match self.clipboard.as_mut() {
+                    Some(clipboard) => clipboard,
+                    None => &mut context.editor_scene.clipboard,
+                };
+                let paste_result =
+                    clipboard.paste(&mut context.scene.graph, &mut context.editor_scene.physics);
 
                 let mut selection =
                     Selection::Graph(GraphSelection::from_list(paste_result.root_nodes.clone()));
@@ -4,8 +4,13 @@ use crate::{
 };
 use rg3d::{
     core::pool::Handle,
-    scene::{graph::Graph, node::Node, terrain::Layer},
+    scene::{
+        graph::Graph,
+        node::Node,
+        terrain::{Layer, Terrain},
+    },
 };
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct AddTerrainLayerCommand {
@@ -80,38 +85,185 @@ impl Command for DeleteTerrainLayerCommand {
     }
 }
 
+/// Moves a layer to other position in the list of layers. Layer is moved as a whole, so its
+/// masks stay with its material.
 #[derive(Debug)]
-pub struct ModifyTerrainHeightCommand {
+pub struct MoveTerrainLayerCommand {
     terrain: Handle<Node>,
-    // TODO: This is very memory-inefficient solution, it could be done
-    //  better by either pack/unpack data on the fly, or by saving changes
-    //  for sub-chunks.
-    old_heightmaps: Vec<Vec<f32>>,
-    new_heightmaps: Vec<Vec<f32>>,
+    from: usize,
+    to: usize,
 }
 
-impl ModifyTerrainHeightCommand {
-    pub fn new(
-        terrain: Handle<Node>,
-        old_heightmaps: Vec<Vec<f32>>,
-        new_heightmaps: Vec<Vec<f32>>,
-    ) -> Self {
+impl MoveTerrainLayerCommand {
+    pub fn new(terrain: Handle<Node>, from: usize, to: usize) -> Self {
+        Self { terrain, from, to }
+    }
+
+    fn move_layer(&self, context: &mut SceneContext, from: usize, to: usize) {
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        let layer = terrain.remove_layer(from);
+        terrain.insert_layer(layer, to);
+    }
+}
+
+impl Command for MoveTerrainLayerCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Move Terrain Layer".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.move_layer(context, self.from, self.to);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.move_layer(context, self.to, self.from);
+    }
+}
+
+/// Gives a name to a layer, empty name removes the name. Names are stored by the editor
+/// scene, see `EditorScene::terrain_layer_names`.
+#[derive(Debug)]
+pub struct RenameTerrainLayerCommand {
+    terrain: Handle<Node>,
+    layer: usize,
+    name: String,
+}
+
+impl RenameTerrainLayerCommand {
+    pub fn new(terrain: Handle<Node>, layer: usize, name: String) -> Self {
         Self {
             terrain,
-            old_heightmaps,
-            new_heightmaps,
+            layer,
+            name,
+        }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        let material = context.scene.graph[self.terrain].as_terrain().layers()[self.layer]
+            .material
+            .clone();
+
+        let names = &mut context.editor_scene.terrain_layer_names;
+        let old_name = match names.iter().position(|(m, _)| Arc::ptr_eq(m, &material)) {
+            Some(index) => names.remove(index).1,
+            None => String::new(),
+        };
+        let new_name = std::mem::replace(&mut self.name, old_name);
+        if !new_name.is_empty() {
+            names.push((material, new_name));
         }
     }
+}
+
+impl Command for RenameTerrainLayerCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Rename Terrain Layer".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
+
+/// Replaces size, resolution, height map and layers of a terrain with the ones of other
+/// terrain. The node itself (its name, transform, children, etc.) stays the same.
+#[derive(Debug)]
+pub struct ResizeTerrainCommand {
+    terrain: Handle<Node>,
+    data: Terrain,
+}
+
+impl ResizeTerrainCommand {
+    pub fn new(terrain: Handle<Node>, data: Terrain) -> Self {
+        Self { terrain, data }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        std::mem::swap(terrain, &mut self.data);
+        // Only terrain data is exchanged, base goes back to the node.
+        std::mem::swap(&mut **terrain, &mut *self.data);
+    }
+}
+
+impl Command for ResizeTerrainCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Resize Terrain".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
+
+/// Modified part of a chunk's height map or layer mask. Only the span between the first
+/// and the last changed sample is stored, so small strokes on large terrains are cheap
+/// to keep in the command stack.
+#[derive(Debug)]
+pub struct TerrainRegion<T> {
+    chunk: usize,
+    // Index of the first sample of the region in chunk's data.
+    offset: usize,
+    old: Vec<T>,
+    new: Vec<T>,
+}
+
+impl<T: Copy + PartialEq> TerrainRegion<T> {
+    fn swap(&mut self, data: &mut [T]) {
+        data[self.offset..(self.offset + self.new.len())].copy_from_slice(&self.new);
+        std::mem::swap(&mut self.old, &mut self.new);
+    }
+}
+
+/// Compares per-chunk data before and after an edit and returns the modified regions.
+/// Chunks that were not changed are skipped entirely.
+pub fn diff_terrain_regions<'a, T, I>(old: &[Vec<T>], new: I) -> Vec<TerrainRegion<T>>
+where
+    T: Copy + PartialEq + 'a,
+    I: IntoIterator<Item = &'a [T]>,
+{
+    old.iter()
+        .zip(new)
+        .enumerate()
+        .filter_map(|(chunk, (old, new))| {
+            let first = old.iter().zip(new.iter()).position(|(a, b)| a != b)?;
+            let last = old.iter().zip(new.iter()).rposition(|(a, b)| a != b)?;
+            Some(TerrainRegion {
+                chunk,
+                offset: first,
+                old: old[first..=last].to_vec(),
+                new: new[first..=last].to_vec(),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct ModifyTerrainHeightCommand {
+    terrain: Handle<Node>,
+    regions: Vec<TerrainRegion<f32>>,
+}
+
+impl ModifyTerrainHeightCommand {
+    pub fn new(terrain: Handle<Node>, regions: Vec<TerrainRegion<f32>>) -> Self {
+        Self { terrain, regions }
+    }
 
     pub fn swap(&mut self, context: &mut SceneContext) {
         let terrain = context.scene.graph[self.terrain].as_terrain_mut();
-        for (chunk, (old, new)) in terrain.chunks_mut().iter_mut().zip(
-            self.old_heightmaps
-                .iter_mut()
-                .zip(self.new_heightmaps.iter_mut()),
-        ) {
-            chunk.set_heightmap(new.clone());
-            std::mem::swap(old, new);
+        for region in self.regions.iter_mut() {
+            let chunk = &mut terrain.chunks_mut()[region.chunk];
+            let mut heightmap = chunk.heightmap().to_vec();
+            region.swap(&mut heightmap);
+            chunk.set_heightmap(heightmap);
         }
     }
 }
@@ -133,45 +285,25 @@ impl Command for ModifyTerrainHeightCommand {
 #[derive(Debug)]
 pub struct ModifyTerrainLayerMaskCommand {
     terrain: Handle<Node>,
-    // TODO: This is very memory-inefficient solution, it could be done
-    //  better by either pack/unpack data on the fly, or by saving changes
-    //  for sub-chunks.
-    old_masks: Vec<Vec<u8>>,
-    new_masks: Vec<Vec<u8>>,
+    regions: Vec<TerrainRegion<u8>>,
     layer: usize,
 }
 
 impl ModifyTerrainLayerMaskCommand {
-    pub fn new(
-        terrain: Handle<Node>,
-        old_masks: Vec<Vec<u8>>,
-        new_masks: Vec<Vec<u8>>,
-        layer: usize,
-    ) -> Self {
+    pub fn new(terrain: Handle<Node>, regions: Vec<TerrainRegion<u8>>, layer: usize) -> Self {
         Self {
             terrain,
-            old_masks,
-            new_masks,
+            regions,
             layer,
         }
     }
 
     pub fn swap(&mut self, context: &mut SceneContext) {
         let terrain = context.scene.graph[self.terrain].as_terrain_mut();
-        for (chunk_mask, (old, new)) in terrain.layers_mut()[self.layer]
-            .chunk_masks()
-            .iter()
-            .zip(self.old_masks.iter_mut().zip(self.new_masks.iter_mut()))
-        {
-            let mut texture_data = chunk_mask.data_ref();
-
-            for (mask_pixel, new_pixel) in
-                texture_data.modify().data_mut().iter_mut().zip(new.iter())
-            {
-                *mask_pixel = *new_pixel;
-            }
-
-            std::mem::swap(old, new);
+        let chunk_masks = terrain.layers_mut()[self.layer].chunk_masks();
+        for region in self.regions.iter_mut() {
+            let mut texture_data = chunk_masks[region.chunk].data_ref();
+            region.swap(texture_data.modify().data_mut());
         }
     }
 }
@@ -225,3 +357,92 @@ impl Command for SetTerrainLayerMaskPropertyNameCommand {
         self.swap(context);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn swap_all<T: Copy + PartialEq>(regions: &mut [TerrainRegion<T>], chunks: &mut [Vec<T>]) {
+        for region in regions.iter_mut() {
+            region.swap(&mut chunks[region.chunk]);
+        }
+    }
+
+    fn diff(old: &[Vec<f32>], new: &[Vec<f32>]) -> Vec<TerrainRegion<f32>> {
+        diff_terrain_regions(old, new.iter().map(|c| c.as_slice()))
+    }
+
+    #[test]
+    fn overlapping_regions_are_undone_in_order() {
+        let original = vec![vec![0.0; 10]];
+
+        let mut first_stroke = original.clone();
+        first_stroke[0][2..6].copy_from_slice(&[1.0; 4]);
+        let mut second_stroke = first_stroke.clone();
+        second_stroke[0][4..8].copy_from_slice(&[2.0; 4]);
+
+        let mut first = diff(&original, &first_stroke);
+        let mut second = diff(&first_stroke, &second_stroke);
+        assert_eq!((first[0].offset, first[0].new.len()), (2, 4));
+        assert_eq!((second[0].offset, second[0].new.len()), (4, 4));
+
+        // Each command is executed right after its stroke was drawn, so execution changes
+        // nothing.
+        let mut chunks = first_stroke.clone();
+        swap_all(&mut first, &mut chunks);
+        assert_eq!(chunks, first_stroke);
+        chunks = second_stroke.clone();
+        swap_all(&mut second, &mut chunks);
+        assert_eq!(chunks, second_stroke);
+
+        swap_all(&mut second, &mut chunks);
+        assert_eq!(chunks, first_stroke);
+        swap_all(&mut first, &mut chunks);
+        assert_eq!(chunks, original);
+
+        swap_all(&mut first, &mut chunks);
+        swap_all(&mut second, &mut chunks);
+        assert_eq!(chunks, second_stroke);
+    }
+
+    #[test]
+    fn region_crossing_chunk_border() {
+        let original = vec![vec![0.0; 4], vec![0.0; 4], vec![0.0; 4]];
+
+        // Neighbour chunks share the border samples, so a stroke over the border changes
+        // the end of one chunk and the beginning of the other one.
+        let mut modified = original.clone();
+        modified[0][2..4].copy_from_slice(&[1.0, 2.0]);
+        modified[1][0..2].copy_from_slice(&[2.0, 1.0]);
+
+        let mut regions = diff(&original, &modified);
+        assert_eq!(regions.len(), 2);
+        assert_eq!((regions[0].chunk, regions[0].offset), (0, 2));
+        assert_eq!((regions[1].chunk, regions[1].offset), (1, 0));
+
+        let mut chunks = modified.clone();
+        swap_all(&mut regions, &mut chunks);
+        assert_eq!(chunks, modified);
+        swap_all(&mut regions, &mut chunks);
+        assert_eq!(chunks, original);
+        assert_eq!(chunks[2], original[2]);
+    }
+
+    #[test]
+    fn double_swap_restores_heights() {
+        let original = vec![vec![0.0, 1.0, 2.0, 3.0, 4.0]];
+        let mut modified = original.clone();
+        modified[0][1] = 5.0;
+        modified[0][3] = 6.0;
+
+        let mut regions = diff(&original, &modified);
+        // Unchanged samples between the changed ones are kept in the region too.
+        assert_eq!(regions[0].old, vec![1.0, 2.0, 3.0]);
+
+        let mut chunks = original.clone();
+        swap_all(&mut regions, &mut chunks);
+        assert_eq!(chunks, modified);
+        swap_all(&mut regions, &mut chunks);
+        assert_eq!(chunks, original);
+    }
+}
@@ -13,19 +13,34 @@ use crate::{
 };
 use rg3d::{
     core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
         pool::{Handle, Pool},
         visitor::{Visit, Visitor},
     },
-    scene::{node::Node, Scene},
+    material::Material,
+    scene::{graph::Graph, node::Node, terrain::Layer, Scene},
     sound::math::TriangleDefinition,
 };
-use std::{collections::HashMap, fmt::Write, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 pub mod clipboard;
 
 #[macro_use]
 pub mod commands;
 
+/// "Align to Surface" action that waits for a click on a surface.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SurfaceAlignment {
+    /// Nodes keep their rotation around world Y axis and are only tilted to the slope.
+    pub keep_yaw: bool,
+}
+
 pub struct EditorScene {
     pub path: Option<PathBuf>,
     pub scene: Handle<Scene>,
@@ -38,10 +53,161 @@ pub struct EditorScene {
     // but some parts are not because of incompatible data model.
     pub physics: Physics,
     pub navmeshes: Pool<Navmesh>,
+    // Set when any command was done, undone or redone since last save.
+    pub has_unsaved_changes: bool,
+    // Set when any command was done, undone or redone since last auto-save or save, so an
+    // unchanged scene is not auto-saved again and again.
+    pub has_changes_since_autosave: bool,
+    // Set while the editor waits for a click on a surface to align selected nodes to it.
+    pub surface_alignment: Option<SurfaceAlignment>,
+    // Names of terrain layers given by the user. Engine's terrain layers have no names, so
+    // they are kept by the editor and matched with layers by their materials, a layer keeps
+    // its material when it is moved, removed and restored.
+    pub terrain_layer_names: Vec<(Arc<Mutex<Material>>, String)>,
 }
 
 impl EditorScene {
+    /// Returns name given to a terrain layer by the user, if any.
+    pub fn terrain_layer_name(&self, layer: &Layer) -> Option<&str> {
+        self.terrain_layer_names
+            .iter()
+            .find(|(material, _)| Arc::ptr_eq(material, &layer.material))
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns world-space bounding box of every mesh and terrain of the scene, editor
+    /// nodes are ignored.
+    pub fn content_bounding_box(&self, engine: &GameEngine) -> Option<AxisAlignedBoundingBox> {
+        let graph = &engine.scenes[self.scene].graph;
+
+        let mut nodes = Vec::new();
+        let mut stack = vec![graph.get_root()];
+        while let Some(handle) = stack.pop() {
+            if handle == self.root {
+                continue;
+            }
+            let node = &graph[handle];
+            if matches!(node, Node::Mesh(_) | Node::Terrain(_)) {
+                nodes.push(handle);
+            }
+            stack.extend_from_slice(node.children());
+        }
+
+        GraphSelection::from_list(nodes).world_bounding_box(graph)
+    }
+
+    /// Switches the viewport between the editor camera and the selected scene camera.
+    /// Returns a message for the log.
+    pub fn toggle_look_through(&mut self, engine: &mut GameEngine) -> String {
+        let graph = &mut engine.scenes[self.scene].graph;
+
+        if self.camera_controller.is_looking_through() {
+            self.camera_controller.stop_looking_through(graph);
+            return "Viewport is rendered through the editor camera.".to_owned();
+        }
+
+        let camera = match &self.selection {
+            Selection::Graph(selection) => selection
+                .nodes()
+                .iter()
+                .cloned()
+                .find(|&handle| matches!(graph[handle], Node::Camera(_))),
+            _ => None,
+        };
+
+        match camera {
+            Some(camera) => {
+                self.camera_controller.look_through(camera, graph);
+                format!(
+                    "Looking through camera {}, toggle again to return to the editor camera.",
+                    graph[camera].name()
+                )
+            }
+            None => "Select a camera to look through it.".to_owned(),
+        }
+    }
+
+    /// Writes selected sub-trees to a separate scene file that can be instantiated later as
+    /// a model. Roots of the sub-trees are placed relative to the first selected root, so it
+    /// becomes the origin of the prefab, while their world rotation and scale are kept.
+    pub fn export_selection(
+        &self,
+        selection: &GraphSelection,
+        path: &Path,
+        engine: &mut GameEngine,
+    ) -> Result<String, String> {
+        let mut clipboard = Clipboard::default();
+        clipboard.fill_from_selection(selection, self.scene, &self.physics, engine);
+
+        let mut prefab = Scene::new();
+        let mut physics = Physics::default();
+        let copies = clipboard.paste(&mut prefab.graph, &mut physics);
+
+        let graph = &engine.scenes[self.scene].graph;
+        let roots = selection.root_nodes(graph);
+        let origin = match roots.first() {
+            Some(&first) => graph[first].global_position(),
+            None => return Err("Nothing to export, selection is empty!".to_owned()),
+        };
+
+        // Copies of the roots are linked directly to the root of the prefab, so their
+        // transforms must include transforms of their former ancestors.
+        for (&original, &copy) in roots.iter().zip(copies.root_nodes.iter()) {
+            let position = graph[original].global_position() - origin;
+            let rotation = graph.global_rotation(original);
+            let scale = global_scale(graph, original);
+            prefab.graph[copy]
+                .local_transform_mut()
+                .set_position(position)
+                .set_rotation(rotation)
+                .set_scale(scale);
+        }
+
+        // Physics binder moves bound nodes to their bodies, so copied bodies must be moved
+        // by the same offset as the nodes, otherwise the prefab ends up at the old location.
+        for &body in copies.bodies.iter() {
+            physics.bodies[body].position -= origin;
+        }
+
+        for node in prefab.graph.linear_iter_mut() {
+            if let Node::ParticleSystem(particle_system) = node {
+                particle_system.clear_particles();
+            }
+        }
+
+        let (desc, binder) = physics.generate_engine_desc();
+        prefab.physics.desc = Some(desc);
+        prefab.physics_binder.enabled = true;
+        for (node, body) in binder {
+            prefab.physics_binder.bind(node, body);
+        }
+
+        let mut visitor = Visitor::new();
+        prefab.visit("Scene", &mut visitor).unwrap();
+        if let Err(e) = visitor.save_binary(path) {
+            Err(format!("Failed to export selection! Reason: {}", e))
+        } else {
+            Ok(format!("Selection was exported to {}!", path.display()))
+        }
+    }
+
     pub fn save(&mut self, path: PathBuf, engine: &mut GameEngine) -> Result<String, String> {
+        let result = self.write(&path, engine);
+        if result.is_ok() {
+            self.path = Some(path);
+            self.has_unsaved_changes = false;
+            self.has_changes_since_autosave = false;
+        }
+        result
+    }
+
+    /// Writes the scene to given path, but unlike `save` the scene stays bound to its
+    /// current path and keeps its unsaved changes flag.
+    pub fn save_copy(&self, path: PathBuf, engine: &mut GameEngine) -> Result<String, String> {
+        self.write(&path, engine)
+    }
+
+    fn write(&self, path: &Path, engine: &mut GameEngine) -> Result<String, String> {
         let scene = &mut engine.scenes[self.scene];
 
         // Validate first.
@@ -71,8 +237,6 @@ impl EditorScene {
         }
 
         if valid {
-            self.path = Some(path.clone());
-
             let editor_root = self.root;
             let (mut pure_scene, old_to_new) = scene.clone(&mut |node, _| node != editor_root);
 
@@ -85,6 +249,16 @@ impl EditorScene {
                 }
             }
 
+            // Looking through a camera toggles scene cameras, they must be saved as they were.
+            for (handle, enabled) in self.camera_controller.original_camera_states() {
+                if let Some(Node::Camera(camera)) = old_to_new
+                    .get(&handle)
+                    .and_then(|&handle| pure_scene.graph.try_get_mut(handle))
+                {
+                    camera.set_enabled(enabled);
+                }
+            }
+
             pure_scene.navmeshes.clear();
 
             for navmesh in self.navmeshes.iter() {
@@ -129,7 +303,7 @@ impl EditorScene {
             }
             let mut visitor = Visitor::new();
             pure_scene.visit("Scene", &mut visitor).unwrap();
-            if let Err(e) = visitor.save_binary(&path) {
+            if let Err(e) = visitor.save_binary(path) {
                 Err(format!("Failed to save scene! Reason: {}", e.to_string()))
             } else {
                 Ok(format!("Scene {} was successfully saved!", path.display()))
@@ -142,6 +316,21 @@ impl EditorScene {
     }
 }
 
+/// Returns accumulated scale of a node and its ancestors, rotations are ignored.
+fn global_scale(graph: &Graph, node: Handle<Node>) -> Vector3<f32> {
+    let mut scale = Vector3::new(1.0, 1.0, 1.0);
+    let mut handle = node;
+    while handle.is_some() {
+        let node = &graph[handle];
+        let local_scale = node.local_transform().scale();
+        scale.x *= local_scale.x;
+        scale.y *= local_scale.y;
+        scale.z *= local_scale.z;
+        handle = node.parent();
+    }
+    scale
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Selection {
     None,
@@ -0,0 +1,108 @@
+use crate::{gui::make_dropdown_list_option, settings::make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        dropdown_list::DropdownListBuilder,
+        grid::{Column, GridBuilder, Row},
+        message::{DropdownListMessage, MessageDirection, UiMessageData},
+        widget::WidgetBuilder,
+        Thickness,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// What should happen when user double-clicks an asset in the asset browser.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AssetDoubleClickAction {
+    /// Open asset in appropriate editor: scenes are loaded, everything else is opened
+    /// in an external application.
+    Open,
+    /// Instantiate asset in current scene, if it is possible. Falls back to `Open` otherwise.
+    Instantiate,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+pub struct AssetBrowserSettings {
+    pub double_click_action: AssetDoubleClickAction,
+}
+
+impl Default for AssetBrowserSettings {
+    fn default() -> Self {
+        Self {
+            double_click_action: AssetDoubleClickAction::Open,
+        }
+    }
+}
+
+pub struct AssetBrowserSection {
+    pub section: Handle<UiNode>,
+    double_click_action: Handle<UiNode>,
+}
+
+fn action_index(action: AssetDoubleClickAction) -> usize {
+    match action {
+        AssetDoubleClickAction::Open => 0,
+        AssetDoubleClickAction::Instantiate => 1,
+    }
+}
+
+impl AssetBrowserSection {
+    pub fn new(ctx: &mut BuildContext, settings: &AssetBrowserSettings) -> Self {
+        let double_click_action;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child(make_text_mark(ctx, "Double Click", 0))
+                .with_child({
+                    double_click_action = DropdownListBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(0)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_items(vec![
+                        make_dropdown_list_option(ctx, "Open"),
+                        make_dropdown_list_option(ctx, "Instantiate"),
+                    ])
+                    .with_selected(action_index(settings.double_click_action))
+                    .with_close_on_selection(true)
+                    .build(ctx);
+                    double_click_action
+                }),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::stretch())
+        .add_column(Column::strict(120.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        Self {
+            section,
+            double_click_action,
+        }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &AssetBrowserSettings) {
+        ui.send_message(DropdownListMessage::selection(
+            self.double_click_action,
+            MessageDirection::ToWidget,
+            Some(action_index(settings.double_click_action)),
+        ));
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut AssetBrowserSettings) {
+        if let UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index))) =
+            *message.data()
+        {
+            if message.destination() == self.double_click_action {
+                settings.double_click_action = match index {
+                    0 => AssetDoubleClickAction::Open,
+                    1 => AssetDoubleClickAction::Instantiate,
+                    _ => unreachable!(),
+                };
+            }
+        }
+    }
+}
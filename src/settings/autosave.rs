@@ -0,0 +1,111 @@
+use crate::settings::{make_bool_input_field, make_f32_input_field, make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{CheckBoxMessage, MessageDirection, UiMessageData},
+        numeric::NumericUpDownMessage,
+        widget::WidgetBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct AutoSaveSettings {
+    pub enabled: bool,
+    /// Time between auto-saves in minutes.
+    pub interval: f32,
+}
+
+impl Default for AutoSaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 5.0,
+        }
+    }
+}
+
+impl AutoSaveSettings {
+    /// Returns path of auto-save file for a scene, it is placed next to the scene,
+    /// `level.rgs` will be auto-saved to `level.autosave.rgs`.
+    pub fn autosave_path(scene_path: &Path) -> PathBuf {
+        scene_path.with_extension("autosave.rgs")
+    }
+}
+
+pub struct AutoSaveSection {
+    pub section: Handle<UiNode>,
+    enabled: Handle<UiNode>,
+    interval: Handle<UiNode>,
+}
+
+impl AutoSaveSection {
+    pub fn new(ctx: &mut BuildContext, settings: &AutoSaveSettings) -> Self {
+        let enabled;
+        let interval;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child(make_text_mark(ctx, "Enabled", 0))
+                .with_child({
+                    enabled = make_bool_input_field(ctx, 0, settings.enabled);
+                    enabled
+                })
+                .with_child(make_text_mark(ctx, "Interval (min)", 1))
+                .with_child({
+                    interval = make_f32_input_field(ctx, 1, settings.interval, 1.0);
+                    interval
+                }),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::stretch())
+        .add_column(Column::strict(120.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        Self {
+            section,
+            enabled,
+            interval,
+        }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &AutoSaveSettings) {
+        ui.send_message(CheckBoxMessage::checked(
+            self.enabled,
+            MessageDirection::ToWidget,
+            Some(settings.enabled),
+        ));
+        ui.send_message(NumericUpDownMessage::value(
+            self.interval,
+            MessageDirection::ToWidget,
+            settings.interval,
+        ));
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut AutoSaveSettings) {
+        match message.data() {
+            UiMessageData::User(msg) if message.direction() == MessageDirection::FromWidget => {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.destination() == self.interval {
+                        settings.interval = value;
+                    }
+                }
+            }
+            &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) => {
+                if message.destination() == self.enabled {
+                    settings.enabled = value;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Saved view of the editor camera.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub pivot_position: [f32; 3],
+    /// Position of the camera relative to its pivot.
+    pub camera_offset: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Orientation used by trackball orbit style, quaternion in (x, y, z, w) order.
+    pub rotation: [f32; 4],
+}
+
+/// Camera bookmarks of every scene, scenes are identified by their paths.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Default, Debug)]
+#[serde(default)]
+pub struct CameraBookmarks {
+    pub scenes: HashMap<PathBuf, Vec<CameraBookmark>>,
+}
+
+impl CameraBookmarks {
+    pub fn of_scene(&self, path: &Path) -> &[CameraBookmark] {
+        self.scenes
+            .get(path)
+            .map_or(&[], |bookmarks| bookmarks.as_slice())
+    }
+
+    /// Adds a bookmark to a scene, bookmark with the same name is replaced.
+    pub fn add(&mut self, path: &Path, bookmark: CameraBookmark) {
+        let bookmarks = self.scenes.entry(path.to_owned()).or_default();
+        if let Some(existing) = bookmarks.iter_mut().find(|b| b.name == bookmark.name) {
+            *existing = bookmark;
+        } else {
+            bookmarks.push(bookmark);
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path, index: usize) {
+        if let Some(bookmarks) = self.scenes.get_mut(path) {
+            if index < bookmarks.len() {
+                bookmarks.remove(index);
+            }
+            if bookmarks.is_empty() {
+                self.scenes.remove(path);
+            }
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use crate::{
+    gui::make_dropdown_list_option,
+    settings::{make_bool_input_field, make_f32_input_field, make_text_mark},
+};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        dropdown_list::DropdownListBuilder,
+        grid::{Column, GridBuilder, Row},
+        message::{CheckBoxMessage, DropdownListMessage, KeyCode, MessageDirection, UiMessageData},
+        numeric::NumericUpDownMessage,
+        widget::WidgetBuilder,
+        Thickness,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Defines how editor camera orbits around its pivot.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum OrbitStyle {
+    /// Yaw around world Y axis and clamped pitch around local X axis.
+    Turntable,
+    /// Free rotation around local axes without any clamping.
+    Trackball,
+}
+
+/// Modifier key that constrains camera drag to a single screen axis while held.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DragAxisLock {
+    Disabled,
+    Shift,
+    Ctrl,
+    Alt,
+}
+
+impl DragAxisLock {
+    pub fn is_modifier(self, key: KeyCode) -> bool {
+        match self {
+            DragAxisLock::Disabled => false,
+            DragAxisLock::Shift => matches!(key, KeyCode::LShift | KeyCode::RShift),
+            DragAxisLock::Ctrl => matches!(key, KeyCode::LControl | KeyCode::RControl),
+            DragAxisLock::Alt => matches!(key, KeyCode::LAlt | KeyCode::RAlt),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub orbit_style: OrbitStyle,
+    /// Test mesh triangles when picking instead of bounding boxes only. It is more precise,
+    /// but slower on heavy meshes.
+    pub precise_picking: bool,
+    /// Radians per pixel of mouse movement when rotating the camera.
+    pub rotate_sensitivity: f32,
+    /// Units per pixel of mouse movement when dragging the camera.
+    pub drag_sensitivity: f32,
+    /// Base fly speed in units per second.
+    pub speed: f32,
+    /// Speed multiplier applied while Ctrl is held.
+    pub fast_speed_multiplier: f32,
+    /// Speed multiplier applied while Shift is held.
+    pub slow_speed_multiplier: f32,
+    /// Dolly towards the point under the cursor instead of the screen center.
+    pub zoom_to_cursor: bool,
+    /// Flip horizontal mouse look (yaw).
+    pub invert_x: bool,
+    /// Flip vertical mouse look (pitch).
+    pub invert_y: bool,
+    /// Stop the camera in front of scene meshes instead of flying through them.
+    pub collide_with_scene: bool,
+    /// Ease movement and rotation of the camera instead of applying input instantly.
+    pub smoothing: bool,
+    /// Time in seconds that the camera needs to (mostly) catch up with the input.
+    pub smoothing_time: f32,
+    /// Fly the camera with the first connected gamepad.
+    pub gamepad: bool,
+    /// Stick deflection (in [0; 1] range) that is ignored to prevent drift.
+    pub gamepad_deadzone: f32,
+    /// Key that locks camera drag to the dominant screen axis.
+    pub drag_axis_lock: DragAxisLock,
+    /// Time in seconds that the camera needs to move to a bookmark, zero jumps instantly.
+    pub bookmark_transition_time: f32,
+    /// Mouse wheel changes fly speed instead of dollying while right mouse button is held.
+    pub scroll_adjusts_fly_speed: bool,
+    /// Alt + left mouse button drag orbits the camera around selection (or scene) center.
+    pub alt_drag_orbit: bool,
+    /// Write time spent in precise picking to the log after every pick.
+    pub log_picking_time: bool,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            orbit_style: OrbitStyle::Turntable,
+            precise_picking: false,
+            rotate_sensitivity: 0.01,
+            drag_sensitivity: 0.01,
+            speed: 10.0,
+            fast_speed_multiplier: 2.0,
+            slow_speed_multiplier: 0.25,
+            zoom_to_cursor: false,
+            invert_x: false,
+            invert_y: false,
+            collide_with_scene: false,
+            smoothing: false,
+            smoothing_time: 0.1,
+            gamepad: false,
+            gamepad_deadzone: 0.15,
+            drag_axis_lock: DragAxisLock::Shift,
+            bookmark_transition_time: 0.5,
+            scroll_adjusts_fly_speed: true,
+            alt_drag_orbit: true,
+            log_picking_time: false,
+        }
+    }
+}
+
+pub struct CameraSection {
+    pub section: Handle<UiNode>,
+    orbit_style: Handle<UiNode>,
+    precise_picking: Handle<UiNode>,
+    rotate_sensitivity: Handle<UiNode>,
+    drag_sensitivity: Handle<UiNode>,
+    speed: Handle<UiNode>,
+    fast_speed_multiplier: Handle<UiNode>,
+    slow_speed_multiplier: Handle<UiNode>,
+    zoom_to_cursor: Handle<UiNode>,
+    invert_x: Handle<UiNode>,
+    invert_y: Handle<UiNode>,
+    collide_with_scene: Handle<UiNode>,
+    smoothing: Handle<UiNode>,
+    smoothing_time: Handle<UiNode>,
+    gamepad: Handle<UiNode>,
+    gamepad_deadzone: Handle<UiNode>,
+    drag_axis_lock: Handle<UiNode>,
+    bookmark_transition_time: Handle<UiNode>,
+    scroll_adjusts_fly_speed: Handle<UiNode>,
+    alt_drag_orbit: Handle<UiNode>,
+    log_picking_time: Handle<UiNode>,
+}
+
+fn orbit_style_index(style: OrbitStyle) -> usize {
+    match style {
+        OrbitStyle::Turntable => 0,
+        OrbitStyle::Trackball => 1,
+    }
+}
+
+fn drag_axis_lock_index(lock: DragAxisLock) -> usize {
+    match lock {
+        DragAxisLock::Disabled => 0,
+        DragAxisLock::Shift => 1,
+        DragAxisLock::Ctrl => 2,
+        DragAxisLock::Alt => 3,
+    }
+}
+
+impl CameraSection {
+    pub fn new(ctx: &mut BuildContext, settings: &CameraSettings) -> Self {
+        let orbit_style;
+        let precise_picking;
+        let rotate_sensitivity;
+        let drag_sensitivity;
+        let speed;
+        let fast_speed_multiplier;
+        let slow_speed_multiplier;
+        let zoom_to_cursor;
+        let invert_x;
+        let invert_y;
+        let collide_with_scene;
+        let smoothing;
+        let smoothing_time;
+        let gamepad;
+        let gamepad_deadzone;
+        let drag_axis_lock;
+        let bookmark_transition_time;
+        let scroll_adjusts_fly_speed;
+        let alt_drag_orbit;
+        let log_picking_time;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child(make_text_mark(ctx, "Orbit Style", 0))
+                .with_child({
+                    orbit_style = DropdownListBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(0)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_items(vec![
+                        make_dropdown_list_option(ctx, "Turntable"),
+                        make_dropdown_list_option(ctx, "Trackball"),
+                    ])
+                    .with_selected(orbit_style_index(settings.orbit_style))
+                    .with_close_on_selection(true)
+                    .build(ctx);
+                    orbit_style
+                })
+                .with_child(make_text_mark(ctx, "Precise Picking", 1))
+                .with_child({
+                    precise_picking = make_bool_input_field(ctx, 1, settings.precise_picking);
+                    precise_picking
+                })
+                .with_child(make_text_mark(ctx, "Rotate Sensitivity", 2))
+                .with_child({
+                    rotate_sensitivity =
+                        make_f32_input_field(ctx, 2, settings.rotate_sensitivity, 0.0);
+                    rotate_sensitivity
+                })
+                .with_child(make_text_mark(ctx, "Drag Sensitivity", 3))
+                .with_child({
+                    drag_sensitivity = make_f32_input_field(ctx, 3, settings.drag_sensitivity, 0.0);
+                    drag_sensitivity
+                })
+                .with_child(make_text_mark(ctx, "Fly Speed", 4))
+                .with_child({
+                    speed = make_f32_input_field(ctx, 4, settings.speed, 0.0);
+                    speed
+                })
+                .with_child(make_text_mark(ctx, "Fast Multiplier", 5))
+                .with_child({
+                    fast_speed_multiplier =
+                        make_f32_input_field(ctx, 5, settings.fast_speed_multiplier, 0.0);
+                    fast_speed_multiplier
+                })
+                .with_child(make_text_mark(ctx, "Slow Multiplier", 6))
+                .with_child({
+                    slow_speed_multiplier =
+                        make_f32_input_field(ctx, 6, settings.slow_speed_multiplier, 0.0);
+                    slow_speed_multiplier
+                })
+                .with_child(make_text_mark(ctx, "Zoom To Cursor", 7))
+                .with_child({
+                    zoom_to_cursor = make_bool_input_field(ctx, 7, settings.zoom_to_cursor);
+                    zoom_to_cursor
+                })
+                .with_child(make_text_mark(ctx, "Invert X", 8))
+                .with_child({
+                    invert_x = make_bool_input_field(ctx, 8, settings.invert_x);
+                    invert_x
+                })
+                .with_child(make_text_mark(ctx, "Invert Y", 9))
+                .with_child({
+                    invert_y = make_bool_input_field(ctx, 9, settings.invert_y);
+                    invert_y
+                })
+                .with_child(make_text_mark(ctx, "Collide With Scene", 10))
+                .with_child({
+                    collide_with_scene =
+                        make_bool_input_field(ctx, 10, settings.collide_with_scene);
+                    collide_with_scene
+                })
+                .with_child(make_text_mark(ctx, "Smoothing", 11))
+                .with_child({
+                    smoothing = make_bool_input_field(ctx, 11, settings.smoothing);
+                    smoothing
+                })
+                .with_child(make_text_mark(ctx, "Smoothing Time", 12))
+                .with_child({
+                    smoothing_time = make_f32_input_field(ctx, 12, settings.smoothing_time, 0.0);
+                    smoothing_time
+                })
+                .with_child(make_text_mark(ctx, "Gamepad", 13))
+                .with_child({
+                    gamepad = make_bool_input_field(ctx, 13, settings.gamepad);
+                    gamepad
+                })
+                .with_child(make_text_mark(ctx, "Gamepad Dead Zone", 14))
+                .with_child({
+                    gamepad_deadzone =
+                        make_f32_input_field(ctx, 14, settings.gamepad_deadzone, 0.0);
+                    gamepad_deadzone
+                })
+                .with_child(make_text_mark(ctx, "Drag Axis Lock", 15))
+                .with_child({
+                    drag_axis_lock = DropdownListBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(15)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_items(vec![
+                        make_dropdown_list_option(ctx, "Disabled"),
+                        make_dropdown_list_option(ctx, "Shift"),
+                        make_dropdown_list_option(ctx, "Ctrl"),
+                        make_dropdown_list_option(ctx, "Alt"),
+                    ])
+                    .with_selected(drag_axis_lock_index(settings.drag_axis_lock))
+                    .with_close_on_selection(true)
+                    .build(ctx);
+                    drag_axis_lock
+                })
+                .with_child(make_text_mark(ctx, "Bookmark Transition", 16))
+                .with_child({
+                    bookmark_transition_time =
+                        make_f32_input_field(ctx, 16, settings.bookmark_transition_time, 0.0);
+                    bookmark_transition_time
+                })
+                .with_child(make_text_mark(ctx, "Scroll Sets Fly Speed", 17))
+                .with_child({
+                    scroll_adjusts_fly_speed =
+                        make_bool_input_field(ctx, 17, settings.scroll_adjusts_fly_speed);
+                    scroll_adjusts_fly_speed
+                })
+                .with_child(make_text_mark(ctx, "Alt+Drag Orbits", 18))
+                .with_child({
+                    alt_drag_orbit = make_bool_input_field(ctx, 18, settings.alt_drag_orbit);
+                    alt_drag_orbit
+                })
+                .with_child(make_text_mark(ctx, "Log Picking Time", 19))
+                .with_child({
+                    log_picking_time = make_bool_input_field(ctx, 19, settings.log_picking_time);
+                    log_picking_time
+                }),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::stretch())
+        .add_column(Column::strict(120.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        Self {
+            section,
+            orbit_style,
+            precise_picking,
+            rotate_sensitivity,
+            drag_sensitivity,
+            speed,
+            fast_speed_multiplier,
+            slow_speed_multiplier,
+            zoom_to_cursor,
+            invert_x,
+            invert_y,
+            collide_with_scene,
+            smoothing,
+            smoothing_time,
+            gamepad,
+            gamepad_deadzone,
+            drag_axis_lock,
+            bookmark_transition_time,
+            scroll_adjusts_fly_speed,
+            alt_drag_orbit,
+            log_picking_time,
+        }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &CameraSettings) {
+        ui.send_message(DropdownListMessage::selection(
+            self.orbit_style,
+            MessageDirection::ToWidget,
+            Some(orbit_style_index(settings.orbit_style)),
+        ));
+        ui.send_message(DropdownListMessage::selection(
+            self.drag_axis_lock,
+            MessageDirection::ToWidget,
+            Some(drag_axis_lock_index(settings.drag_axis_lock)),
+        ));
+        ui.send_message(CheckBoxMessage::checked(
+            self.precise_picking,
+            MessageDirection::ToWidget,
+            Some(settings.precise_picking),
+        ));
+        for &(node, value) in &[
+            (self.zoom_to_cursor, settings.zoom_to_cursor),
+            (self.invert_x, settings.invert_x),
+            (self.invert_y, settings.invert_y),
+            (self.collide_with_scene, settings.collide_with_scene),
+            (self.smoothing, settings.smoothing),
+            (self.gamepad, settings.gamepad),
+            (
+                self.scroll_adjusts_fly_speed,
+                settings.scroll_adjusts_fly_speed,
+            ),
+            (self.alt_drag_orbit, settings.alt_drag_orbit),
+            (self.log_picking_time, settings.log_picking_time),
+        ] {
+            ui.send_message(CheckBoxMessage::checked(
+                node,
+                MessageDirection::ToWidget,
+                Some(value),
+            ));
+        }
+        for &(node, value) in &[
+            (self.rotate_sensitivity, settings.rotate_sensitivity),
+            (self.drag_sensitivity, settings.drag_sensitivity),
+            (self.speed, settings.speed),
+            (self.fast_speed_multiplier, settings.fast_speed_multiplier),
+            (self.slow_speed_multiplier, settings.slow_speed_multiplier),
+            (self.smoothing_time, settings.smoothing_time),
+            (self.gamepad_deadzone, settings.gamepad_deadzone),
+            (
+                self.bookmark_transition_time,
+                settings.bookmark_transition_time,
+            ),
+        ] {
+            ui.send_message(NumericUpDownMessage::value(
+                node,
+                MessageDirection::ToWidget,
+                value,
+            ));
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut CameraSettings) {
+        match message.data() {
+            UiMessageData::User(msg) if message.direction() == MessageDirection::FromWidget => {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.destination() == self.rotate_sensitivity {
+                        settings.rotate_sensitivity = value;
+                    } else if message.destination() == self.drag_sensitivity {
+                        settings.drag_sensitivity = value;
+                    } else if message.destination() == self.speed {
+                        settings.speed = value;
+                    } else if message.destination() == self.fast_speed_multiplier {
+                        settings.fast_speed_multiplier = value;
+                    } else if message.destination() == self.slow_speed_multiplier {
+                        settings.slow_speed_multiplier = value;
+                    } else if message.destination() == self.smoothing_time {
+                        settings.smoothing_time = value;
+                    } else if message.destination() == self.gamepad_deadzone {
+                        settings.gamepad_deadzone = value.min(0.99);
+                    } else if message.destination() == self.bookmark_transition_time {
+                        settings.bookmark_transition_time = value;
+                    }
+                }
+            }
+            &UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index))) => {
+                if message.destination() == self.orbit_style {
+                    settings.orbit_style = match index {
+                        0 => OrbitStyle::Turntable,
+                        1 => OrbitStyle::Trackball,
+                        _ => unreachable!(),
+                    };
+                } else if message.destination() == self.drag_axis_lock {
+                    settings.drag_axis_lock = match index {
+                        0 => DragAxisLock::Disabled,
+                        1 => DragAxisLock::Shift,
+                        2 => DragAxisLock::Ctrl,
+                        3 => DragAxisLock::Alt,
+                        _ => unreachable!(),
+                    };
+                }
+            }
+            &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) => {
+                if message.destination() == self.precise_picking {
+                    settings.precise_picking = value;
+                } else if message.destination() == self.zoom_to_cursor {
+                    settings.zoom_to_cursor = value;
+                } else if message.destination() == self.invert_x {
+                    settings.invert_x = value;
+                } else if message.destination() == self.invert_y {
+                    settings.invert_y = value;
+                } else if message.destination() == self.collide_with_scene {
+                    settings.collide_with_scene = value;
+                } else if message.destination() == self.smoothing {
+                    settings.smoothing = value;
+                } else if message.destination() == self.gamepad {
+                    settings.gamepad = value;
+                } else if message.destination() == self.scroll_adjusts_fly_speed {
+                    settings.scroll_adjusts_fly_speed = value;
+                } else if message.destination() == self.alt_drag_orbit {
+                    settings.alt_drag_orbit = value;
+                } else if message.destination() == self.log_picking_time {
+                    settings.log_picking_time = value;
+                }
+            }
+            _ => {}
+        }
+    }
+}
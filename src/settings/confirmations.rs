@@ -0,0 +1,128 @@
+use crate::settings::{make_bool_input_field, make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{CheckBoxMessage, MessageDirection, UiMessageData},
+        widget::WidgetBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Questions suppressed by "Don't ask again" check box, one flag per question kind.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Default)]
+#[serde(default)]
+pub struct ConfirmationSettings {
+    /// Keep suppressed questions suppressed between sessions.
+    pub remember: bool,
+    pub dont_ask_save_before_exit: bool,
+    pub dont_ask_save_before_open: bool,
+    pub dont_ask_revert_scene: bool,
+}
+
+impl ConfirmationSettings {
+    /// Must be called once settings were loaded, "Don't ask again" answers are valid only for
+    /// the session they were given in unless they should be remembered.
+    pub fn start_session(&mut self) {
+        if !self.remember {
+            *self = Self::default();
+        }
+    }
+}
+
+pub struct ConfirmationSection {
+    pub section: Handle<UiNode>,
+    remember: Handle<UiNode>,
+    dont_ask_save_before_exit: Handle<UiNode>,
+    dont_ask_save_before_open: Handle<UiNode>,
+    dont_ask_revert_scene: Handle<UiNode>,
+}
+
+impl ConfirmationSection {
+    pub fn new(ctx: &mut BuildContext, settings: &ConfirmationSettings) -> Self {
+        let remember;
+        let dont_ask_save_before_exit;
+        let dont_ask_save_before_open;
+        let dont_ask_revert_scene;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child(make_text_mark(ctx, "Remember Answers", 0))
+                .with_child({
+                    remember = make_bool_input_field(ctx, 0, settings.remember);
+                    remember
+                })
+                .with_child(make_text_mark(ctx, "Skip Save On Exit", 1))
+                .with_child({
+                    dont_ask_save_before_exit =
+                        make_bool_input_field(ctx, 1, settings.dont_ask_save_before_exit);
+                    dont_ask_save_before_exit
+                })
+                .with_child(make_text_mark(ctx, "Skip Save On Open", 2))
+                .with_child({
+                    dont_ask_save_before_open =
+                        make_bool_input_field(ctx, 2, settings.dont_ask_save_before_open);
+                    dont_ask_save_before_open
+                })
+                .with_child(make_text_mark(ctx, "Skip Revert Scene", 3))
+                .with_child({
+                    dont_ask_revert_scene =
+                        make_bool_input_field(ctx, 3, settings.dont_ask_revert_scene);
+                    dont_ask_revert_scene
+                }),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::stretch())
+        .add_column(Column::strict(120.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        Self {
+            section,
+            remember,
+            dont_ask_save_before_exit,
+            dont_ask_save_before_open,
+            dont_ask_revert_scene,
+        }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &ConfirmationSettings) {
+        for (check_box, value) in [
+            (self.remember, settings.remember),
+            (
+                self.dont_ask_save_before_exit,
+                settings.dont_ask_save_before_exit,
+            ),
+            (
+                self.dont_ask_save_before_open,
+                settings.dont_ask_save_before_open,
+            ),
+            (self.dont_ask_revert_scene, settings.dont_ask_revert_scene),
+        ] {
+            ui.send_message(CheckBoxMessage::checked(
+                check_box,
+                MessageDirection::ToWidget,
+                Some(value),
+            ));
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut ConfirmationSettings) {
+        if let &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.destination() == self.remember {
+                settings.remember = value;
+            } else if message.destination() == self.dont_ask_save_before_exit {
+                settings.dont_ask_save_before_exit = value;
+            } else if message.destination() == self.dont_ask_save_before_open {
+                settings.dont_ask_save_before_open = value;
+            } else if message.destination() == self.dont_ask_revert_scene {
+                settings.dont_ask_revert_scene = value;
+            }
+        }
+    }
+}
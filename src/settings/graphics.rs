@@ -21,10 +21,13 @@ use rg3d::{
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default)]
 pub struct GraphicsSettings {
     pub quality: QualitySettings,
     pub z_near: f32,
     pub z_far: f32,
+    /// Vertical field of view of editor camera in degrees.
+    pub fov: f32,
 }
 
 impl Default for GraphicsSettings {
@@ -33,10 +36,18 @@ impl Default for GraphicsSettings {
             quality: Default::default(),
             z_near: 0.025,
             z_far: 128.0,
+            fov: 75.0,
         }
     }
 }
 
+impl GraphicsSettings {
+    /// Checks that clip planes define non-empty depth range in front of the camera.
+    pub fn has_valid_clip_planes(&self) -> bool {
+        self.z_near > 0.0 && self.z_far > 0.0 && self.z_near < self.z_far
+    }
+}
+
 pub struct GraphicsSection {
     pub section: Handle<UiNode>,
     ssao: Handle<UiNode>,
@@ -47,6 +58,7 @@ pub struct GraphicsSection {
     near_plane: Handle<UiNode>,
     far_plane: Handle<UiNode>,
     parallax_mapping: Handle<UiNode>,
+    fov: Handle<UiNode>,
 }
 
 impl GraphicsSection {
@@ -59,6 +71,7 @@ impl GraphicsSection {
         let near_plane;
         let far_plane;
         let parallax_mapping;
+        let fov;
 
         let section = GridBuilder::new(
             WidgetBuilder::new()
@@ -123,6 +136,20 @@ impl GraphicsSection {
                     parallax_mapping =
                         make_bool_input_field(ctx, 7, settings.quality.use_parallax_mapping);
                     parallax_mapping
+                })
+                .with_child(make_text_mark(ctx, "Field Of View", 8))
+                .with_child({
+                    fov = NumericUpDownBuilder::new(
+                        WidgetBuilder::new()
+                            .on_column(1)
+                            .on_row(8)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .with_min_value(10.0)
+                    .with_max_value(150.0)
+                    .with_value(settings.fov)
+                    .build(ctx);
+                    fov
                 }),
         )
         .add_row(Row::strict(25.0))
@@ -133,6 +160,7 @@ impl GraphicsSection {
         .add_row(Row::strict(25.0))
         .add_row(Row::strict(25.0))
         .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
         .add_row(Row::stretch())
         .add_row(Row::stretch())
         .add_column(Column::strict(120.0))
@@ -149,6 +177,7 @@ impl GraphicsSection {
             near_plane,
             far_plane,
             parallax_mapping,
+            fov,
         }
     }
 
@@ -163,6 +192,11 @@ impl GraphicsSection {
             MessageDirection::ToWidget,
             settings.z_far,
         ));
+        ui.send_message(NumericUpDownMessage::value(
+            self.fov,
+            MessageDirection::ToWidget,
+            settings.fov,
+        ));
 
         let sync_check_box = |handle: Handle<UiNode>, value: bool| {
             ui.send_message(CheckBoxMessage::checked(
@@ -215,10 +249,35 @@ impl GraphicsSection {
                 if let Some(&NumericUpDownMessage::Value(value)) =
                     msg.cast::<NumericUpDownMessage<f32>>()
                 {
-                    if message.destination() == self.near_plane {
-                        settings.z_near = value;
-                    } else if message.destination() == self.far_plane {
-                        settings.z_far = value;
+                    if message.destination() == self.near_plane
+                        || message.destination() == self.far_plane
+                    {
+                        let mut new_settings = settings.clone();
+                        if message.destination() == self.near_plane {
+                            new_settings.z_near = value;
+                        } else {
+                            new_settings.z_far = value;
+                        }
+
+                        if new_settings.has_valid_clip_planes() {
+                            *settings = new_settings;
+                        } else {
+                            // Roll the field back to the last valid value.
+                            let old_value = if message.destination() == self.near_plane {
+                                settings.z_near
+                            } else {
+                                settings.z_far
+                            };
+                            engine
+                                .user_interface
+                                .send_message(NumericUpDownMessage::value(
+                                    message.destination(),
+                                    MessageDirection::ToWidget,
+                                    old_value,
+                                ));
+                        }
+                    } else if message.destination() == self.fov {
+                        settings.fov = value;
                     }
                 }
             }
@@ -1,9 +1,19 @@
 use crate::{
     scene::EditorScene,
     settings::{
+        asset_browser::{AssetBrowserSection, AssetBrowserSettings},
+        autosave::{AutoSaveSection, AutoSaveSettings},
+        bookmarks::CameraBookmarks,
+        camera::{CameraSection, CameraSettings},
+        confirmations::{ConfirmationSection, ConfirmationSettings},
         debugging::{DebuggingSection, DebuggingSettings},
         graphics::{GraphicsSection, GraphicsSettings},
         move_mode::{MoveInteractionModeSettings, MoveModeSection},
+        recent::RecentFiles,
+        shortcuts::{BrushSizeShortcuts, PanelShortcuts, ShortcutsSection},
+        terrain::{TerrainBrushSection, TerrainBrushSettings},
+        toolbar::{ToolbarSection, ToolbarSettings},
+        windows::WindowsSettings,
     },
     GameEngine, Message, CONFIG_DIR,
 };
@@ -34,9 +44,19 @@ use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
 use std::{fs::File, path::PathBuf, sync::mpsc::Sender};
 
+pub mod asset_browser;
+pub mod autosave;
+pub mod bookmarks;
+pub mod camera;
+pub mod confirmations;
 pub mod debugging;
 pub mod graphics;
 pub mod move_mode;
+pub mod recent;
+pub mod shortcuts;
+pub mod terrain;
+pub mod toolbar;
+pub mod windows;
 
 struct SwitchEntry {
     tree_item: Handle<UiNode>,
@@ -52,6 +72,13 @@ pub struct SettingsWindow {
     graphics_section: GraphicsSection,
     move_mode_section: MoveModeSection,
     debugging_section: DebuggingSection,
+    asset_browser_section: AssetBrowserSection,
+    camera_section: CameraSection,
+    toolbar_section: ToolbarSection,
+    autosave_section: AutoSaveSection,
+    shortcuts_section: ShortcutsSection,
+    confirmation_section: ConfirmationSection,
+    terrain_brush_section: TerrainBrushSection,
     section_switches: Vec<SwitchEntry>,
     sections_root: Handle<UiNode>,
 }
@@ -61,6 +88,13 @@ pub enum SettingsSectionKind {
     Graphics,
     Debugging,
     MoveModeSettings,
+    AssetBrowser,
+    Camera,
+    Toolbar,
+    AutoSave,
+    Shortcuts,
+    Confirmations,
+    TerrainBrush,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Default)]
@@ -68,6 +102,28 @@ pub struct Settings {
     pub graphics: GraphicsSettings,
     pub debugging: DebuggingSettings,
     pub move_mode_settings: MoveInteractionModeSettings,
+    #[serde(default)]
+    pub asset_browser: AssetBrowserSettings,
+    #[serde(default)]
+    pub camera: CameraSettings,
+    #[serde(default)]
+    pub toolbar: ToolbarSettings,
+    #[serde(default)]
+    pub recent: RecentFiles,
+    #[serde(default)]
+    pub autosave: AutoSaveSettings,
+    #[serde(default)]
+    pub windows: WindowsSettings,
+    #[serde(default)]
+    pub camera_bookmarks: CameraBookmarks,
+    #[serde(default)]
+    pub panel_shortcuts: PanelShortcuts,
+    #[serde(default)]
+    pub brush_size_shortcuts: BrushSizeShortcuts,
+    #[serde(default)]
+    pub confirmations: ConfirmationSettings,
+    #[serde(default)]
+    pub terrain_brush: TerrainBrushSettings,
 }
 
 #[derive(Debug)]
@@ -107,7 +163,7 @@ impl Settings {
     }
 }
 
-fn make_text_mark(ctx: &mut BuildContext, text: &str, row: usize) -> Handle<UiNode> {
+pub fn make_text_mark(ctx: &mut BuildContext, text: &str, row: usize) -> Handle<UiNode> {
     TextBuilder::new(
         WidgetBuilder::new()
             .with_vertical_alignment(VerticalAlignment::Center)
@@ -162,11 +218,29 @@ impl SettingsWindow {
         let graphics_section = GraphicsSection::new(ctx, &settings.graphics);
         let debugging_section = DebuggingSection::new(ctx, &settings.debugging);
         let move_mode_section = MoveModeSection::new(ctx, &settings.move_mode_settings);
+        let asset_browser_section = AssetBrowserSection::new(ctx, &settings.asset_browser);
+        let camera_section = CameraSection::new(ctx, &settings.camera);
+        let toolbar_section = ToolbarSection::new(ctx, &settings.toolbar);
+        let autosave_section = AutoSaveSection::new(ctx, &settings.autosave);
+        let shortcuts_section = ShortcutsSection::new(
+            ctx,
+            &settings.panel_shortcuts,
+            &settings.brush_size_shortcuts,
+        );
+        let confirmation_section = ConfirmationSection::new(ctx, &settings.confirmations);
+        let terrain_brush_section = TerrainBrushSection::new(ctx, &settings.terrain_brush);
 
         let sections_root;
         let graphics_section_item;
         let debugging_section_item;
         let move_mode_section_item;
+        let asset_browser_section_item;
+        let camera_section_item;
+        let toolbar_section_item;
+        let autosave_section_item;
+        let shortcuts_section_item;
+        let confirmation_section_item;
+        let terrain_brush_section_item;
         let section = GridBuilder::new(
             WidgetBuilder::new()
                 .on_row(1)
@@ -204,6 +278,79 @@ impl SettingsWindow {
                                         .build(ctx);
                                     move_mode_section_item
                                 },
+                                {
+                                    asset_browser_section_item =
+                                        TreeBuilder::new(WidgetBuilder::new())
+                                            .with_content(
+                                                TextBuilder::new(WidgetBuilder::new())
+                                                    .with_text("Asset Browser")
+                                                    .build(ctx),
+                                            )
+                                            .build(ctx);
+                                    asset_browser_section_item
+                                },
+                                {
+                                    camera_section_item = TreeBuilder::new(WidgetBuilder::new())
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_text("Camera")
+                                                .build(ctx),
+                                        )
+                                        .build(ctx);
+                                    camera_section_item
+                                },
+                                {
+                                    toolbar_section_item = TreeBuilder::new(WidgetBuilder::new())
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_text("Toolbar")
+                                                .build(ctx),
+                                        )
+                                        .build(ctx);
+                                    toolbar_section_item
+                                },
+                                {
+                                    autosave_section_item = TreeBuilder::new(WidgetBuilder::new())
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_text("Auto Save")
+                                                .build(ctx),
+                                        )
+                                        .build(ctx);
+                                    autosave_section_item
+                                },
+                                {
+                                    shortcuts_section_item = TreeBuilder::new(WidgetBuilder::new())
+                                        .with_content(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_text("Shortcuts")
+                                                .build(ctx),
+                                        )
+                                        .build(ctx);
+                                    shortcuts_section_item
+                                },
+                                {
+                                    confirmation_section_item =
+                                        TreeBuilder::new(WidgetBuilder::new())
+                                            .with_content(
+                                                TextBuilder::new(WidgetBuilder::new())
+                                                    .with_text("Confirmations")
+                                                    .build(ctx),
+                                            )
+                                            .build(ctx);
+                                    confirmation_section_item
+                                },
+                                {
+                                    terrain_brush_section_item =
+                                        TreeBuilder::new(WidgetBuilder::new())
+                                            .with_content(
+                                                TextBuilder::new(WidgetBuilder::new())
+                                                    .with_text("Terrain Brush")
+                                                    .build(ctx),
+                                            )
+                                            .build(ctx);
+                                    terrain_brush_section_item
+                                },
                             ])
                             .build(ctx);
                     sections_root
@@ -214,6 +361,13 @@ impl SettingsWindow {
                             graphics_section.section,
                             debugging_section.section,
                             move_mode_section.section,
+                            asset_browser_section.section,
+                            camera_section.section,
+                            toolbar_section.section,
+                            autosave_section.section,
+                            shortcuts_section.section,
+                            confirmation_section.section,
+                            terrain_brush_section.section,
                         ],
                     ))
                     .build(ctx),
@@ -240,6 +394,41 @@ impl SettingsWindow {
                 section: move_mode_section.section,
                 kind: SettingsSectionKind::MoveModeSettings,
             },
+            SwitchEntry {
+                tree_item: asset_browser_section_item,
+                section: asset_browser_section.section,
+                kind: SettingsSectionKind::AssetBrowser,
+            },
+            SwitchEntry {
+                tree_item: camera_section_item,
+                section: camera_section.section,
+                kind: SettingsSectionKind::Camera,
+            },
+            SwitchEntry {
+                tree_item: toolbar_section_item,
+                section: toolbar_section.section,
+                kind: SettingsSectionKind::Toolbar,
+            },
+            SwitchEntry {
+                tree_item: autosave_section_item,
+                section: autosave_section.section,
+                kind: SettingsSectionKind::AutoSave,
+            },
+            SwitchEntry {
+                tree_item: shortcuts_section_item,
+                section: shortcuts_section.section,
+                kind: SettingsSectionKind::Shortcuts,
+            },
+            SwitchEntry {
+                tree_item: confirmation_section_item,
+                section: confirmation_section.section,
+                kind: SettingsSectionKind::Confirmations,
+            },
+            SwitchEntry {
+                tree_item: terrain_brush_section_item,
+                section: terrain_brush_section.section,
+                kind: SettingsSectionKind::TerrainBrush,
+            },
         ];
 
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0).with_height(600.0))
@@ -307,6 +496,13 @@ impl SettingsWindow {
             graphics_section,
             move_mode_section,
             debugging_section,
+            asset_browser_section,
+            camera_section,
+            toolbar_section,
+            autosave_section,
+            shortcuts_section,
+            confirmation_section,
+            terrain_brush_section,
         }
     }
 
@@ -337,12 +533,26 @@ impl SettingsWindow {
         self.sync_to_model(ui, settings);
     }
 
-    fn sync_to_model(&self, ui: &UserInterface, settings: &Settings) {
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &Settings) {
         self.graphics_section.sync_to_model(ui, &settings.graphics);
         self.move_mode_section
             .sync_to_model(ui, &settings.move_mode_settings);
         self.debugging_section
             .sync_to_model(ui, &settings.debugging);
+        self.asset_browser_section
+            .sync_to_model(ui, &settings.asset_browser);
+        self.camera_section.sync_to_model(ui, &settings.camera);
+        self.toolbar_section.sync_to_model(ui, &settings.toolbar);
+        self.autosave_section.sync_to_model(ui, &settings.autosave);
+        self.shortcuts_section.sync_to_model(
+            ui,
+            &settings.panel_shortcuts,
+            &settings.brush_size_shortcuts,
+        );
+        self.confirmation_section
+            .sync_to_model(ui, &settings.confirmations);
+        self.terrain_brush_section
+            .sync_to_model(ui, &settings.terrain_brush);
     }
 
     pub fn handle_message(
@@ -362,6 +572,23 @@ impl SettingsWindow {
             .handle_message(message, &mut settings.debugging);
         self.move_mode_section
             .handle_message(message, &mut settings.move_mode_settings);
+        self.asset_browser_section
+            .handle_message(message, &mut settings.asset_browser);
+        self.camera_section
+            .handle_message(message, &mut settings.camera);
+        self.toolbar_section
+            .handle_message(message, &mut settings.toolbar);
+        self.autosave_section
+            .handle_message(message, &mut settings.autosave);
+        self.shortcuts_section.handle_message(
+            message,
+            &mut settings.panel_shortcuts,
+            &mut settings.brush_size_shortcuts,
+        );
+        self.confirmation_section
+            .handle_message(message, &mut settings.confirmations);
+        self.terrain_brush_section
+            .handle_message(message, &mut settings.terrain_brush);
 
         match message.data() {
             UiMessageData::Button(ButtonMessage::Click) => {
@@ -371,7 +598,10 @@ impl SettingsWindow {
                         MessageDirection::ToWidget,
                     ));
                 } else if message.destination() == self.default {
+                    // Recent files are not configurable, so keep them.
+                    let recent = std::mem::take(&mut settings.recent);
                     *settings = Default::default();
+                    settings.recent = recent;
                     self.sync_to_model(&engine.user_interface, settings);
                 }
             }
@@ -3,7 +3,7 @@ use rg3d::gui::message::UiMessage;
 use rg3d::gui::numeric::NumericUpDownMessage;
 use rg3d::gui::{BuildContext, UiNode, UserInterface};
 use rg3d::{
-    core::pool::Handle,
+    core::{algebra::Vector3, pool::Handle},
     gui::{
         grid::{Column, GridBuilder, Row},
         message::{CheckBoxMessage, MessageDirection, UiMessageData},
@@ -31,6 +31,30 @@ impl Default for MoveInteractionModeSettings {
     }
 }
 
+impl MoveInteractionModeSettings {
+    /// Rounds every coordinate of the position to the nearest multiple of its snap step.
+    /// The position is returned as is if grid snapping is disabled.
+    pub fn snap_position(&self, position: Vector3<f32>) -> Vector3<f32> {
+        fn round_to_step(x: f32, step: f32) -> f32 {
+            if step > 0.0 {
+                (x / step).round() * step
+            } else {
+                x
+            }
+        }
+
+        if self.grid_snapping {
+            Vector3::new(
+                round_to_step(position.x, self.x_snap_step),
+                round_to_step(position.y, self.y_snap_step),
+                round_to_step(position.z, self.z_snap_step),
+            )
+        } else {
+            position
+        }
+    }
+}
+
 pub struct MoveModeSection {
     pub section: Handle<UiNode>,
     snapping: Handle<UiNode>,
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// List of recently saved or loaded scenes, the most recent one goes first.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Default, Debug)]
+pub struct RecentFiles {
+    pub scenes: Vec<PathBuf>,
+    /// Directory of the last scene picked in save or load file selector.
+    #[serde(default)]
+    pub last_directory: Option<PathBuf>,
+}
+
+impl RecentFiles {
+    pub const MAX_ENTRIES: usize = 10;
+
+    /// Moves given path to the top of the list, the oldest entry is dropped if the list
+    /// is full.
+    pub fn push(&mut self, path: &Path) {
+        self.scenes.retain(|p| p != path);
+        self.scenes.insert(0, path.to_owned());
+        self.scenes.truncate(Self::MAX_ENTRIES);
+    }
+
+    /// Returns directory in which file selectors should be opened.
+    pub fn initial_directory(&self) -> PathBuf {
+        self.last_directory
+            .clone()
+            .filter(|dir| dir.exists())
+            .unwrap_or_else(|| std::env::current_dir().unwrap())
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{gui::make_dropdown_list_option, settings::make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        dropdown_list::DropdownListBuilder,
+        grid::{Column, GridBuilder, Row},
+        message::{DropdownListMessage, KeyCode, MessageDirection, UiMessageData},
+        widget::WidgetBuilder,
+        Thickness,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Key that toggles a panel. Only function keys can be assigned, so the shortcuts do not
+/// clash with keys used by camera and interaction modes.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelKey {
+    None,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+}
+
+impl PanelKey {
+    const ALL: [PanelKey; 13] = [
+        PanelKey::None,
+        PanelKey::F1,
+        PanelKey::F2,
+        PanelKey::F3,
+        PanelKey::F4,
+        PanelKey::F5,
+        PanelKey::F6,
+        PanelKey::F7,
+        PanelKey::F8,
+        PanelKey::F9,
+        PanelKey::F10,
+        PanelKey::F11,
+        PanelKey::F12,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&k| k == self).unwrap()
+    }
+
+    fn key_code(self) -> Option<KeyCode> {
+        match self {
+            PanelKey::None => None,
+            PanelKey::F1 => Some(KeyCode::F1),
+            PanelKey::F2 => Some(KeyCode::F2),
+            PanelKey::F3 => Some(KeyCode::F3),
+            PanelKey::F4 => Some(KeyCode::F4),
+            PanelKey::F5 => Some(KeyCode::F5),
+            PanelKey::F6 => Some(KeyCode::F6),
+            PanelKey::F7 => Some(KeyCode::F7),
+            PanelKey::F8 => Some(KeyCode::F8),
+            PanelKey::F9 => Some(KeyCode::F9),
+            PanelKey::F10 => Some(KeyCode::F10),
+            PanelKey::F11 => Some(KeyCode::F11),
+            PanelKey::F12 => Some(KeyCode::F12),
+        }
+    }
+
+    pub fn matches(self, key: KeyCode) -> bool {
+        self.key_code() == Some(key)
+    }
+}
+
+/// Key that changes size of terrain brush. Only keys that are not used by the editor
+/// otherwise can be assigned.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BrushSizeKey {
+    None,
+    LBracket,
+    RBracket,
+    Minus,
+    Equals,
+    Comma,
+    Period,
+    PageDown,
+    PageUp,
+}
+
+impl BrushSizeKey {
+    const ALL: [BrushSizeKey; 9] = [
+        BrushSizeKey::None,
+        BrushSizeKey::LBracket,
+        BrushSizeKey::RBracket,
+        BrushSizeKey::Minus,
+        BrushSizeKey::Equals,
+        BrushSizeKey::Comma,
+        BrushSizeKey::Period,
+        BrushSizeKey::PageDown,
+        BrushSizeKey::PageUp,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&k| k == self).unwrap()
+    }
+
+    fn key_code(self) -> Option<KeyCode> {
+        match self {
+            BrushSizeKey::None => None,
+            BrushSizeKey::LBracket => Some(KeyCode::LBracket),
+            BrushSizeKey::RBracket => Some(KeyCode::RBracket),
+            BrushSizeKey::Minus => Some(KeyCode::Minus),
+            BrushSizeKey::Equals => Some(KeyCode::Equals),
+            BrushSizeKey::Comma => Some(KeyCode::Comma),
+            BrushSizeKey::Period => Some(KeyCode::Period),
+            BrushSizeKey::PageDown => Some(KeyCode::PageDown),
+            BrushSizeKey::PageUp => Some(KeyCode::PageUp),
+        }
+    }
+
+    pub fn matches(self, key: KeyCode) -> bool {
+        self.key_code() == Some(key)
+    }
+}
+
+/// Keys that change size of terrain brush in terrain interaction mode.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct BrushSizeShortcuts {
+    pub decrease: BrushSizeKey,
+    pub increase: BrushSizeKey,
+}
+
+impl Default for BrushSizeShortcuts {
+    fn default() -> Self {
+        Self {
+            decrease: BrushSizeKey::LBracket,
+            increase: BrushSizeKey::RBracket,
+        }
+    }
+}
+
+const BRUSH_SIZE_NAMES: [&str; 2] = ["Decrease Brush Size", "Increase Brush Size"];
+
+impl BrushSizeShortcuts {
+    fn keys_mut(&mut self) -> [&mut BrushSizeKey; 2] {
+        [&mut self.decrease, &mut self.increase]
+    }
+
+    fn keys(&self) -> [BrushSizeKey; 2] {
+        [self.decrease, self.increase]
+    }
+}
+
+/// Keys that toggle panels from View menu.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct PanelShortcuts {
+    pub sidebar: PanelKey,
+    pub asset_browser: PanelKey,
+    pub world_outliner: PanelKey,
+    pub light_panel: PanelKey,
+    pub camera_bookmarks: PanelKey,
+    pub log_panel: PanelKey,
+}
+
+impl Default for PanelShortcuts {
+    fn default() -> Self {
+        Self {
+            sidebar: PanelKey::F1,
+            asset_browser: PanelKey::F2,
+            world_outliner: PanelKey::F3,
+            light_panel: PanelKey::F4,
+            camera_bookmarks: PanelKey::None,
+            log_panel: PanelKey::F5,
+        }
+    }
+}
+
+const PANEL_NAMES: [&str; 6] = [
+    "Sidebar",
+    "Asset Browser",
+    "World Outliner",
+    "Light Panel",
+    "Camera Bookmarks",
+    "Log Panel",
+];
+
+impl PanelShortcuts {
+    fn keys_mut(&mut self) -> [&mut PanelKey; 6] {
+        [
+            &mut self.sidebar,
+            &mut self.asset_browser,
+            &mut self.world_outliner,
+            &mut self.light_panel,
+            &mut self.camera_bookmarks,
+            &mut self.log_panel,
+        ]
+    }
+
+    fn keys(&self) -> [PanelKey; 6] {
+        [
+            self.sidebar,
+            self.asset_browser,
+            self.world_outliner,
+            self.light_panel,
+            self.camera_bookmarks,
+            self.log_panel,
+        ]
+    }
+}
+
+pub struct ShortcutsSection {
+    pub section: Handle<UiNode>,
+    // Drop-down list of every panel, in the same order as `PANEL_NAMES`.
+    keys: Vec<Handle<UiNode>>,
+    // Drop-down list of every brush size key, in the same order as `BRUSH_SIZE_NAMES`.
+    brush_size_keys: Vec<Handle<UiNode>>,
+}
+
+fn make_key_dropdown(
+    ctx: &mut BuildContext,
+    names: Vec<String>,
+    selected: usize,
+    row: usize,
+) -> Handle<UiNode> {
+    let items = names
+        .iter()
+        .map(|name| make_dropdown_list_option(ctx, name))
+        .collect();
+    DropdownListBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_items(items)
+    .with_selected(selected)
+    .with_close_on_selection(true)
+    .build(ctx)
+}
+
+impl ShortcutsSection {
+    pub fn new(
+        ctx: &mut BuildContext,
+        settings: &PanelShortcuts,
+        brush_size: &BrushSizeShortcuts,
+    ) -> Self {
+        let mut keys = Vec::new();
+        let mut children = Vec::new();
+        for (row, (name, key)) in PANEL_NAMES.iter().zip(settings.keys()).enumerate() {
+            children.push(make_text_mark(ctx, name, row));
+
+            let names = PanelKey::ALL.iter().map(|k| format!("{:?}", k)).collect();
+            let dropdown = make_key_dropdown(ctx, names, key.index(), row);
+            children.push(dropdown);
+            keys.push(dropdown);
+        }
+
+        let mut brush_size_keys = Vec::new();
+        for (i, (name, key)) in BRUSH_SIZE_NAMES.iter().zip(brush_size.keys()).enumerate() {
+            let row = PANEL_NAMES.len() + i;
+            children.push(make_text_mark(ctx, name, row));
+
+            let names = BrushSizeKey::ALL
+                .iter()
+                .map(|k| format!("{:?}", k))
+                .collect();
+            let dropdown = make_key_dropdown(ctx, names, key.index(), row);
+            children.push(dropdown);
+            brush_size_keys.push(dropdown);
+        }
+
+        let mut grid = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_children(children),
+        );
+        for _ in PANEL_NAMES.iter().chain(BRUSH_SIZE_NAMES.iter()) {
+            grid = grid.add_row(Row::strict(25.0));
+        }
+        let section = grid
+            .add_row(Row::stretch())
+            .add_column(Column::strict(120.0))
+            .add_column(Column::stretch())
+            .build(ctx);
+
+        Self {
+            section,
+            keys,
+            brush_size_keys,
+        }
+    }
+
+    pub fn sync_to_model(
+        &self,
+        ui: &UserInterface,
+        settings: &PanelShortcuts,
+        brush_size: &BrushSizeShortcuts,
+    ) {
+        let selections = self
+            .keys
+            .iter()
+            .zip(settings.keys().iter().map(|k| k.index()))
+            .chain(
+                self.brush_size_keys
+                    .iter()
+                    .zip(brush_size.keys().iter().map(|k| k.index())),
+            );
+        for (&dropdown, index) in selections {
+            ui.send_message(DropdownListMessage::selection(
+                dropdown,
+                MessageDirection::ToWidget,
+                Some(index),
+            ));
+        }
+    }
+
+    pub fn handle_message(
+        &mut self,
+        message: &UiMessage,
+        settings: &mut PanelShortcuts,
+        brush_size: &mut BrushSizeShortcuts,
+    ) {
+        if let &UiMessageData::DropdownList(DropdownListMessage::SelectionChanged(Some(index))) =
+            message.data()
+        {
+            if let Some(position) = self.keys.iter().position(|&k| k == message.destination()) {
+                *settings.keys_mut()[position] = PanelKey::ALL[index];
+            } else if let Some(position) = self
+                .brush_size_keys
+                .iter()
+                .position(|&k| k == message.destination())
+            {
+                *brush_size.keys_mut()[position] = BrushSizeKey::ALL[index];
+            }
+        }
+    }
+}
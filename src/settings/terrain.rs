@@ -0,0 +1,127 @@
+use crate::settings::{make_f32_input_field, make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessageData},
+        numeric::NumericUpDownMessage,
+        widget::WidgetBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Ranges of terrain brush parameters. Negative amount lowers the terrain, negative alpha
+/// erases a layer.
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct TerrainBrushSettings {
+    pub min_amount: f32,
+    pub max_amount: f32,
+    pub min_alpha: f32,
+    pub max_alpha: f32,
+}
+
+impl Default for TerrainBrushSettings {
+    fn default() -> Self {
+        Self {
+            min_amount: -10.0,
+            max_amount: 10.0,
+            min_alpha: 0.0,
+            max_alpha: 1.0,
+        }
+    }
+}
+
+pub struct TerrainBrushSection {
+    pub section: Handle<UiNode>,
+    min_amount: Handle<UiNode>,
+    max_amount: Handle<UiNode>,
+    min_alpha: Handle<UiNode>,
+    max_alpha: Handle<UiNode>,
+}
+
+impl TerrainBrushSection {
+    pub fn new(ctx: &mut BuildContext, settings: &TerrainBrushSettings) -> Self {
+        let min_amount;
+        let max_amount;
+        let min_alpha;
+        let max_alpha;
+        let section = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child(make_text_mark(ctx, "Min Amount", 0))
+                .with_child({
+                    min_amount = make_f32_input_field(ctx, 0, settings.min_amount, f32::MIN);
+                    min_amount
+                })
+                .with_child(make_text_mark(ctx, "Max Amount", 1))
+                .with_child({
+                    max_amount = make_f32_input_field(ctx, 1, settings.max_amount, f32::MIN);
+                    max_amount
+                })
+                .with_child(make_text_mark(ctx, "Min Alpha", 2))
+                .with_child({
+                    min_alpha = make_f32_input_field(ctx, 2, settings.min_alpha, -1.0);
+                    min_alpha
+                })
+                .with_child(make_text_mark(ctx, "Max Alpha", 3))
+                .with_child({
+                    max_alpha = make_f32_input_field(ctx, 3, settings.max_alpha, -1.0);
+                    max_alpha
+                }),
+        )
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::strict(25.0))
+        .add_row(Row::stretch())
+        .add_column(Column::strict(120.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        Self {
+            section,
+            min_amount,
+            max_amount,
+            min_alpha,
+            max_alpha,
+        }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &TerrainBrushSettings) {
+        for &(field, value) in &[
+            (self.min_amount, settings.min_amount),
+            (self.max_amount, settings.max_amount),
+            (self.min_alpha, settings.min_alpha),
+            (self.max_alpha, settings.max_alpha),
+        ] {
+            ui.send_message(NumericUpDownMessage::value(
+                field,
+                MessageDirection::ToWidget,
+                value,
+            ));
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut TerrainBrushSettings) {
+        if let UiMessageData::User(msg) = message.data() {
+            if message.direction() == MessageDirection::FromWidget {
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if message.destination() == self.min_amount {
+                        settings.min_amount = value;
+                    } else if message.destination() == self.max_amount {
+                        settings.max_amount = value;
+                    } else if message.destination() == self.min_alpha {
+                        settings.min_alpha = value;
+                    } else if message.destination() == self.max_alpha {
+                        settings.max_alpha = value;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::settings::{make_bool_input_field, make_text_mark};
+use rg3d::gui::message::UiMessage;
+use rg3d::gui::{BuildContext, UiNode, UserInterface};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{CheckBoxMessage, MessageDirection, UiMessageData},
+        widget::WidgetBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// An action that could be placed on quick-access toolbar above scene preview.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ToolbarAction {
+    Save,
+    Undo,
+    Redo,
+    CreateCube,
+    CreateSphere,
+    CreatePointLight,
+    SelectMode,
+    MoveMode,
+    RotateMode,
+    ScaleMode,
+    ToggleSnapping,
+    LookThrough,
+}
+
+impl ToolbarAction {
+    pub const ALL: [ToolbarAction; 12] = [
+        ToolbarAction::Save,
+        ToolbarAction::Undo,
+        ToolbarAction::Redo,
+        ToolbarAction::CreateCube,
+        ToolbarAction::CreateSphere,
+        ToolbarAction::CreatePointLight,
+        ToolbarAction::SelectMode,
+        ToolbarAction::MoveMode,
+        ToolbarAction::RotateMode,
+        ToolbarAction::ScaleMode,
+        ToolbarAction::ToggleSnapping,
+        ToolbarAction::LookThrough,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ToolbarAction::Save => "Save",
+            ToolbarAction::Undo => "Undo",
+            ToolbarAction::Redo => "Redo",
+            ToolbarAction::CreateCube => "Cube",
+            ToolbarAction::CreateSphere => "Sphere",
+            ToolbarAction::CreatePointLight => "Point Light",
+            ToolbarAction::SelectMode => "Select",
+            ToolbarAction::MoveMode => "Move",
+            ToolbarAction::RotateMode => "Rotate",
+            ToolbarAction::ScaleMode => "Scale",
+            ToolbarAction::ToggleSnapping => "Snap",
+            ToolbarAction::LookThrough => "Look Through",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone)]
+pub struct ToolbarSettings {
+    /// Actions that are shown on the toolbar.
+    pub actions: Vec<ToolbarAction>,
+}
+
+impl Default for ToolbarSettings {
+    fn default() -> Self {
+        Self {
+            actions: ToolbarAction::ALL.to_vec(),
+        }
+    }
+}
+
+pub struct ToolbarSection {
+    pub section: Handle<UiNode>,
+    // One check box per each action from `ToolbarAction::ALL`.
+    actions: Vec<Handle<UiNode>>,
+}
+
+impl ToolbarSection {
+    pub fn new(ctx: &mut BuildContext, settings: &ToolbarSettings) -> Self {
+        let mut children = Vec::new();
+        let mut actions = Vec::new();
+        for (row, action) in ToolbarAction::ALL.iter().enumerate() {
+            children.push(make_text_mark(ctx, action.name(), row));
+            let check_box = make_bool_input_field(ctx, row, settings.actions.contains(action));
+            children.push(check_box);
+            actions.push(check_box);
+        }
+
+        let mut grid = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_children(children),
+        );
+        for _ in ToolbarAction::ALL.iter() {
+            grid = grid.add_row(Row::strict(25.0));
+        }
+        let section = grid
+            .add_row(Row::stretch())
+            .add_column(Column::strict(120.0))
+            .add_column(Column::stretch())
+            .build(ctx);
+
+        Self { section, actions }
+    }
+
+    pub fn sync_to_model(&self, ui: &UserInterface, settings: &ToolbarSettings) {
+        for (check_box, action) in self.actions.iter().zip(ToolbarAction::ALL.iter()) {
+            ui.send_message(CheckBoxMessage::checked(
+                *check_box,
+                MessageDirection::ToWidget,
+                Some(settings.actions.contains(action)),
+            ));
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &UiMessage, settings: &mut ToolbarSettings) {
+        if let UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) = *message.data() {
+            if let Some(index) = self
+                .actions
+                .iter()
+                .position(|&check_box| check_box == message.destination())
+            {
+                let action = ToolbarAction::ALL[index];
+                if value {
+                    if !settings.actions.contains(&action) {
+                        settings.actions.push(action);
+                    }
+                } else {
+                    settings.actions.retain(|&a| a != action);
+                }
+            }
+        }
+    }
+}
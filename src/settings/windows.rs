@@ -0,0 +1,144 @@
+use crate::{light::LightPanel, menu::Panels};
+use rg3d::{
+    core::{algebra::Vector2, pool::Handle},
+    gui::{
+        dock::{Tile, TileContent},
+        message::{MessageDirection, TileMessage, WidgetMessage, WindowMessage},
+        UiNode, UserInterface,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Saved state of a single panel window.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct WindowState {
+    pub visible: bool,
+    /// Position and size are stored only for floating windows, docked windows are
+    /// arranged by the docking manager.
+    pub position: Option<[f32; 2]>,
+    pub size: Option<[f32; 2]>,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            position: None,
+            size: None,
+        }
+    }
+}
+
+impl WindowState {
+    fn capture(window: Handle<UiNode>, ui: &UserInterface) -> Self {
+        let node = ui.node(window);
+        let floating = ui.node(node.parent()).cast::<Tile>().is_none();
+
+        let (position, size) = if floating {
+            let position = node.screen_position();
+            let size = node.actual_size();
+            (Some([position.x, position.y]), Some([size.x, size.y]))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            visible: node.visibility(),
+            position,
+            size,
+        }
+    }
+
+    fn apply(&self, window: Handle<UiNode>, ui: &UserInterface) {
+        if let Some([x, y]) = self.position {
+            ui.send_message(WidgetMessage::desktop_position(
+                window,
+                MessageDirection::ToWidget,
+                Vector2::new(x, y),
+            ));
+        }
+        if let Some([width, height]) = self.size {
+            ui.send_message(WidgetMessage::width(
+                window,
+                MessageDirection::ToWidget,
+                width,
+            ));
+            ui.send_message(WidgetMessage::height(
+                window,
+                MessageDirection::ToWidget,
+                height,
+            ));
+        }
+
+        ui.send_message(if self.visible {
+            WindowMessage::open(window, MessageDirection::ToWidget, false)
+        } else {
+            WindowMessage::close(window, MessageDirection::ToWidget)
+        });
+    }
+}
+
+/// Layout of panels that can be switched from View menu. Panels without saved state
+/// keep their default layout.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Default, Debug)]
+#[serde(default)]
+pub struct WindowsSettings {
+    pub sidebar: Option<WindowState>,
+    pub asset_browser: Option<WindowState>,
+    pub world_outliner: Option<WindowState>,
+    pub light_panel: Option<WindowState>,
+    pub log_panel: Option<WindowState>,
+}
+
+impl WindowsSettings {
+    /// Remembers current state of panel windows.
+    pub fn capture(&mut self, panels: &Panels, ui: &UserInterface) {
+        self.sidebar = Some(WindowState::capture(panels.inspector_window, ui));
+        self.asset_browser = Some(WindowState::capture(panels.asset_window, ui));
+        self.world_outliner = Some(WindowState::capture(panels.world_outliner_window, ui));
+        self.light_panel = Some(WindowState::capture(panels.light_panel, ui));
+        self.log_panel = Some(WindowState::capture(panels.log_panel, ui));
+    }
+
+    /// Forgets saved layout and puts panel windows back to their default state: docked
+    /// panels are shown and docked back to their initial tiles if they were made floating,
+    /// light panel is closed and gets its initial size.
+    pub fn reset(&mut self, panels: &Panels, ui: &UserInterface) {
+        *self = Default::default();
+
+        for &(window, tile) in panels.docked_panels.iter() {
+            WindowState::default().apply(window, ui);
+
+            if ui.node(window).parent() != tile && ui.try_get_node(tile).is_some() {
+                ui.send_message(TileMessage::content(
+                    tile,
+                    MessageDirection::ToWidget,
+                    TileContent::Window(window),
+                ));
+            }
+        }
+
+        WindowState {
+            visible: false,
+            position: None,
+            size: Some([LightPanel::WIDTH, LightPanel::HEIGHT]),
+        }
+        .apply(panels.light_panel, ui);
+    }
+
+    /// Restores previously captured state of panel windows.
+    pub fn apply(&self, panels: &Panels, ui: &UserInterface) {
+        for (state, window) in [
+            (&self.sidebar, panels.inspector_window),
+            (&self.asset_browser, panels.asset_window),
+            (&self.world_outliner, panels.world_outliner_window),
+            (&self.light_panel, panels.light_panel),
+            (&self.log_panel, panels.log_panel),
+        ] {
+            if let Some(state) = state {
+                state.apply(window, ui);
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Quick-access toolbar above scene preview. Set of shown actions is configured
+//! in editor settings.
+
+use crate::settings::toolbar::{ToolbarAction, ToolbarSettings};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        button::ButtonBuilder,
+        message::{ButtonMessage, MessageDirection, UiMessage, UiMessageData, WidgetMessage},
+        stack_panel::StackPanelBuilder,
+        widget::WidgetBuilder,
+        BuildContext, Orientation, Thickness, UiNode, UserInterface,
+    },
+};
+
+pub struct QuickToolbar {
+    pub panel: Handle<UiNode>,
+    buttons: Vec<(ToolbarAction, Handle<UiNode>)>,
+    // Actions that are currently visible, used to avoid sending messages every frame.
+    shown: Vec<ToolbarAction>,
+}
+
+impl QuickToolbar {
+    pub fn new(ctx: &mut BuildContext, row: usize, column: usize) -> Self {
+        let buttons = ToolbarAction::ALL
+            .iter()
+            .map(|&action| {
+                let button = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .with_width(70.0)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text(action.name())
+                .build(ctx);
+                (action, button)
+            })
+            .collect::<Vec<_>>();
+
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(row)
+                .on_column(column)
+                .with_children(
+                    buttons
+                        .iter()
+                        .map(|(_, button)| *button)
+                        .collect::<Vec<_>>(),
+                ),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+
+        Self {
+            panel,
+            buttons,
+            shown: ToolbarAction::ALL.to_vec(),
+        }
+    }
+
+    pub fn sync_to_settings(&mut self, settings: &ToolbarSettings, ui: &UserInterface) {
+        if self.shown == settings.actions {
+            return;
+        }
+
+        for (action, button) in self.buttons.iter() {
+            ui.send_message(WidgetMessage::visibility(
+                *button,
+                MessageDirection::ToWidget,
+                settings.actions.contains(action),
+            ));
+        }
+
+        self.shown = settings.actions.clone();
+    }
+
+    pub fn handle_ui_message(&self, message: &UiMessage) -> Option<ToolbarAction> {
+        if let UiMessageData::Button(ButtonMessage::Click) = message.data() {
+            self.buttons
+                .iter()
+                .find(|(_, button)| *button == message.destination())
+                .map(|(action, _)| *action)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Import and export of terrain height maps as 16-bit grayscale PNG images or floating-point
+//! OpenEXR images. Terrain consists
+//! of chunks that share border samples, so the image is mapped onto the sample grid of the
+//! whole terrain.
+//!
+//! Exported image is normalized to the full `[0; 1]` range, the actual height range is
+//! written to a companion `.txt` file next to it so the import could restore the scale.
+
+use image::{ImageBuffer, ImageError, Luma, Rgb};
+use rg3d::scene::terrain::{Chunk, Terrain};
+use std::{fs, path::Path};
+
+#[derive(Debug)]
+pub enum HeightmapError {
+    Io(std::io::Error),
+    Image(ImageError),
+}
+
+impl From<std::io::Error> for HeightmapError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ImageError> for HeightmapError {
+    fn from(e: ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+/// Returns position of the first sample of the chunk in the sample grid of whole terrain.
+fn chunk_origin(chunk: &Chunk) -> (usize, usize) {
+    let column = (chunk.position().x / chunk.width()).round().max(0.0) as usize;
+    let row = (chunk.position().z / chunk.length()).round().max(0.0) as usize;
+    (
+        column * (chunk.width_point_count() as usize - 1),
+        row * (chunk.length_point_count() as usize - 1),
+    )
+}
+
+/// Returns amount of samples along X and Z axes of whole terrain.
+fn grid_size(terrain: &Terrain) -> (usize, usize) {
+    terrain
+        .chunks_ref()
+        .iter()
+        .fold((0, 0), |(width, length), chunk| {
+            let (x, z) = chunk_origin(chunk);
+            (
+                width.max(x + chunk.width_point_count() as usize),
+                length.max(z + chunk.length_point_count() as usize),
+            )
+        })
+}
+
+fn sample_bilinear(image: &ImageBuffer<Luma<u16>, Vec<u16>>, x: f32, y: f32) -> f32 {
+    let (width, height) = image.dimensions();
+    let x0 = (x.floor() as u32).min(width - 1);
+    let y0 = (y.floor() as u32).min(height - 1);
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+    let tx = x - x0 as f32;
+    let ty = y - y0 as f32;
+
+    let pixel = |x, y| image.get_pixel(x, y).0[0] as f32 / u16::MAX as f32;
+    let top = pixel(x0, y0) + (pixel(x1, y0) - pixel(x0, y0)) * tx;
+    let bottom = pixel(x0, y1) + (pixel(x1, y1) - pixel(x0, y1)) * tx;
+    top + (bottom - top) * ty
+}
+
+/// Loads grayscale image (PNG or OpenEXR) and converts it to height maps of every chunk of
+/// the terrain. Image is resampled if its size does not match the size of the terrain, black
+/// pixels are mapped to `min_height` and white ones to `max_height`. Values of floating-point
+/// images are expected to be in `[0; 1]` range, others are clamped.
+pub fn import_heightmap(
+    terrain: &Terrain,
+    path: &Path,
+    min_height: f32,
+    max_height: f32,
+) -> Result<Vec<Vec<f32>>, HeightmapError> {
+    let image = image::open(path)?.into_luma16();
+    let (image_width, image_height) = image.dimensions();
+    let (grid_width, grid_length) = grid_size(terrain);
+
+    let scale_x = (image_width - 1) as f32 / (grid_width.max(2) - 1) as f32;
+    let scale_y = (image_height - 1) as f32 / (grid_length.max(2) - 1) as f32;
+
+    Ok(terrain
+        .chunks_ref()
+        .iter()
+        .map(|chunk| {
+            let (origin_x, origin_z) = chunk_origin(chunk);
+            let width_points = chunk.width_point_count() as usize;
+            let length_points = chunk.length_point_count() as usize;
+
+            let mut heightmap = Vec::with_capacity(width_points * length_points);
+            for z in 0..length_points {
+                for x in 0..width_points {
+                    let value = sample_bilinear(
+                        &image,
+                        (origin_x + x) as f32 * scale_x,
+                        (origin_z + z) as f32 * scale_y,
+                    );
+                    heightmap.push(min_height + (max_height - min_height) * value);
+                }
+            }
+            heightmap
+        })
+        .collect())
+}
+
+/// Saves heights of the whole terrain as 16-bit grayscale PNG, or as floating-point image if
+/// the path has `exr` extension. Returns height range that was mapped to black and white.
+pub fn export_heightmap(terrain: &Terrain, path: &Path) -> Result<(f32, f32), HeightmapError> {
+    let (grid_width, grid_length) = grid_size(terrain);
+    let mut heights = vec![0.0; grid_width * grid_length];
+    for chunk in terrain.chunks_ref() {
+        let (origin_x, origin_z) = chunk_origin(chunk);
+        let width_points = chunk.width_point_count() as usize;
+        for (i, &height) in chunk.heightmap().iter().enumerate() {
+            let x = origin_x + i % width_points;
+            let z = origin_z + i / width_points;
+            heights[z * grid_width + x] = height;
+        }
+    }
+
+    let min_height = heights.iter().cloned().fold(f32::MAX, f32::min);
+    let max_height = heights.iter().cloned().fold(f32::MIN, f32::max);
+    let range = (max_height - min_height).max(f32::EPSILON);
+
+    let normalized = heights.iter().map(|h| (h - min_height) / range);
+    let is_exr = path
+        .extension()
+        .map_or(false, |ext| ext.to_string_lossy().to_lowercase() == "exr");
+    if is_exr {
+        // OpenEXR encoder does not support single channel images.
+        let pixels = normalized.flat_map(|v| [v, v, v]).collect::<Vec<_>>();
+        ImageBuffer::<Rgb<f32>, _>::from_raw(grid_width as u32, grid_length as u32, pixels)
+            .expect("Buffer size must match image size!")
+            .save(path)?;
+    } else {
+        let pixels = normalized
+            .map(|v| (v * u16::MAX as f32).round() as u16)
+            .collect::<Vec<_>>();
+        ImageBuffer::<Luma<u16>, _>::from_raw(grid_width as u32, grid_length as u32, pixels)
+            .expect("Buffer size must match image size!")
+            .save(path)?;
+    }
+
+    fs::write(
+        path.with_extension("txt"),
+        format!("{} {}", min_height, max_height),
+    )?;
+
+    Ok((min_height, max_height))
+}
+
+/// Reads height range from companion file of exported height map, if there is any.
+pub fn read_height_range(path: &Path) -> Option<(f32, f32)> {
+    let text = fs::read_to_string(path.with_extension("txt")).ok()?;
+    let mut values = text.split_whitespace().map(|v| v.parse::<f32>());
+    match (values.next()?, values.next()?) {
+        (Ok(min), Ok(max)) => Some((min, max)),
+        _ => None,
+    }
+}
@@ -1,4 +1,17 @@
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    scene::{graph::Graph, node::Node},
+};
+use std::{io, path::Path, process::Command};
+
+pub mod heightmap;
+pub mod name_replacer;
 pub mod path_fixer;
+pub mod terrain;
+pub mod transform_randomizer;
 
 pub fn is_slice_equal_permutation<T: PartialEq>(a: &[T], b: &[T]) -> bool {
     if a.is_empty() && !b.is_empty() {
@@ -20,3 +33,59 @@ pub fn is_slice_equal_permutation<T: PartialEq>(a: &[T], b: &[T]) -> bool {
         true
     }
 }
+
+/// Opens given file in an application associated with the file type by the OS.
+pub fn open_in_external_application(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(path).spawn().map(|_| ())
+}
+
+fn format_vector(v: &Vector3<f32>) -> String {
+    format!("({:.3}, {:.3}, {:.3})", v.x, v.y, v.z)
+}
+
+fn format_euler(rotation: &UnitQuaternion<f32>) -> String {
+    let (x, y, z) = rotation.euler_angles();
+    format!(
+        "({:.2}, {:.2}, {:.2}) deg",
+        x.to_degrees(),
+        y.to_degrees(),
+        z.to_degrees()
+    )
+}
+
+/// Makes human-readable description of local and world transforms of given node.
+pub fn make_transform_report(graph: &Graph, handle: Handle<Node>) -> String {
+    let node = &graph[handle];
+    let local = node.local_transform();
+    let global = node.global_transform();
+    let world_scale = Vector3::new(
+        global.column(0).xyz().norm(),
+        global.column(1).xyz().norm(),
+        global.column(2).xyz().norm(),
+    );
+    format!(
+        "{} ({}:{})\n\
+        \tLocal: position {}, rotation {}, scale {}\n\
+        \tWorld: position {}, rotation {}, scale {}",
+        node.name(),
+        handle.index(),
+        handle.generation(),
+        format_vector(local.position()),
+        format_euler(local.rotation()),
+        format_vector(local.scale()),
+        format_vector(&node.global_position()),
+        format_euler(&graph.global_rotation(handle)),
+        format_vector(&world_scale),
+    )
+}
@@ -0,0 +1,341 @@
+//! Scene-wide find and replace for node names. All renames are applied as a single
+//! command, so they can be undone at once.
+
+use crate::{
+    gui::make_button,
+    scene::{
+        commands::{graph::SetNameCommand, CommandGroup, SceneCommand},
+        EditorScene,
+    },
+    settings::make_text_mark,
+    GameEngine, Message,
+};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        check_box::CheckBoxBuilder,
+        decorator::DecoratorBuilder,
+        grid::{Column, GridBuilder, Row},
+        list_view::ListViewBuilder,
+        message::{
+            ButtonMessage, CheckBoxMessage, ListViewMessage, MessageDirection, TextBoxMessage,
+            UiMessage, UiMessageData, WindowMessage,
+        },
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        text_box::{TextBoxBuilder, TextCommitMode},
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+    scene::{graph::Graph, node::Node},
+};
+use std::{collections::HashMap, sync::mpsc::Sender};
+
+pub struct NameReplacer {
+    pub window: Handle<UiNode>,
+    find: Handle<UiNode>,
+    replace: Handle<UiNode>,
+    match_case: Handle<UiNode>,
+    preview: Handle<UiNode>,
+    apply: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+    find_value: String,
+    replace_value: String,
+    match_case_value: bool,
+}
+
+struct Rename {
+    node: Handle<Node>,
+    old_name: String,
+    new_name: String,
+    // Whether new name clashes with a name of some other node.
+    duplicate: bool,
+}
+
+/// Replaces every occurrence of `find` in `name`, returns `None` if there was nothing
+/// to replace. Case-insensitive search ignores case of ASCII characters only.
+fn replace_name(name: &str, find: &str, replacement: &str, match_case: bool) -> Option<String> {
+    if find.is_empty() {
+        return None;
+    }
+
+    let (haystack, needle) = if match_case {
+        (name.to_owned(), find.to_owned())
+    } else {
+        (name.to_ascii_lowercase(), find.to_ascii_lowercase())
+    };
+
+    let mut result = String::with_capacity(name.len());
+    let mut last = 0;
+    let mut found = false;
+    for (index, _) in haystack.match_indices(&needle) {
+        result.push_str(&name[last..index]);
+        result.push_str(replacement);
+        last = index + needle.len();
+        found = true;
+    }
+    result.push_str(&name[last..]);
+
+    if found {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn make_text_box(ctx: &mut BuildContext, row: usize) -> Handle<UiNode> {
+    TextBoxBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_text_commit_mode(TextCommitMode::Immediate)
+    .with_vertical_text_alignment(VerticalAlignment::Center)
+    .build(ctx)
+}
+
+impl NameReplacer {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let find;
+        let replace;
+        let match_case;
+        let fields = GridBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .with_child(make_text_mark(ctx, "Find", 0))
+                .with_child({
+                    find = make_text_box(ctx, 0);
+                    find
+                })
+                .with_child(make_text_mark(ctx, "Replace With", 1))
+                .with_child({
+                    replace = make_text_box(ctx, 1);
+                    replace
+                })
+                .with_child(make_text_mark(ctx, "Match Case", 2))
+                .with_child({
+                    match_case = CheckBoxBuilder::new(
+                        WidgetBuilder::new()
+                            .on_row(2)
+                            .on_column(1)
+                            .with_margin(Thickness::uniform(1.0)),
+                    )
+                    .checked(Some(false))
+                    .build(ctx);
+                    match_case
+                }),
+        )
+        .add_row(Row::strict(26.0))
+        .add_row(Row::strict(26.0))
+        .add_row(Row::strict(26.0))
+        .add_column(Column::strict(100.0))
+        .add_column(Column::stretch())
+        .build(ctx);
+
+        let preview;
+        let apply;
+        let cancel;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
+            .with_title(WindowTitle::text("Find and Replace Names"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(fields)
+                        .with_child({
+                            preview = ListViewBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .build(ctx);
+                            preview
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .on_row(2)
+                                    .with_child({
+                                        apply = make_button(ctx, "Apply");
+                                        apply
+                                    })
+                                    .with_child({
+                                        cancel = make_button(ctx, "Cancel");
+                                        cancel
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::auto())
+                .add_row(Row::stretch())
+                .add_row(Row::strict(28.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            find,
+            replace,
+            match_case,
+            preview,
+            apply,
+            cancel,
+            find_value: Default::default(),
+            replace_value: Default::default(),
+            match_case_value: false,
+        }
+    }
+
+    fn collect_renames(&self, graph: &Graph, editor_root: Handle<Node>) -> Vec<Rename> {
+        let mut renames = Vec::new();
+        let mut names = HashMap::<String, usize>::new();
+
+        let mut stack = graph[graph.get_root()].children().to_vec();
+        while let Some(handle) = stack.pop() {
+            if handle == editor_root {
+                continue;
+            }
+
+            let node = &graph[handle];
+            let name = if let Some(new_name) = replace_name(
+                node.name(),
+                &self.find_value,
+                &self.replace_value,
+                self.match_case_value,
+            ) {
+                renames.push(Rename {
+                    node: handle,
+                    old_name: node.name_owned(),
+                    new_name: new_name.clone(),
+                    duplicate: false,
+                });
+                new_name
+            } else {
+                node.name_owned()
+            };
+            *names.entry(name).or_default() += 1;
+
+            stack.extend_from_slice(node.children());
+        }
+
+        for rename in renames.iter_mut() {
+            rename.duplicate = names[&rename.new_name] > 1;
+        }
+
+        renames
+    }
+
+    fn sync_preview(&self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        let renames =
+            self.collect_renames(&engine.scenes[editor_scene.scene].graph, editor_scene.root);
+
+        let ui = &mut engine.user_interface;
+        let ctx = &mut ui.build_ctx();
+        let items = renames
+            .iter()
+            .map(|rename| {
+                DecoratorBuilder::new(BorderBuilder::new(
+                    WidgetBuilder::new().with_height(22.0).with_child(
+                        TextBuilder::new(
+                            WidgetBuilder::new()
+                                .with_margin(Thickness::uniform(1.0))
+                                .with_foreground(Brush::Solid(if rename.duplicate {
+                                    Color::RED
+                                } else {
+                                    rg3d::gui::COLOR_FOREGROUND
+                                })),
+                        )
+                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                        .with_text(format!("{} -> {}", rename.old_name, rename.new_name))
+                        .build(ctx),
+                    ),
+                ))
+                .build(ctx)
+            })
+            .collect::<Vec<_>>();
+
+        ui.send_message(ListViewMessage::items(
+            self.preview,
+            MessageDirection::ToWidget,
+            items,
+        ));
+    }
+
+    fn close(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::close(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        match message.data() {
+            UiMessageData::TextBox(TextBoxMessage::Text(text))
+                if message.direction() == MessageDirection::FromWidget =>
+            {
+                if message.destination() == self.find {
+                    self.find_value = text.clone();
+                    self.sync_preview(editor_scene, engine);
+                } else if message.destination() == self.replace {
+                    self.replace_value = text.clone();
+                    self.sync_preview(editor_scene, engine);
+                }
+            }
+            &UiMessageData::CheckBox(CheckBoxMessage::Check(Some(value))) => {
+                if message.destination() == self.match_case {
+                    self.match_case_value = value;
+                    self.sync_preview(editor_scene, engine);
+                }
+            }
+            UiMessageData::Button(ButtonMessage::Click) => {
+                if message.destination() == self.apply {
+                    let renames = self.collect_renames(
+                        &engine.scenes[editor_scene.scene].graph,
+                        editor_scene.root,
+                    );
+
+                    let duplicates = renames.iter().filter(|r| r.duplicate).count();
+                    if duplicates > 0 {
+                        sender
+                            .send(Message::Log(format!(
+                                "Warning: {} renamed node(s) have non-unique names.",
+                                duplicates
+                            )))
+                            .unwrap();
+                    }
+
+                    if !renames.is_empty() {
+                        let commands = renames
+                            .into_iter()
+                            .map(|r| SceneCommand::new(SetNameCommand::new(r.node, r.new_name)))
+                            .collect::<Vec<_>>();
+                        sender
+                            .send(Message::do_scene_command(CommandGroup::from(commands)))
+                            .unwrap();
+                    }
+
+                    self.close(&engine.user_interface);
+                } else if message.destination() == self.cancel {
+                    self.close(&engine.user_interface);
+                }
+            }
+            _ => (),
+        }
+    }
+}
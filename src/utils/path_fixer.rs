@@ -21,7 +21,7 @@ use rg3d::{
         brush::Brush,
         button::ButtonBuilder,
         decorator::DecoratorBuilder,
-        file_browser::FileSelectorBuilder,
+        file_browser::{FileSelectorBuilder, Filter},
         formatted_text::WrapMode,
         grid::{Column, GridBuilder, Row},
         list_view::ListViewBuilder,
@@ -40,7 +40,8 @@ use rg3d::{
 };
 use std::path::Path;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    ffi::OsString,
     hash::{Hash, Hasher},
     path::PathBuf,
 };
@@ -61,6 +62,13 @@ pub struct PathFixer {
     resource_path: Handle<UiNode>,
     new_path_selector: Handle<UiNode>,
     auto_fix: Handle<UiNode>,
+    search_folder: Handle<UiNode>,
+    search_folder_selector: Handle<UiNode>,
+    // Possible new paths for each orphaned resource, best matches go first.
+    candidates: Vec<Vec<PathBuf>>,
+    candidates_list: Handle<UiNode>,
+    candidate_selection: Option<usize>,
+    use_candidate: Handle<UiNode>,
 }
 
 #[derive(Clone)]
@@ -120,6 +128,47 @@ fn find_file(name: &Path) -> Vec<PathBuf> {
     files
 }
 
+/// Maps file names to every file with such name in the given directory tree.
+fn index_files(root: &Path) -> HashMap<OsString, Vec<PathBuf>> {
+    let mut files = HashMap::<OsString, Vec<PathBuf>>::new();
+    for entry in rg3d::walkdir::WalkDir::new(root).into_iter().flatten() {
+        if entry.file_type().is_file() {
+            files
+                .entry(entry.file_name().to_owned())
+                .or_default()
+                .push(entry.path().to_owned());
+        }
+    }
+    files
+}
+
+/// Amount of trailing path components (including file name) that match. Candidate that
+/// keeps more of the old path is more likely to be the moved resource.
+fn match_score(old_path: &Path, candidate: &Path) -> usize {
+    old_path
+        .components()
+        .rev()
+        .zip(candidate.components().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+fn make_list_item(ctx: &mut BuildContext, text: String, color: Color) -> Handle<UiNode> {
+    DecoratorBuilder::new(BorderBuilder::new(
+        WidgetBuilder::new().with_height(22.0).with_child(
+            TextBuilder::new(
+                WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(1.0))
+                    .with_foreground(Brush::Solid(color)),
+            )
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .with_text(text)
+            .build(ctx),
+        ),
+    ))
+    .build(ctx)
+}
+
 impl PathFixer {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let scene_selector = FileSelectorBuilder::new(
@@ -139,6 +188,16 @@ impl PathFixer {
         )
         .build(ctx);
 
+        let search_folder_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::Text(
+                    "Select a folder to search resources in".into(),
+                )),
+        )
+        .with_filter(Filter::new(|p: &Path| p.is_dir()))
+        .build(ctx);
+
         let load_scene;
         let scene_path;
         let resources_list;
@@ -147,7 +206,10 @@ impl PathFixer {
         let auto_fix;
         let fix;
         let resource_path;
-        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
+        let search_folder;
+        let candidates_list;
+        let use_candidate;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(520.0).with_height(500.0))
             .with_title(WindowTitle::text("Path Fixer"))
             .open(false)
             .with_content(
@@ -196,11 +258,40 @@ impl PathFixer {
                                 ListViewBuilder::new(WidgetBuilder::new().on_row(2)).build(ctx);
                             resources_list
                         })
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .with_child({
+                                        candidates_list =
+                                            ListViewBuilder::new(WidgetBuilder::new().on_column(0))
+                                                .build(ctx);
+                                        candidates_list
+                                    })
+                                    .with_child({
+                                        use_candidate = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .on_column(1)
+                                                .with_enabled(false)
+                                                .with_vertical_alignment(VerticalAlignment::Top)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Use Candidate")
+                                        .build(ctx);
+                                        use_candidate
+                                    }),
+                            )
+                            .add_column(Column::stretch())
+                            .add_column(Column::auto())
+                            .add_row(Row::stretch())
+                            .build(ctx),
+                        )
                         .with_child(
                             StackPanelBuilder::new(
                                 WidgetBuilder::new()
                                     .with_horizontal_alignment(HorizontalAlignment::Right)
-                                    .on_row(3)
+                                    .on_row(4)
                                     .with_child({
                                         load_scene = ButtonBuilder::new(
                                             WidgetBuilder::new()
@@ -211,6 +302,16 @@ impl PathFixer {
                                         .build(ctx);
                                         load_scene
                                     })
+                                    .with_child({
+                                        search_folder = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Search Folder...")
+                                        .build(ctx);
+                                        search_folder
+                                    })
                                     .with_child({
                                         auto_fix = ButtonBuilder::new(
                                             WidgetBuilder::new()
@@ -249,6 +350,7 @@ impl PathFixer {
                 .add_row(Row::auto())
                 .add_row(Row::strict(28.0))
                 .add_row(Row::stretch())
+                .add_row(Row::strict(100.0))
                 .add_row(Row::strict(28.0))
                 .add_column(Column::stretch())
                 .build(ctx),
@@ -271,20 +373,90 @@ impl PathFixer {
             new_path_selector,
             auto_fix,
             scene_path_value: Default::default(),
+            search_folder,
+            search_folder_selector,
+            candidates: Default::default(),
+            candidates_list,
+            candidate_selection: None,
+            use_candidate,
         }
     }
 
-    fn fix_path(&mut self, index: usize, new_path: PathBuf, ui: &UserInterface) {
-        let text = new_path.to_string_lossy().to_string();
-
-        self.orphaned_scene_resources[index].set_path(new_path);
-
+    fn resource_item_text(&self, index: usize, ui: &UserInterface) -> Handle<UiNode> {
         let item = ui
             .node(self.resources_list)
             .cast::<ListView>()
             .unwrap()
             .items()[index];
-        let item_text = ui.find_by_criteria_down(item, &|n| n.cast::<Text>().is_some());
+        ui.find_by_criteria_down(item, &|n| n.cast::<Text>().is_some())
+    }
+
+    /// Looks for files with the same names as orphaned resources in the given folder and
+    /// highlights resources that have at least one candidate.
+    fn search_candidates(&mut self, root: &Path, ui: &mut UserInterface) {
+        let files = index_files(root);
+
+        self.candidates = self
+            .orphaned_scene_resources
+            .iter()
+            .map(|resource| {
+                let old_path = resource.path();
+                let mut candidates = old_path
+                    .file_name()
+                    .and_then(|name| files.get(name))
+                    .cloned()
+                    .unwrap_or_default();
+                candidates.sort_by_key(|c| std::cmp::Reverse(match_score(&old_path, c)));
+                candidates
+            })
+            .collect();
+
+        for (i, candidates) in self.candidates.iter().enumerate() {
+            if !candidates.is_empty() && !self.orphaned_scene_resources[i].path().exists() {
+                ui.send_message(WidgetMessage::foreground(
+                    self.resource_item_text(i, ui),
+                    MessageDirection::ToWidget,
+                    Brush::Solid(Color::opaque(255, 200, 0)),
+                ));
+            }
+        }
+
+        self.sync_candidates(ui);
+    }
+
+    /// Shows candidates of selected resource. The only candidate is selected right away,
+    /// so it needs just a confirmation, ambiguous matches are left for the user to choose.
+    fn sync_candidates(&mut self, ui: &mut UserInterface) {
+        let candidates = self
+            .selection
+            .and_then(|i| self.candidates.get(i))
+            .cloned()
+            .unwrap_or_default();
+
+        let ctx = &mut ui.build_ctx();
+        let items = candidates
+            .iter()
+            .map(|c| make_list_item(ctx, c.to_string_lossy().to_string(), Color::WHITE))
+            .collect::<Vec<_>>();
+
+        ui.send_message(ListViewMessage::items(
+            self.candidates_list,
+            MessageDirection::ToWidget,
+            items,
+        ));
+        ui.send_message(ListViewMessage::selection(
+            self.candidates_list,
+            MessageDirection::ToWidget,
+            if candidates.len() == 1 { Some(0) } else { None },
+        ));
+    }
+
+    fn fix_path(&mut self, index: usize, new_path: PathBuf, ui: &UserInterface) {
+        let text = new_path.to_string_lossy().to_string();
+
+        self.orphaned_scene_resources[index].set_path(new_path);
+
+        let item_text = self.resource_item_text(index, ui);
 
         assert!(item_text.is_some());
 
@@ -438,26 +610,18 @@ impl PathFixer {
                                     .filter(|r| !r.path().exists())
                                     .collect::<Vec<_>>();
 
+                                self.candidates.clear();
+
                                 let ctx = &mut ui.build_ctx();
                                 let items = self
                                     .orphaned_scene_resources
                                     .iter()
                                     .map(|r| {
-                                        DecoratorBuilder::new(BorderBuilder::new(
-                                            WidgetBuilder::new().with_height(22.0).with_child(
-                                                TextBuilder::new(
-                                                    WidgetBuilder::new()
-                                                        .with_margin(Thickness::uniform(1.0))
-                                                        .with_foreground(Brush::Solid(Color::RED)),
-                                                )
-                                                .with_vertical_text_alignment(
-                                                    VerticalAlignment::Center,
-                                                )
-                                                .with_text(r.path().to_string_lossy().to_string())
-                                                .build(ctx),
-                                            ),
-                                        ))
-                                        .build(ctx)
+                                        make_list_item(
+                                            ctx,
+                                            r.path().to_string_lossy().to_string(),
+                                            Color::RED,
+                                        )
                                     })
                                     .collect::<Vec<_>>();
 
@@ -493,6 +657,8 @@ impl PathFixer {
                     if let Some(selection) = self.selection {
                         self.fix_path(selection, replace_slashes(path), ui);
                     }
+                } else if message.destination() == self.search_folder_selector {
+                    self.search_candidates(path, ui);
                 }
             }
             UiMessageData::Button(ButtonMessage::Click) => {
@@ -502,6 +668,19 @@ impl PathFixer {
                         MessageDirection::ToWidget,
                         true,
                     ));
+                } else if message.destination() == self.search_folder {
+                    ui.send_message(WindowMessage::open_modal(
+                        self.search_folder_selector,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
+                } else if message.destination() == self.use_candidate {
+                    if let (Some(selection), Some(candidate_selection)) =
+                        (self.selection, self.candidate_selection)
+                    {
+                        let new_path = self.candidates[selection][candidate_selection].clone();
+                        self.fix_path(selection, replace_slashes(new_path), ui);
+                    }
                 } else if message.destination() == self.cancel {
                     ui.send_message(WindowMessage::close(
                         self.window,
@@ -543,6 +722,8 @@ impl PathFixer {
                         MessageDirection::ToWidget,
                         false,
                     ));
+                    self.candidates.clear();
+                    self.sync_candidates(ui);
                 } else if message.destination() == self.fix {
                     if let Some(selection) = self.selection {
                         // Try to find a resource by its file name.
@@ -615,6 +796,16 @@ impl PathFixer {
                         MessageDirection::ToWidget,
                         selection.is_some(),
                     ));
+
+                    self.sync_candidates(ui);
+                } else if message.destination() == self.candidates_list {
+                    self.candidate_selection = *selection;
+
+                    ui.send_message(WidgetMessage::enabled(
+                        self.use_candidate,
+                        MessageDirection::ToWidget,
+                        selection.is_some(),
+                    ));
                 }
             }
             _ => {}
@@ -633,6 +824,11 @@ impl PathFixer {
                 MessageDirection::ToWidget,
                 Some(working_directory.to_owned()),
             ));
+            ui.send_message(FileSelectorMessage::root(
+                self.search_folder_selector,
+                MessageDirection::ToWidget,
+                Some(working_directory.to_owned()),
+            ));
         }
     }
 }
@@ -0,0 +1,238 @@
+//! Resampling of terrains to other size and height map resolution. Terrain can't change its
+//! resolution in place, so a new terrain is built and heights and layer masks of the old one
+//! are sampled into it. Terrain is stretched to the new size, not cropped.
+
+use rg3d::{
+    resource::texture::TextureKind,
+    scene::{
+        base::BaseBuilder,
+        node::Node,
+        terrain::{LayerDefinition, Terrain, TerrainBuilder},
+    },
+};
+use std::sync::{Arc, Mutex};
+
+/// Physical size and height map resolution of a terrain.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TerrainSize {
+    pub width: f32,
+    pub length: f32,
+    /// Amount of height map samples per unit of length.
+    pub resolution: f32,
+}
+
+impl TerrainSize {
+    pub fn of(terrain: &Terrain) -> Self {
+        let (columns, rows) = chunk_grid(terrain);
+        match terrain.chunks_ref().first() {
+            Some(chunk) => Self {
+                width: chunk.width() * columns as f32,
+                length: chunk.length() * rows as f32,
+                resolution: chunk.width_point_count() as f32 / chunk.width(),
+            },
+            None => Self {
+                width: 0.0,
+                length: 0.0,
+                resolution: 0.0,
+            },
+        }
+    }
+
+    /// Returns true if a terrain of this size has less height map samples along any axis
+    /// than a terrain of other size, so fine details would be lost when resampled.
+    pub fn is_coarser_than(&self, other: &TerrainSize) -> bool {
+        self.width * self.resolution < other.width * other.resolution
+            || self.length * self.resolution < other.length * other.resolution
+    }
+}
+
+/// Returns amount of chunks along X and Z axes.
+fn chunk_grid(terrain: &Terrain) -> (u32, u32) {
+    terrain
+        .chunks_ref()
+        .iter()
+        .fold((0, 0), |(columns, rows), chunk| {
+            let column = (chunk.position().x / chunk.width()).round().max(0.0) as u32;
+            let row = (chunk.position().z / chunk.length()).round().max(0.0) as u32;
+            (columns.max(column + 1), rows.max(row + 1))
+        })
+}
+
+/// Returns index of the chunk that contains given point (in local coordinates of the terrain)
+/// and position of the point relative to the chunk in [0; 1] range. Points outside of the
+/// terrain are moved to the closest chunk.
+fn chunk_at(terrain: &Terrain, x: f32, z: f32) -> (usize, f32, f32) {
+    let mut result = (0, 0.0, 0.0);
+    let mut min_distance = f32::MAX;
+    for (index, chunk) in terrain.chunks_ref().iter().enumerate() {
+        let origin = chunk.position();
+        let rx = ((x - origin.x) / chunk.width()).clamp(0.0, 1.0);
+        let rz = ((z - origin.z) / chunk.length()).clamp(0.0, 1.0);
+        // Distance is zero if the chunk contains the point.
+        let dx = x - (origin.x + rx * chunk.width());
+        let dz = z - (origin.z + rz * chunk.length());
+        let distance = dx * dx + dz * dz;
+        if distance < min_distance {
+            min_distance = distance;
+            result = (index, rx, rz);
+        }
+    }
+    result
+}
+
+/// Bilinearly samples grid of values at fractional coordinates.
+fn sample_grid(values: &[f32], width: usize, height: usize, x: f32, y: f32) -> f32 {
+    let x = x.clamp(0.0, (width - 1) as f32);
+    let y = y.clamp(0.0, (height - 1) as f32);
+    let x0 = x.floor() as usize;
+    let y0 = y.floor() as usize;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+    let tx = x - x0 as f32;
+    let ty = y - y0 as f32;
+
+    let value = |x, y| values[y * width + x];
+    let top = value(x0, y0) + (value(x1, y0) - value(x0, y0)) * tx;
+    let bottom = value(x0, y1) + (value(x1, y1) - value(x0, y1)) * tx;
+    top + (bottom - top) * ty
+}
+
+/// Returns height of the terrain at given point in local coordinates of the terrain, or
+/// `None` if the point is outside of the terrain.
+pub fn height_at(terrain: &Terrain, x: f32, z: f32) -> Option<f32> {
+    let size = TerrainSize::of(terrain);
+    if x < 0.0 || z < 0.0 || x > size.width || z > size.length {
+        return None;
+    }
+
+    let (index, rx, rz) = chunk_at(terrain, x, z);
+    let chunk = &terrain.chunks_ref()[index];
+    let width = chunk.width_point_count() as usize;
+    let length = chunk.length_point_count() as usize;
+    Some(sample_grid(
+        chunk.heightmap(),
+        width,
+        length,
+        rx * (width - 1) as f32,
+        rz * (length - 1) as f32,
+    ))
+}
+
+/// Builds a copy of the terrain with given size and resolution, heights and layer masks are
+/// resampled from the terrain. Amount of chunks stays the same. Only terrain data of the
+/// result is meaningful, its base (name, transform, etc.) is a default one.
+pub fn resample_terrain(terrain: &Terrain, size: TerrainSize) -> Terrain {
+    let (columns, rows) = chunk_grid(terrain);
+    let old_size = TerrainSize::of(terrain);
+
+    let layers = terrain
+        .layers()
+        .iter()
+        .map(|layer| LayerDefinition {
+            // Copy of the material, so the old terrain stays untouched and can be restored.
+            material: Arc::new(Mutex::new(layer.material.lock().unwrap().clone())),
+            mask_property_name: layer.mask_property_name.clone(),
+        })
+        .collect();
+
+    let mut resampled = match TerrainBuilder::new(BaseBuilder::new())
+        .with_width(size.width)
+        .with_length(size.length)
+        .with_width_chunks(columns)
+        .with_length_chunks(rows)
+        .with_height_map_resolution(size.resolution)
+        .with_layers(layers)
+        .build_node()
+    {
+        Node::Terrain(terrain) => terrain,
+        _ => unreachable!(),
+    };
+
+    // Maps a point of the new terrain to the point of the old one.
+    let scale_x = old_size.width / size.width.max(f32::EPSILON);
+    let scale_z = old_size.length / size.length.max(f32::EPSILON);
+
+    for chunk in resampled.chunks_mut() {
+        let origin = chunk.position();
+        let width_points = chunk.width_point_count() as usize;
+        let length_points = chunk.length_point_count() as usize;
+
+        let mut heightmap = Vec::with_capacity(width_points * length_points);
+        for z in 0..length_points {
+            let kz = z as f32 / (length_points - 1) as f32;
+            for x in 0..width_points {
+                let kx = x as f32 / (width_points - 1) as f32;
+                let (index, rx, rz) = chunk_at(
+                    terrain,
+                    (origin.x + kx * chunk.width()) * scale_x,
+                    (origin.z + kz * chunk.length()) * scale_z,
+                );
+                let source = &terrain.chunks_ref()[index];
+                let source_width = source.width_point_count() as usize;
+                let source_length = source.length_point_count() as usize;
+                heightmap.push(sample_grid(
+                    source.heightmap(),
+                    source_width,
+                    source_length,
+                    rx * (source_width - 1) as f32,
+                    rz * (source_length - 1) as f32,
+                ));
+            }
+        }
+        chunk.set_heightmap(heightmap);
+    }
+
+    for (layer, old_layer) in resampled.layers().iter().zip(terrain.layers()) {
+        // Copy old masks once, instead of locking their textures for every pixel.
+        let old_masks = old_layer
+            .chunk_masks()
+            .iter()
+            .map(|mask| {
+                let data = mask.data_ref();
+                let (width, height) = match data.kind() {
+                    TextureKind::Rectangle { width, height } => (width as usize, height as usize),
+                    _ => (0, 0),
+                };
+                let values = data.data().iter().map(|&v| v as f32).collect::<Vec<_>>();
+                (width, height, values)
+            })
+            .collect::<Vec<_>>();
+
+        for (chunk, mask) in resampled.chunks_ref().iter().zip(layer.chunk_masks()) {
+            let origin = chunk.position();
+
+            let mut data = mask.data_ref();
+            let (mask_width, mask_height) = match data.kind() {
+                TextureKind::Rectangle { width, height } => (width as usize, height as usize),
+                _ => continue,
+            };
+
+            let pixels = data.modify().data_mut();
+            for z in 0..mask_height {
+                let kz = z as f32 / mask_height as f32;
+                for x in 0..mask_width {
+                    let kx = x as f32 / mask_width as f32;
+                    let (index, rx, rz) = chunk_at(
+                        terrain,
+                        (origin.x + kx * chunk.width()) * scale_x,
+                        (origin.z + kz * chunk.length()) * scale_z,
+                    );
+                    if let Some((width, height, values)) =
+                        old_masks.get(index).filter(|(w, h, _)| *w > 0 && *h > 0)
+                    {
+                        let value = sample_grid(
+                            values,
+                            *width,
+                            *height,
+                            rx * *width as f32,
+                            rz * *height as f32,
+                        );
+                        pixels[z * mask_width + x] = value.round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    resampled
+}
@@ -0,0 +1,348 @@
+//! Applies random offset, rotation and uniform scale to every selected node. Useful to
+//! make scattered objects (foliage, rocks, etc.) look more natural.
+
+use crate::{
+    gui::{make_button, make_f32_field},
+    scene::{
+        commands::{
+            graph::{MoveNodeCommand, RotateNodeCommand, ScaleNodeCommand},
+            CommandGroup, SceneCommand,
+        },
+        EditorScene, Selection,
+    },
+    settings::make_text_mark,
+    GameEngine, Message,
+};
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        message::{
+            ButtonMessage, MessageDirection, TextMessage, UiMessage, UiMessageData, WindowMessage,
+        },
+        numeric::NumericUpDownMessage,
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        vec::vec3::{Vec3EditorBuilder, Vec3EditorMessage},
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+    },
+};
+use std::sync::mpsc::Sender;
+
+/// Small xorshift generator, it is enough for scattering and gives the same sequence
+/// for the same seed on every platform.
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u32) -> Self {
+        Self {
+            // State must never be zero.
+            state: (seed as u64) ^ 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn symmetric(&mut self, amplitude: Vector3<f32>) -> Vector3<f32> {
+        Vector3::new(
+            self.range(-amplitude.x, amplitude.x),
+            self.range(-amplitude.y, amplitude.y),
+            self.range(-amplitude.z, amplitude.z),
+        )
+    }
+}
+
+pub struct TransformRandomizer {
+    pub window: Handle<UiNode>,
+    position: Handle<UiNode>,
+    rotation: Handle<UiNode>,
+    min_scale: Handle<UiNode>,
+    max_scale: Handle<UiNode>,
+    seed: Handle<UiNode>,
+    summary: Handle<UiNode>,
+    apply: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+    position_value: Vector3<f32>,
+    // In degrees.
+    rotation_value: Vector3<f32>,
+    min_scale_value: f32,
+    max_scale_value: f32,
+    seed_value: u32,
+}
+
+fn make_vec3_field(ctx: &mut BuildContext, row: usize, value: Vector3<f32>) -> Handle<UiNode> {
+    Vec3EditorBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_value(value)
+    .build(ctx)
+}
+
+fn make_summary(position: Vector3<f32>, rotation: Vector3<f32>, min: f32, max: f32) -> String {
+    format!(
+        "Offset: ±({:.2}, {:.2}, {:.2})\nRotation: ±({:.1}, {:.1}, {:.1}) deg\nScale: [{:.3}; {:.3}]",
+        position.x,
+        position.y,
+        position.z,
+        rotation.x,
+        rotation.y,
+        rotation.z,
+        min.min(max),
+        min.max(max),
+    )
+}
+
+impl TransformRandomizer {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let position_value = Vector3::new(0.0, 0.0, 0.0);
+        let rotation_value = Vector3::new(0.0, 180.0, 0.0);
+        let min_scale_value = 0.8;
+        let max_scale_value = 1.2;
+        let seed_value = 0;
+
+        let position;
+        let rotation;
+        let min_scale;
+        let max_scale;
+        let seed;
+        let summary;
+        let apply;
+        let cancel;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(300.0))
+            .with_title(WindowTitle::text("Randomize Transforms"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(make_text_mark(ctx, "Position Jitter", 0))
+                        .with_child({
+                            position = make_vec3_field(ctx, 0, position_value);
+                            position
+                        })
+                        .with_child(make_text_mark(ctx, "Rotation (deg)", 1))
+                        .with_child({
+                            rotation = make_vec3_field(ctx, 1, rotation_value);
+                            rotation
+                        })
+                        .with_child(make_text_mark(ctx, "Min Scale", 2))
+                        .with_child({
+                            min_scale = make_f32_field(ctx, 2, min_scale_value, 0.0, 3);
+                            min_scale
+                        })
+                        .with_child(make_text_mark(ctx, "Max Scale", 3))
+                        .with_child({
+                            max_scale = make_f32_field(ctx, 3, max_scale_value, 0.0, 3);
+                            max_scale
+                        })
+                        .with_child(make_text_mark(ctx, "Seed", 4))
+                        .with_child({
+                            seed = make_f32_field(ctx, 4, seed_value as f32, 0.0, 0);
+                            seed
+                        })
+                        .with_child({
+                            summary = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(5)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_text(make_summary(
+                                position_value,
+                                rotation_value,
+                                min_scale_value,
+                                max_scale_value,
+                            ))
+                            .build(ctx);
+                            summary
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .on_row(6)
+                                    .on_column(1)
+                                    .with_child({
+                                        apply = make_button(ctx, "Apply");
+                                        apply
+                                    })
+                                    .with_child({
+                                        cancel = make_button(ctx, "Cancel");
+                                        cancel
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::strict(26.0))
+                .add_row(Row::stretch())
+                .add_row(Row::strict(28.0))
+                .add_column(Column::strict(110.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            position,
+            rotation,
+            min_scale,
+            max_scale,
+            seed,
+            summary,
+            apply,
+            cancel,
+            position_value,
+            rotation_value,
+            min_scale_value,
+            max_scale_value,
+            seed_value,
+        }
+    }
+
+    fn sync_summary(&self, ui: &UserInterface) {
+        ui.send_message(TextMessage::text(
+            self.summary,
+            MessageDirection::ToWidget,
+            make_summary(
+                self.position_value,
+                self.rotation_value,
+                self.min_scale_value,
+                self.max_scale_value,
+            ),
+        ));
+    }
+
+    fn make_command(
+        &self,
+        editor_scene: &EditorScene,
+        engine: &GameEngine,
+    ) -> Option<SceneCommand> {
+        if let Selection::Graph(selection) = &editor_scene.selection {
+            let graph = &engine.scenes[editor_scene.scene].graph;
+            let mut random = Random::new(self.seed_value);
+            let min_scale = self.min_scale_value.min(self.max_scale_value);
+            let max_scale = self.min_scale_value.max(self.max_scale_value);
+
+            let mut commands = Vec::new();
+            for &handle in selection.nodes() {
+                let transform = graph[handle].local_transform();
+
+                let old_position = **transform.position();
+                let new_position = old_position + random.symmetric(self.position_value);
+                commands.push(SceneCommand::new(MoveNodeCommand::new(
+                    handle,
+                    old_position,
+                    new_position,
+                )));
+
+                let angles = random.symmetric(self.rotation_value);
+                let old_rotation = **transform.rotation();
+                let new_rotation = old_rotation
+                    * UnitQuaternion::from_euler_angles(
+                        angles.x.to_radians(),
+                        angles.y.to_radians(),
+                        angles.z.to_radians(),
+                    );
+                commands.push(SceneCommand::new(RotateNodeCommand::new(
+                    handle,
+                    old_rotation,
+                    new_rotation,
+                )));
+
+                let old_scale = **transform.scale();
+                let new_scale = old_scale.scale(random.range(min_scale, max_scale));
+                commands.push(SceneCommand::new(ScaleNodeCommand::new(
+                    handle, old_scale, new_scale,
+                )));
+            }
+
+            if !commands.is_empty() {
+                return Some(SceneCommand::new(CommandGroup::from(commands)));
+            }
+        }
+        None
+    }
+
+    fn close(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::close(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_scene: &EditorScene,
+        engine: &GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        match message.data() {
+            UiMessageData::User(msg) if message.direction() == MessageDirection::FromWidget => {
+                let destination = message.destination();
+                if let Some(&NumericUpDownMessage::Value(value)) =
+                    msg.cast::<NumericUpDownMessage<f32>>()
+                {
+                    if destination == self.min_scale {
+                        self.min_scale_value = value;
+                    } else if destination == self.max_scale {
+                        self.max_scale_value = value;
+                    } else if destination == self.seed {
+                        self.seed_value = value as u32;
+                    } else {
+                        return;
+                    }
+                } else if let Some(&Vec3EditorMessage::Value(value)) =
+                    msg.cast::<Vec3EditorMessage<f32>>()
+                {
+                    if destination == self.position {
+                        self.position_value = value;
+                    } else if destination == self.rotation {
+                        self.rotation_value = value;
+                    } else {
+                        return;
+                    }
+                } else {
+                    return;
+                }
+                self.sync_summary(&engine.user_interface);
+            }
+            UiMessageData::Button(ButtonMessage::Click) => {
+                if message.destination() == self.apply {
+                    if let Some(command) = self.make_command(editor_scene, engine) {
+                        sender.send(Message::DoSceneCommand(command)).unwrap();
+                    }
+                    self.close(&engine.user_interface);
+                } else if message.destination() == self.cancel {
+                    self.close(&engine.user_interface);
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use rg3d::{
-    core::{algebra::Vector2, pool::Handle},
+    core::{algebra::Vector2, color::Color, pool::Handle},
     gui::{
         brush::Brush,
         draw::{DrawingContext, SharedTexture},
         grid::{Column, GridBuilder, Row},
         image::ImageBuilder,
-        message::{MessageDirection, OsEvent, TextMessage, UiMessage, UiMessageData},
+        message::{
+            MessageDirection, OsEvent, TextMessage, UiMessage, UiMessageData, WidgetMessage,
+        },
         text::TextBuilder,
         tree::{Tree, TreeBuilder},
         widget::Widget,
@@ -23,6 +25,8 @@ use std::{
 #[derive(Debug, Clone, PartialEq)]
 pub enum SceneItemMessage {
     Name(String),
+    /// Disabled items are drawn dimmed.
+    Enabled(bool),
 }
 
 impl SceneItemMessage {
@@ -33,12 +37,24 @@ impl SceneItemMessage {
             Box::new(SceneItemMessage::Name(name)),
         )
     }
+
+    pub fn enabled(destination: Handle<UiNode>, enabled: bool) -> UiMessage {
+        UiMessage::user(
+            destination,
+            MessageDirection::ToWidget,
+            Box::new(SceneItemMessage::Enabled(enabled)),
+        )
+    }
 }
 
+const DISABLED_TEXT_COLOR: Color = Color::opaque(100, 100, 100);
+
 pub struct SceneItem<T> {
     pub tree: Tree,
     text_name: Handle<UiNode>,
     name_value: String,
+    text_brush: Brush,
+    enabled: bool,
     pub entity_handle: Handle<T>,
 }
 
@@ -54,6 +70,8 @@ impl<T> Clone for SceneItem<T> {
             tree: self.tree.clone(),
             text_name: self.text_name,
             name_value: self.name_value.clone(),
+            text_brush: self.text_brush.clone(),
+            enabled: self.enabled,
             entity_handle: self.entity_handle,
         }
     }
@@ -106,20 +124,37 @@ impl<T: 'static> Control for SceneItem<T> {
 
         if let UiMessageData::User(msg) = message.data() {
             if let Some(msg) = msg.cast::<SceneItemMessage>() {
-                if let SceneItemMessage::Name(name) = msg {
-                    if message.destination() == self.handle() {
-                        self.name_value = format!(
-                            "{} ({}:{})",
-                            name,
-                            self.entity_handle.index(),
-                            self.entity_handle.generation()
-                        );
-
-                        ui.send_message(TextMessage::text(
-                            self.text_name,
-                            MessageDirection::ToWidget,
-                            self.name_value.clone(),
-                        ));
+                if message.destination() == self.handle() {
+                    match msg {
+                        SceneItemMessage::Name(name) => {
+                            self.name_value = format!(
+                                "{} ({}:{})",
+                                name,
+                                self.entity_handle.index(),
+                                self.entity_handle.generation()
+                            );
+
+                            ui.send_message(TextMessage::text(
+                                self.text_name,
+                                MessageDirection::ToWidget,
+                                self.name_value.clone(),
+                            ));
+                        }
+                        &SceneItemMessage::Enabled(enabled) => {
+                            if self.enabled != enabled {
+                                self.enabled = enabled;
+
+                                ui.send_message(WidgetMessage::foreground(
+                                    self.text_name,
+                                    MessageDirection::ToWidget,
+                                    if enabled {
+                                        self.text_brush.clone()
+                                    } else {
+                                        Brush::Solid(DISABLED_TEXT_COLOR)
+                                    },
+                                ));
+                            }
+                        }
                     }
                 }
             }
@@ -184,6 +219,10 @@ impl<T: 'static> SceneItemBuilder<T> {
     }
 
     pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let text_brush = self
+            .text_brush
+            .unwrap_or(Brush::Solid(rg3d::gui::COLOR_FOREGROUND));
+
         let text_name;
         let content = GridBuilder::new(
             WidgetBuilder::new()
@@ -201,10 +240,7 @@ impl<T: 'static> SceneItemBuilder<T> {
                 .with_child({
                     text_name = TextBuilder::new(
                         WidgetBuilder::new()
-                            .with_foreground(
-                                self.text_brush
-                                    .unwrap_or(Brush::Solid(rg3d::gui::COLOR_FOREGROUND)),
-                            )
+                            .with_foreground(text_brush.clone())
                             .with_margin(Thickness::uniform(1.0))
                             .on_column(1)
                             .with_vertical_alignment(VerticalAlignment::Center),
@@ -230,6 +266,8 @@ impl<T: 'static> SceneItemBuilder<T> {
             tree,
             entity_handle: self.entity_handle,
             name_value: self.name,
+            text_brush,
+            enabled: true,
             text_name,
         };
 
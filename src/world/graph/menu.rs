@@ -1,6 +1,12 @@
 use crate::scene::commands::physics::SetBodyCommand;
 use crate::{
-    scene::{commands::make_delete_selection_command, EditorScene, Selection},
+    scene::{
+        commands::{
+            graph::{is_node_enabled, make_set_enabled_command},
+            make_delete_selection_command,
+        },
+        EditorScene, Selection,
+    },
     GameEngine, Message,
 };
 use rg3d::gui::message::{MessageDirection, PopupMessage, WidgetMessage};
@@ -14,14 +20,18 @@ use rg3d::{
         widget::WidgetBuilder,
         BuildContext, UiNode,
     },
+    material::Material,
+    scene::node::Node,
 };
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc, Mutex};
 
 pub struct ItemContextMenu {
     pub menu: Handle<UiNode>,
     delete_selection: Handle<UiNode>,
     copy_selection: Handle<UiNode>,
     add_rigid_body: Handle<UiNode>,
+    toggle_enabled: Handle<UiNode>,
+    edit_materials: Handle<UiNode>,
 }
 
 impl ItemContextMenu {
@@ -29,6 +39,8 @@ impl ItemContextMenu {
         let delete_selection;
         let copy_selection;
         let add_rigid_body;
+        let toggle_enabled;
+        let edit_materials;
 
         let menu = PopupBuilder::new(WidgetBuilder::new().with_visibility(false))
             .with_content(
@@ -65,6 +77,22 @@ impl ItemContextMenu {
                             .with_content(MenuItemContent::text("Add Rigid Body"))
                             .build(ctx);
                             add_rigid_body
+                        })
+                        .with_child({
+                            toggle_enabled = MenuItemBuilder::new(
+                                WidgetBuilder::new().with_min_size(Vector2::new(120.0, 20.0)),
+                            )
+                            .with_content(MenuItemContent::text("Toggle Enabled"))
+                            .build(ctx);
+                            toggle_enabled
+                        })
+                        .with_child({
+                            edit_materials = MenuItemBuilder::new(
+                                WidgetBuilder::new().with_min_size(Vector2::new(120.0, 20.0)),
+                            )
+                            .with_content(MenuItemContent::text("Edit Materials"))
+                            .build(ctx);
+                            edit_materials
                         }),
                 )
                 .build(ctx),
@@ -76,6 +104,8 @@ impl ItemContextMenu {
             delete_selection,
             copy_selection,
             add_rigid_body,
+            toggle_enabled,
+            edit_materials,
         }
     }
 
@@ -117,6 +147,42 @@ impl ItemContextMenu {
                             )))
                             .unwrap();
                     }
+                } else if message.destination() == self.toggle_enabled {
+                    if let Selection::Graph(graph_selection) = &editor_scene.selection {
+                        let graph = &engine.scenes[editor_scene.scene].graph;
+                        if let Some(&first) = graph_selection.nodes().first() {
+                            // Whole selection gets state opposite to the first node.
+                            let enabled = !is_node_enabled(&graph[first]);
+                            if let Some(command) =
+                                make_set_enabled_command(graph, graph_selection.nodes(), enabled)
+                            {
+                                sender.send(Message::DoSceneCommand(command)).unwrap();
+                            }
+                        }
+                    }
+                } else if message.destination() == self.edit_materials {
+                    if let Selection::Graph(graph_selection) = &editor_scene.selection {
+                        let graph = &engine.scenes[editor_scene.scene].graph;
+
+                        // Surfaces can share materials, each material is edited once.
+                        let mut materials = Vec::<Arc<Mutex<Material>>>::new();
+                        for &node in graph_selection.nodes() {
+                            if let Node::Mesh(mesh) = &graph[node] {
+                                for surface in mesh.surfaces() {
+                                    if !materials.iter().any(|m| Arc::ptr_eq(m, surface.material()))
+                                    {
+                                        materials.push(surface.material().clone());
+                                    }
+                                }
+                            }
+                        }
+
+                        if !materials.is_empty() {
+                            sender
+                                .send(Message::OpenMaterialEditorForMany(materials))
+                                .unwrap();
+                        }
+                    }
                 }
             }
             UiMessageData::Popup(PopupMessage::Open) => {
@@ -1,7 +1,11 @@
 use crate::utils;
 use rg3d::{
     asset::core::algebra::Vector3,
-    core::{algebra::UnitQuaternion, math::Matrix4Ext, pool::Handle},
+    core::{
+        algebra::{Point3, UnitQuaternion},
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        pool::Handle,
+    },
     scene::{graph::Graph, node::Node},
 };
 
@@ -121,6 +125,29 @@ impl GraphSelection {
         }
     }
 
+    /// Returns world-space bounding box that encloses every selected node. Nodes without
+    /// geometry are treated as unit boxes.
+    pub fn world_bounding_box(&self, graph: &Graph) -> Option<AxisAlignedBoundingBox> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut result = AxisAlignedBoundingBox::default();
+        for &handle in self.nodes.iter() {
+            let node = &graph[handle];
+            let aabb = match node {
+                Node::Mesh(mesh) => mesh.bounding_box(),
+                Node::Terrain(terrain) => terrain.bounding_box(),
+                _ => AxisAlignedBoundingBox::unit(),
+            };
+            let transform = node.global_transform();
+            for corner in aabb.corners().iter() {
+                result.add_point(transform.transform_point(&Point3::from(*corner)).coords);
+            }
+        }
+        Some(result)
+    }
+
     pub fn offset(&self, graph: &mut Graph, offset: Vector3<f32>) {
         for &handle in self.nodes.iter() {
             let mut chain_scale = Vector3::new(1.0, 1.0, 1.0);
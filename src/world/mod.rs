@@ -3,7 +3,7 @@ use crate::{
     physics::{Collider, Joint, RigidBody},
     scene::{
         commands::{
-            graph::LinkNodesCommand,
+            graph::{is_node_enabled, LinkNodesCommand},
             physics::{
                 LinkBodyCommand, SetJointBody1Command, SetJointBody2Command, UnlinkBodyCommand,
             },
@@ -804,6 +804,7 @@ impl WorldViewer {
                 if graph.is_valid_handle(item.entity_handle) {
                     let node = &graph[item.entity_handle];
                     send_sync_message(ui, SceneItemMessage::name(handle, node.name().to_owned()));
+                    send_sync_message(ui, SceneItemMessage::enabled(handle, is_node_enabled(node)));
                     stack.extend_from_slice(item.tree.items());
                 }
             } else if let Some(root) = ui_node.cast::<TreeRoot>() {
@@ -7,11 +7,11 @@ use crate::{
                 AddColliderCommand, DeleteBodyCommand, DeleteColliderCommand, DeleteJointCommand,
                 SetJointBody1Command, SetJointBody2Command,
             },
-            CommandGroup, SceneCommand,
+            ChangeSelectionCommand, CommandGroup, SceneCommand,
         },
-        EditorScene,
+        EditorScene, Selection,
     },
-    world::graph::item::SceneItem,
+    world::{graph::item::SceneItem, physics::selection::RigidBodySelection},
     Message,
 };
 use rg3d::{
@@ -37,6 +37,7 @@ use std::sync::mpsc::Sender;
 pub struct RigidBodyContextMenu {
     pub menu: Handle<UiNode>,
     pub delete: Handle<UiNode>,
+    pub select_same_shape: Handle<UiNode>,
     pub add_ball_collider: Handle<UiNode>,
     pub add_cylinder_collider: Handle<UiNode>,
     pub add_round_cylinder_collider: Handle<UiNode>,
@@ -60,6 +61,7 @@ fn make_menu_item(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
 impl RigidBodyContextMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let delete;
+        let select_same_shape;
         let add_ball_collider;
         let add_cylinder_collider;
         let add_round_cylinder_collider;
@@ -86,6 +88,11 @@ impl RigidBodyContextMenu {
                             .build(ctx);
                             delete
                         })
+                        .with_child({
+                            select_same_shape =
+                                make_menu_item(ctx, "Select Bodies With Same Collider");
+                            select_same_shape
+                        })
                         .with_child({
                             add_ball_collider = make_menu_item(ctx, "Add Ball Collider");
                             add_ball_collider
@@ -136,6 +143,7 @@ impl RigidBodyContextMenu {
         Self {
             menu,
             delete,
+            select_same_shape,
             add_ball_collider,
             add_cylinder_collider,
             add_round_cylinder_collider,
@@ -202,6 +210,23 @@ impl RigidBodyContextMenu {
                             .unwrap();
                     }
 
+                    if message.destination() == self.select_same_shape {
+                        let physics = &editor_scene.physics;
+                        if let Some(&collider) = physics.bodies[rigid_body_handle].colliders.first()
+                        {
+                            let shape = &physics.colliders[collider.into()].shape;
+                            let new_selection = Selection::RigidBody(
+                                RigidBodySelection::all_with_shape(physics, shape),
+                            );
+                            sender
+                                .send(Message::do_scene_command(ChangeSelectionCommand::new(
+                                    new_selection,
+                                    editor_scene.selection.clone(),
+                                )))
+                                .unwrap();
+                        }
+                    }
+
                     // Handle <add x collider> items
                     let shape = if message.destination() == self.add_ball_collider {
                         Some(ColliderShapeDesc::Ball(BallDesc { radius: 0.5 }))
@@ -1,6 +1,24 @@
 use crate::physics::{Collider, Joint, RigidBody};
 use crate::{physics::Physics, utils};
-use rg3d::core::{algebra::Vector3, pool::Handle};
+use rg3d::{
+    core::{
+        algebra::{Isometry3, Point3, Translation3, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+    },
+    physics3d::desc::{ColliderShapeDesc, JointParamsDesc, RigidBodyTypeDesc},
+};
+
+fn has_collider_of_kind(
+    physics: &Physics,
+    body: Handle<RigidBody>,
+    shape: &ColliderShapeDesc,
+) -> bool {
+    physics.bodies[body].colliders.iter().any(|&collider| {
+        std::mem::discriminant(&physics.colliders[collider.into()].shape)
+            == std::mem::discriminant(shape)
+    })
+}
 
 #[derive(Debug, Clone)]
 pub struct RigidBodySelection {
@@ -20,6 +38,19 @@ impl RigidBodySelection {
         self.bodies.first().cloned()
     }
 
+    pub fn contains(&self, handle: Handle<RigidBody>) -> bool {
+        self.bodies.iter().any(|&h| h == handle)
+    }
+
+    /// Adds new selected body, or removes it if it is already selected.
+    pub fn toggle(&mut self, handle: Handle<RigidBody>) {
+        if let Some(position) = self.bodies.iter().position(|&h| h == handle) {
+            self.bodies.remove(position);
+        } else {
+            self.bodies.push(handle);
+        }
+    }
+
     pub fn center(&self, physics: &Physics) -> Option<Vector3<f32>> {
         let mut count = 0;
         let position_sum = self.bodies.iter().fold(Vector3::default(), |acc, handle| {
@@ -32,6 +63,133 @@ impl RigidBodySelection {
             None
         }
     }
+
+    /// Returns new selection with those selected bodies that have a collider of the same
+    /// kind as given shape. Shape parameters (radius, extents, etc.) are ignored.
+    pub fn filter_by_shape(&self, physics: &Physics, shape: &ColliderShapeDesc) -> Self {
+        Self {
+            bodies: self
+                .bodies
+                .iter()
+                .cloned()
+                .filter(|&body| has_collider_of_kind(physics, body, shape))
+                .collect(),
+        }
+    }
+
+    /// Creates selection of every body in the scene that has a collider of the same kind
+    /// as given shape.
+    pub fn all_with_shape(physics: &Physics, shape: &ColliderShapeDesc) -> Self {
+        Self {
+            bodies: physics
+                .bodies
+                .pair_iter()
+                .map(|(handle, _)| handle)
+                .filter(|&body| has_collider_of_kind(physics, body, shape))
+                .collect(),
+        }
+    }
+
+    /// Returns summary mass of selected dynamic bodies. Static and kinematic bodies are
+    /// not affected by forces, so their mass does not count.
+    pub fn total_mass(&self, physics: &Physics) -> f32 {
+        self.bodies
+            .iter()
+            .map(|&handle| &physics.bodies[handle])
+            .filter(|body| {
+                matches!(body.status, RigidBodyTypeDesc::Dynamic) && body.mass.is_finite()
+            })
+            .map(|body| body.mass)
+            .sum()
+    }
+
+    /// Returns world-space bounding box that encloses colliders of every selected body.
+    /// Trimeshes and heightfields take their geometry from scene nodes, so they are
+    /// treated as unit boxes.
+    pub fn bounding_box(&self, physics: &Physics) -> Option<AxisAlignedBoundingBox> {
+        if self.bodies.is_empty() {
+            return None;
+        }
+
+        let mut result = AxisAlignedBoundingBox::default();
+        for &handle in self.bodies.iter() {
+            let body = &physics.bodies[handle];
+            let body_transform = Isometry3 {
+                rotation: body.rotation,
+                translation: Translation3 {
+                    vector: body.position,
+                },
+            };
+
+            if body.colliders.is_empty() {
+                result.add_point(body.position);
+                continue;
+            }
+
+            for &collider in body.colliders.iter() {
+                let collider = &physics.colliders[collider.into()];
+                let aabb = match &collider.shape {
+                    ColliderShapeDesc::Ball(ball) => AxisAlignedBoundingBox::from_min_max(
+                        Vector3::repeat(-ball.radius),
+                        Vector3::repeat(ball.radius),
+                    ),
+                    ColliderShapeDesc::Cylinder(cylinder) => {
+                        let half_extents =
+                            Vector3::new(cylinder.radius, cylinder.half_height, cylinder.radius);
+                        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+                    }
+                    ColliderShapeDesc::RoundCylinder(round_cylinder) => {
+                        let radius = round_cylinder.radius + round_cylinder.border_radius;
+                        let half_extents = Vector3::new(
+                            radius,
+                            round_cylinder.half_height + round_cylinder.border_radius,
+                            radius,
+                        );
+                        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+                    }
+                    ColliderShapeDesc::Cone(cone) => {
+                        let half_extents = Vector3::new(cone.radius, cone.half_height, cone.radius);
+                        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+                    }
+                    ColliderShapeDesc::Cuboid(cuboid) => AxisAlignedBoundingBox::from_min_max(
+                        -cuboid.half_extents,
+                        cuboid.half_extents,
+                    ),
+                    ColliderShapeDesc::Capsule(capsule) => {
+                        let radius = Vector3::repeat(capsule.radius);
+                        AxisAlignedBoundingBox::from_min_max(
+                            capsule.begin.inf(&capsule.end) - radius,
+                            capsule.begin.sup(&capsule.end) + radius,
+                        )
+                    }
+                    ColliderShapeDesc::Segment(segment) => AxisAlignedBoundingBox::from_min_max(
+                        segment.begin.inf(&segment.end),
+                        segment.begin.sup(&segment.end),
+                    ),
+                    ColliderShapeDesc::Triangle(triangle) => AxisAlignedBoundingBox::from_min_max(
+                        triangle.a.inf(&triangle.b).inf(&triangle.c),
+                        triangle.a.sup(&triangle.b).sup(&triangle.c),
+                    ),
+                    ColliderShapeDesc::Trimesh(_) | ColliderShapeDesc::Heightfield(_) => {
+                        AxisAlignedBoundingBox::unit()
+                    }
+                };
+
+                // Collider is always relative to its parent body.
+                let transform = body_transform
+                    * Isometry3 {
+                        rotation: collider.rotation,
+                        translation: Translation3 {
+                            vector: collider.translation,
+                        },
+                    };
+                for corner in aabb.corners().iter() {
+                    result.add_point(transform.transform_point(&Point3::from(*corner)).coords);
+                }
+            }
+        }
+        Some(result)
+    }
 }
 
 impl PartialEq for RigidBodySelection {
@@ -59,6 +217,57 @@ impl JointSelection {
     pub fn first(&self) -> Option<Handle<Joint>> {
         self.joints.first().cloned()
     }
+
+    pub fn contains(&self, handle: Handle<Joint>) -> bool {
+        self.joints.iter().any(|&h| h == handle)
+    }
+
+    /// Adds new selected joint, or removes it if it is already selected.
+    pub fn toggle(&mut self, handle: Handle<Joint>) {
+        if let Some(position) = self.joints.iter().position(|&h| h == handle) {
+            self.joints.remove(position);
+        } else {
+            self.joints.push(handle);
+        }
+    }
+
+    pub fn center(&self, physics: &Physics) -> Option<Vector3<f32>> {
+        let mut count = 0;
+        let position_sum = self.joints.iter().fold(Vector3::default(), |acc, handle| {
+            let joint = &physics.joints[*handle];
+            let (local_anchor1, local_anchor2) = match &joint.params {
+                JointParamsDesc::BallJoint(ball) => (ball.local_anchor1, ball.local_anchor2),
+                JointParamsDesc::FixedJoint(fixed) => (
+                    fixed.local_anchor1_translation,
+                    fixed.local_anchor2_translation,
+                ),
+                JointParamsDesc::PrismaticJoint(prismatic) => {
+                    (prismatic.local_anchor1, prismatic.local_anchor2)
+                }
+                JointParamsDesc::RevoluteJoint(revolute) => {
+                    (revolute.local_anchor1, revolute.local_anchor2)
+                }
+            };
+            // Joint has no position by itself, so take anchor in frame of any attached body.
+            let (body, local_anchor) = if joint.body1.is_some() {
+                (joint.body1, local_anchor1)
+            } else if joint.body2.is_some() {
+                (joint.body2, local_anchor2)
+            } else {
+                return acc;
+            };
+            count += 1;
+            acc + physics.bodies[Handle::<RigidBody>::from(body)]
+                .local_transform()
+                .transform_point(&Point3::from(local_anchor))
+                .coords
+        });
+        if count > 0 {
+            Some(position_sum.scale(1.0 / count as f32))
+        } else {
+            None
+        }
+    }
 }
 
 impl PartialEq for JointSelection {